@@ -1,12 +1,10 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use crossbeam::channel;
 use mini_tokio::Executor;
 use std::sync::mpsc;
 
 fn ping_pong_mini_tokio() {
     let executor = Executor::new();
-    let (tx, rx) = mpsc::channel::<u32>();
-
     executor.block_on(async {
         let (tx1, rx1) = channel::bounded(1);
         let (tx2, rx2) = channel::bounded(1);
@@ -14,7 +12,7 @@ fn ping_pong_mini_tokio() {
         let handle1 = executor.spawn(async move {
             for i in 0..1000 {
                 tx1.send(i).unwrap();
-                let _ = rx2.recv().unwrap();
+                rx2.recv().unwrap();
             }
         });
 
@@ -37,13 +35,13 @@ fn ping_pong_tokio() {
         .unwrap();
 
     rt.block_on(async {
-        let (tx1, mut rx1) = mpsc::channel();
-        let (tx2, mut rx2) = mpsc::channel();
+        let (tx1, rx1) = mpsc::channel();
+        let (tx2, rx2) = mpsc::channel();
 
         let handle1 = tokio::spawn(async move {
             for i in 0..1000 {
                 tx1.send(i).unwrap();
-                let _ = rx2.recv().unwrap();
+                rx2.recv().unwrap();
             }
         });
 
@@ -62,11 +60,11 @@ fn ping_pong_tokio() {
 fn criterion_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("ping-pong");
 
-    group.bench_function("mini_tokio", |b| b.iter(|| ping_pong_mini_tokio()));
-    group.bench_function("tokio", |b| b.iter(|| ping_pong_tokio()));
+    group.bench_function("mini_tokio", |b| b.iter(ping_pong_mini_tokio));
+    group.bench_function("tokio", |b| b.iter(ping_pong_tokio));
 
     group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -0,0 +1,95 @@
+//! Compares the executor's run queue policies on the same workloads.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use mini_tokio::executor::{
+    scheduler::{Edf, Fifo, LifoSlot, Prioritized, Random},
+    Executor, Scheduler,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Returns `Pending` once after waking itself, sending the task to the back
+/// of the run queue.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+/// Two tasks passing a message back and forth over channels.
+fn ping_pong<S: Scheduler>(scheduler: S) {
+    let executor = Executor::with_scheduler(scheduler);
+    let (mut ping_tx, mut ping_rx) = mpsc::channel::<u32>(1);
+    let (mut pong_tx, mut pong_rx) = mpsc::channel::<u32>(1);
+
+    let pinger = executor.spawn(async move {
+        for i in 0..1000 {
+            ping_tx.send(i).await.unwrap();
+            pong_rx.next().await.unwrap();
+        }
+    });
+    let ponger = executor.spawn(async move {
+        while let Some(i) = ping_rx.next().await {
+            pong_tx.send(i).await.unwrap();
+        }
+    });
+
+    executor.block_on(async {
+        pinger.await.unwrap();
+        ponger.await.unwrap();
+    });
+}
+
+/// Many tasks that yield a few times each before finishing.
+fn yield_many<S: Scheduler>(scheduler: S) {
+    let executor = Executor::with_scheduler(scheduler);
+    let handles: Vec<_> = (0..500)
+        .map(|_| {
+            executor.spawn(async {
+                for _ in 0..10 {
+                    YieldNow(false).await;
+                }
+            })
+        })
+        .collect();
+
+    executor.block_on(async {
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("scheduler/ping-pong");
+    group.bench_function("fifo", |b| b.iter(|| ping_pong(Fifo::new())));
+    group.bench_function("lifo-slot", |b| b.iter(|| ping_pong(LifoSlot::new())));
+    group.bench_function("random", |b| b.iter(|| ping_pong(Random::with_seed(1))));
+    group.bench_function("prioritized", |b| b.iter(|| ping_pong(Prioritized::new())));
+    group.bench_function("edf", |b| b.iter(|| ping_pong(Edf::new())));
+    group.finish();
+
+    let mut group = c.benchmark_group("scheduler/yield-many");
+    group.bench_function("fifo", |b| b.iter(|| yield_many(Fifo::new())));
+    group.bench_function("lifo-slot", |b| b.iter(|| yield_many(LifoSlot::new())));
+    group.bench_function("random", |b| b.iter(|| yield_many(Random::with_seed(1))));
+    group.bench_function("prioritized", |b| b.iter(|| yield_many(Prioritized::new())));
+    group.bench_function("edf", |b| b.iter(|| yield_many(Edf::new())));
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
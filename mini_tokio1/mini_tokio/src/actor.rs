@@ -0,0 +1,202 @@
+//! Actors: tasks that own their state and handle messages one at a time.
+//!
+//! An [`Actor`] is spawned with [`spawn`], which returns its [`Addr`]. The
+//! actor takes messages from a bounded mailbox, so senders wait while it is
+//! full. [`Addr::send`] only delivers a message, while [`Addr::ask`] also
+//! waits for the reply.
+//!
+//! The actor stops once every address has been dropped and the messages
+//! already in its mailbox have been handled, or right after a message whose
+//! handler called [`Context::stop`].
+//!
+//! ```
+//! use mini_tokio::{actor::{self, Actor, Context}, Executor};
+//!
+//! struct Counter(u64);
+//!
+//! impl Actor for Counter {
+//!     type Message = u64;
+//!     type Reply = u64;
+//!
+//!     async fn handle(&mut self, n: u64, _ctx: &mut Context) -> u64 {
+//!         self.0 += n;
+//!         self.0
+//!     }
+//! }
+//!
+//! let executor = Executor::new();
+//! let total = executor.block_on(async {
+//!     let counter = actor::spawn(Counter(0));
+//!     counter.send(1).await.unwrap();
+//!     counter.ask(2).await.unwrap()
+//! });
+//! assert_eq!(total, 3);
+//! ```
+
+use std::{fmt, future::Future};
+
+use crate::sync::{
+    mpsc::{self, SendError, TrySendError},
+    oneshot,
+};
+
+/// How many messages a mailbox holds by default
+const DEFAULT_MAILBOX_CAPACITY: usize = 32;
+
+/// State that reacts to messages.
+///
+/// The handlers run on the actor's own task, one at a time, so they can
+/// change the actor's state without locks.
+pub trait Actor: Send + Sized + 'static {
+    /// The messages the actor handles
+    type Message: Send + 'static;
+    /// What handling a message returns to [`Addr::ask`]
+    type Reply: Send + 'static;
+
+    /// Handles one message
+    fn handle(
+        &mut self,
+        msg: Self::Message,
+        ctx: &mut Context,
+    ) -> impl Future<Output = Self::Reply> + Send;
+
+    /// Runs when the actor starts, before it handles any message
+    fn started(&mut self, ctx: &mut Context) -> impl Future<Output = ()> + Send {
+        let _ = ctx;
+        async {}
+    }
+
+    /// Runs when the actor stops, after it has handled its last message
+    fn stopped(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+/// Lets an actor's handlers control the actor.
+#[derive(Debug)]
+pub struct Context {
+    stopping: bool,
+}
+
+/// Sends messages to an actor. Cloning it adds an address.
+pub struct Addr<A: Actor> {
+    mailbox: mpsc::Sender<Envelope<A>>,
+}
+
+/// Returned by [`Addr::ask`] when the actor stopped before replying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AskError;
+
+struct Envelope<A: Actor> {
+    msg: A::Message,
+    /// Where the reply goes, for messages sent with [`Addr::ask`]
+    reply: Option<oneshot::Sender<A::Reply>>,
+}
+
+/// Spawns `actor` onto the current executor with a mailbox of 32 messages
+///
+/// # Panics
+///
+/// Panics if called outside of an executor.
+pub fn spawn<A: Actor>(actor: A) -> Addr<A> {
+    spawn_with_capacity(actor, DEFAULT_MAILBOX_CAPACITY)
+}
+
+/// Spawns `actor` onto the current executor with a mailbox of `capacity`
+/// messages
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0, or if called outside of an executor.
+pub fn spawn_with_capacity<A: Actor>(actor: A, capacity: usize) -> Addr<A> {
+    let (mailbox, rx) = mpsc::channel(capacity);
+    crate::spawn(run(actor, rx));
+    Addr { mailbox }
+}
+
+async fn run<A: Actor>(mut actor: A, mut mailbox: mpsc::Receiver<Envelope<A>>) {
+    let mut ctx = Context { stopping: false };
+    actor.started(&mut ctx).await;
+    while !ctx.stopping {
+        let Some(envelope) = mailbox.recv().await else {
+            break;
+        };
+        let reply = actor.handle(envelope.msg, &mut ctx).await;
+        if let Some(tx) = envelope.reply {
+            let _ = tx.send(reply);
+        }
+    }
+    // Messages still queued are dropped, failing their asks.
+    drop(mailbox);
+    actor.stopped().await;
+}
+
+impl Context {
+    /// Stops the actor once the current handler returns
+    pub fn stop(&mut self) {
+        self.stopping = true;
+    }
+}
+
+impl<A: Actor> Addr<A> {
+    /// Delivers `msg` to the actor's mailbox, waiting while it is full.
+    ///
+    /// Fails with the message if the actor has stopped.
+    pub async fn send(&self, msg: A::Message) -> Result<(), SendError<A::Message>> {
+        self.mailbox
+            .send(Envelope { msg, reply: None })
+            .await
+            .map_err(|SendError(envelope)| SendError(envelope.msg))
+    }
+
+    /// Delivers `msg` if the mailbox has room, without waiting
+    pub fn try_send(&self, msg: A::Message) -> Result<(), TrySendError<A::Message>> {
+        self.mailbox
+            .try_send(Envelope { msg, reply: None })
+            .map_err(|e| match e {
+                TrySendError::Full(envelope) => TrySendError::Full(envelope.msg),
+                TrySendError::Closed(envelope) => TrySendError::Closed(envelope.msg),
+            })
+    }
+
+    /// Delivers `msg` and waits for the actor to handle it, returning its
+    /// reply
+    pub async fn ask(&self, msg: A::Message) -> Result<A::Reply, AskError> {
+        let (tx, rx) = oneshot::channel();
+        let envelope = Envelope {
+            msg,
+            reply: Some(tx),
+        };
+        self.mailbox.send(envelope).await.map_err(|_| AskError)?;
+        rx.await.map_err(|_| AskError)
+    }
+
+    /// Returns whether the actor has stopped taking messages
+    pub fn is_closed(&self) -> bool {
+        self.mailbox.is_closed()
+    }
+}
+
+impl<A: Actor> Clone for Addr<A> {
+    fn clone(&self) -> Self {
+        Self {
+            mailbox: self.mailbox.clone(),
+        }
+    }
+}
+
+impl<A: Actor> fmt::Debug for Addr<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Addr")
+            .field("is_closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl fmt::Display for AskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("actor stopped before replying")
+    }
+}
+
+impl std::error::Error for AskError {}
@@ -0,0 +1,102 @@
+//! Shows the live tasks of a mini_tokio executor as a refreshing table.
+//!
+//! ```text
+//! mini-tokio-console <socket> [--interval <ms>] [--once]
+//! ```
+
+use std::{env, process, thread, time::Duration};
+
+use mini_tokio::console::{Snapshot, TaskSnapshot};
+
+const USAGE: &str = "usage: mini-tokio-console <socket> [--interval <ms>] [--once]";
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut socket = None;
+    let mut interval = Duration::from_secs(1);
+    let mut once = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--once" => once = true,
+            "--interval" => {
+                let ms = args.next().and_then(|ms| ms.parse().ok());
+                interval = Duration::from_millis(ms.unwrap_or_else(|| exit_with(USAGE)));
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ if socket.is_none() => socket = Some(arg),
+            _ => exit_with(USAGE),
+        }
+    }
+    let socket = socket.unwrap_or_else(|| exit_with(USAGE));
+
+    loop {
+        let snapshot = Snapshot::fetch(&socket)
+            .unwrap_or_else(|e| exit_with(&format!("failed to read {socket}: {e}")));
+        if once {
+            print!("{}", render(&snapshot));
+            return;
+        }
+        // Clear the screen and move the cursor home before redrawing.
+        print!("\x1b[2J\x1b[H{}", render(&snapshot));
+        thread::sleep(interval);
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(2);
+}
+
+fn render(snapshot: &Snapshot) -> String {
+    let header = [
+        "ID", "NAME", "STATE", "POLLS", "BUSY", "IDLE", "WOKEN BY", "LOCATION",
+    ]
+    .map(String::from);
+    let rows: Vec<[String; 8]> = snapshot.tasks.iter().map(row).collect();
+
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = format!("{} tasks\n", rows.len());
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<_> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn row(task: &TaskSnapshot) -> [String; 8] {
+    [
+        task.id.to_string(),
+        task.name.clone().unwrap_or_else(|| "-".into()),
+        task.state.clone(),
+        task.polls.to_string(),
+        format_micros(task.busy_us),
+        format_micros(task.idle_us),
+        task.last_woken_by.clone().unwrap_or_else(|| "-".into()),
+        task.location.clone(),
+    ]
+}
+
+fn format_micros(us: u64) -> String {
+    let d = Duration::from_micros(us);
+    if d >= Duration::from_secs(1) {
+        format!("{:.2}s", d.as_secs_f64())
+    } else if d >= Duration::from_millis(1) {
+        format!("{:.2}ms", d.as_secs_f64() * 1e3)
+    } else {
+        format!("{us}us")
+    }
+}
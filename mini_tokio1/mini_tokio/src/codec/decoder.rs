@@ -0,0 +1,36 @@
+use std::io;
+
+use bytes::BytesMut;
+
+/// Decodes frames from a buffer of bytes read from an I/O source.
+///
+/// Used by [`FramedRead`](super::FramedRead) and [`Framed`](super::Framed),
+/// which keep reading into the buffer until `decode` yields a frame.
+pub trait Decoder {
+    /// The type of decoded frames
+    type Item;
+
+    /// The type of decoding errors; I/O errors from the source are converted into it
+    type Error: From<io::Error>;
+
+    /// Attempts to decode a frame from `src`.
+    ///
+    /// Returns `Ok(None)` if `src` does not hold a complete frame yet. A
+    /// decoded frame must be removed from `src`; bytes belonging to the next
+    /// frame must be left in place.
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// Decodes the last frames once the source reached end of stream.
+    ///
+    /// The default calls `decode` and fails if bytes are left over that do
+    /// not form a complete frame.
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(buf)? {
+            Some(frame) => Ok(Some(frame)),
+            None if buf.is_empty() => Ok(None),
+            None => Err(
+                io::Error::new(io::ErrorKind::UnexpectedEof, "bytes remaining on stream").into(),
+            ),
+        }
+    }
+}
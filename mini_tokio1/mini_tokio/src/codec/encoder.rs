@@ -0,0 +1,14 @@
+use std::io;
+
+use bytes::BytesMut;
+
+/// Encodes frames of type `Item` into a buffer of bytes written to an I/O sink.
+///
+/// Used by [`FramedWrite`](super::FramedWrite) and [`Framed`](super::Framed).
+pub trait Encoder<Item> {
+    /// The type of encoding errors; I/O errors from the sink are converted into it
+    type Error: From<io::Error>;
+
+    /// Appends the encoded form of `item` to `dst`
+    fn encode(&mut self, item: Item, dst: &mut BytesMut) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,124 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::BytesMut;
+use futures::{Sink, Stream};
+use pin_project_lite::pin_project;
+
+use super::{
+    framed_impl::{ReadFrame, WriteFrame},
+    Decoder, Encoder,
+};
+use crate::io::{AsyncRead, AsyncWrite};
+
+pin_project! {
+    /// A [`Stream`] and [`Sink`] of frames over a single duplex I/O object.
+    ///
+    /// One codec implements both [`Decoder`] and [`Encoder`]. Use
+    /// `futures::StreamExt::split` to drive the two directions from different
+    /// tasks.
+    pub struct Framed<T, U> {
+        #[pin]
+        inner: T,
+        codec: U,
+        read: ReadFrame,
+        write: WriteFrame,
+    }
+}
+
+impl<T: AsyncRead + AsyncWrite, U> Framed<T, U> {
+    /// Frames `inner` using `codec` in both directions
+    pub fn new(inner: T, codec: U) -> Self {
+        Self {
+            inner,
+            codec,
+            read: ReadFrame::default(),
+            write: WriteFrame::default(),
+        }
+    }
+}
+
+impl<T, U> Framed<T, U> {
+    /// Returns a reference to the underlying I/O object
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying I/O object
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the underlying I/O object, discarding any buffered bytes
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the codec
+    pub fn codec(&self) -> &U {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec
+    pub fn codec_mut(&mut self) -> &mut U {
+        &mut self.codec
+    }
+
+    /// Returns the bytes read but not decoded yet
+    pub fn read_buffer(&self) -> &BytesMut {
+        &self.read.buffer
+    }
+
+    /// Returns the encoded bytes not written yet
+    pub fn write_buffer(&self) -> &BytesMut {
+        &self.write.buffer
+    }
+}
+
+impl<T: AsyncRead, U: Decoder> Stream for Framed<T, U> {
+    type Item = Result<U::Item, U::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.project();
+        me.read.poll_next(me.inner, cx, me.codec)
+    }
+}
+
+impl<T, I, U> Sink<I> for Framed<T, U>
+where
+    T: AsyncWrite,
+    U: Encoder<I>,
+{
+    type Error = U::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.write.poll_ready(me.inner, cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        let me = self.project();
+        me.write.start_send(me.codec, item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.write.poll_flush(me.inner, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.write.poll_close(me.inner, cx)
+    }
+}
+
+impl<T: std::fmt::Debug, U: std::fmt::Debug> std::fmt::Debug for Framed<T, U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Framed")
+            .field("inner", &self.inner)
+            .field("codec", &self.codec)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use bytes::{Buf, BytesMut};
+
+use super::{Decoder, Encoder};
+use crate::io::{AsyncRead, AsyncWrite};
+
+const INITIAL_CAPACITY: usize = 8 * 1024;
+const READ_CHUNK: usize = 4 * 1024;
+
+/// Buffered bytes waiting to be sent before `poll_ready` applies backpressure.
+const BACKPRESSURE_BOUNDARY: usize = INITIAL_CAPACITY;
+
+/// Read-side state shared by `FramedRead` and `Framed`.
+pub(super) struct ReadFrame {
+    pub(super) buffer: BytesMut,
+    eof: bool,
+    is_readable: bool,
+    has_errored: bool,
+}
+
+/// Write-side state shared by `FramedWrite` and `Framed`.
+pub(super) struct WriteFrame {
+    pub(super) buffer: BytesMut,
+}
+
+impl Default for ReadFrame {
+    fn default() -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(INITIAL_CAPACITY),
+            eof: false,
+            is_readable: false,
+            has_errored: false,
+        }
+    }
+}
+
+impl Default for WriteFrame {
+    fn default() -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(INITIAL_CAPACITY),
+        }
+    }
+}
+
+impl ReadFrame {
+    /// Decodes the next frame, reading more bytes from `io` whenever the
+    /// buffer does not hold a complete one.
+    pub(super) fn poll_next<T, D>(
+        &mut self,
+        mut io: Pin<&mut T>,
+        cx: &mut Context<'_>,
+        codec: &mut D,
+    ) -> Poll<Option<Result<D::Item, D::Error>>>
+    where
+        T: AsyncRead,
+        D: Decoder,
+    {
+        if self.has_errored {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if self.is_readable {
+                let frame = if self.eof {
+                    codec.decode_eof(&mut self.buffer)
+                } else {
+                    codec.decode(&mut self.buffer)
+                };
+                match frame {
+                    Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                    Ok(None) if self.eof => return Poll::Ready(None),
+                    Ok(None) => self.is_readable = false,
+                    Err(e) => {
+                        self.has_errored = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+            }
+
+            let len = self.buffer.len();
+            self.buffer.resize(len + READ_CHUNK, 0);
+            let res = io.as_mut().poll_read(cx, &mut self.buffer[len..]);
+            let n = match res {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => {
+                    self.buffer.truncate(len);
+                    self.has_errored = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+                Poll::Pending => {
+                    self.buffer.truncate(len);
+                    return Poll::Pending;
+                }
+            };
+            self.buffer.truncate(len + n);
+
+            if n == 0 {
+                self.eof = true;
+            }
+            self.is_readable = true;
+        }
+    }
+}
+
+impl WriteFrame {
+    /// Applies backpressure by flushing once too many bytes are buffered.
+    pub(super) fn poll_ready<T, E>(
+        &mut self,
+        io: Pin<&mut T>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), E>>
+    where
+        T: AsyncWrite,
+        E: From<io::Error>,
+    {
+        if self.buffer.len() >= BACKPRESSURE_BOUNDARY {
+            return self.poll_flush(io, cx);
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    pub(super) fn start_send<I, U>(&mut self, codec: &mut U, item: I) -> Result<(), U::Error>
+    where
+        U: Encoder<I>,
+    {
+        codec.encode(item, &mut self.buffer)
+    }
+
+    /// Writes out every buffered byte and flushes `io`.
+    pub(super) fn poll_flush<T, E>(
+        &mut self,
+        mut io: Pin<&mut T>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), E>>
+    where
+        T: AsyncWrite,
+        E: From<io::Error>,
+    {
+        while !self.buffer.is_empty() {
+            let n = ready!(io.as_mut().poll_write(cx, &self.buffer))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write frame to transport",
+                )
+                .into()));
+            }
+            self.buffer.advance(n);
+        }
+
+        ready!(io.poll_flush(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Flushes buffered frames and shuts `io` down.
+    pub(super) fn poll_close<T, E>(
+        &mut self,
+        mut io: Pin<&mut T>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), E>>
+    where
+        T: AsyncWrite,
+        E: From<io::Error>,
+    {
+        ready!(self.poll_flush::<T, E>(io.as_mut(), cx))?;
+        ready!(io.poll_shutdown(cx))?;
+        Poll::Ready(Ok(()))
+    }
+}
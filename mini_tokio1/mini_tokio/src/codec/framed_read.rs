@@ -0,0 +1,109 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::BytesMut;
+use futures::{Sink, Stream};
+use pin_project_lite::pin_project;
+
+use super::{framed_impl::ReadFrame, Decoder};
+use crate::io::AsyncRead;
+
+pin_project! {
+    /// A [`Stream`] of frames decoded from an [`AsyncRead`] source.
+    ///
+    /// The stream ends after yielding the first decoding or I/O error.
+    pub struct FramedRead<T, D> {
+        #[pin]
+        inner: T,
+        decoder: D,
+        state: ReadFrame,
+    }
+}
+
+impl<T: AsyncRead, D: Decoder> FramedRead<T, D> {
+    /// Creates a stream that decodes frames from `inner` using `decoder`
+    pub fn new(inner: T, decoder: D) -> Self {
+        Self {
+            inner,
+            decoder,
+            state: ReadFrame::default(),
+        }
+    }
+}
+
+impl<T, D> FramedRead<T, D> {
+    /// Returns a reference to the underlying source
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying source
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the underlying source, discarding any buffered bytes
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the decoder
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes read but not decoded yet
+    pub fn read_buffer(&self) -> &BytesMut {
+        &self.state.buffer
+    }
+}
+
+impl<T: AsyncRead, D: Decoder> Stream for FramedRead<T, D> {
+    type Item = Result<D::Item, D::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.project();
+        me.state.poll_next(me.inner, cx, me.decoder)
+    }
+}
+
+// Lets a `FramedRead` wrapping a sink still be written to directly.
+impl<T, I, D> Sink<I> for FramedRead<T, D>
+where
+    T: Sink<I>,
+{
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        self.project().inner.start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.project().inner.poll_close(cx)
+    }
+}
+
+impl<T: std::fmt::Debug, D: std::fmt::Debug> std::fmt::Debug for FramedRead<T, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FramedRead")
+            .field("inner", &self.inner)
+            .field("decoder", &self.decoder)
+            .field("buffered", &self.state.buffer.len())
+            .finish()
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::BytesMut;
+use futures::{Sink, Stream};
+use pin_project_lite::pin_project;
+
+use super::{framed_impl::WriteFrame, Encoder};
+use crate::io::AsyncWrite;
+
+pin_project! {
+    /// A [`Sink`] of frames encoded into an [`AsyncWrite`] sink.
+    pub struct FramedWrite<T, E> {
+        #[pin]
+        inner: T,
+        encoder: E,
+        state: WriteFrame,
+    }
+}
+
+impl<T: AsyncWrite, E> FramedWrite<T, E> {
+    /// Creates a sink that encodes frames into `inner` using `encoder`
+    pub fn new(inner: T, encoder: E) -> Self {
+        Self {
+            inner,
+            encoder,
+            state: WriteFrame::default(),
+        }
+    }
+}
+
+impl<T, E> FramedWrite<T, E> {
+    /// Returns a reference to the underlying sink
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying sink
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the underlying sink, discarding any frames not flushed yet
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the encoder
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the encoded bytes not written yet
+    pub fn write_buffer(&self) -> &BytesMut {
+        &self.state.buffer
+    }
+}
+
+impl<T, I, E> Sink<I> for FramedWrite<T, E>
+where
+    T: AsyncWrite,
+    E: Encoder<I>,
+{
+    type Error = E::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.state.poll_ready(me.inner, cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: I) -> Result<(), Self::Error> {
+        let me = self.project();
+        me.state.start_send(me.encoder, item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.state.poll_flush(me.inner, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let me = self.project();
+        me.state.poll_close(me.inner, cx)
+    }
+}
+
+// Lets a `FramedWrite` wrapping a stream still be read from directly.
+impl<T: Stream, E> Stream for FramedWrite<T, E> {
+    type Item = T::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().inner.poll_next(cx)
+    }
+}
+
+impl<T: std::fmt::Debug, E: std::fmt::Debug> std::fmt::Debug for FramedWrite<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FramedWrite")
+            .field("inner", &self.inner)
+            .field("encoder", &self.encoder)
+            .field("buffered", &self.state.buffer.len())
+            .finish()
+    }
+}
@@ -0,0 +1,323 @@
+//! Frames prefixed with their length; see [`LengthDelimitedCodec`].
+
+use std::{fmt, io};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use super::{Decoder, Encoder, Framed, FramedRead, FramedWrite};
+use crate::io::{AsyncRead, AsyncWrite};
+
+const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A codec for frames prefixed with their length.
+///
+/// With the default settings every frame is a 4-byte big-endian length
+/// followed by that many payload bytes, and decoded frames hold only the
+/// payload. [`Builder`] adjusts the header layout for other protocols:
+/// fields before the length, a length that counts the header too, little
+/// endian lengths, and so on. The encoder writes only the length field and
+/// the payload; any header bytes before the length field are up to the caller.
+///
+/// ```
+/// use mini_tokio::codec::LengthDelimitedCodec;
+///
+/// // A 2-byte little-endian length that includes the 2 header bytes.
+/// let codec = LengthDelimitedCodec::builder()
+///     .length_field_length(2)
+///     .little_endian()
+///     .length_adjustment(-2)
+///     .new_codec();
+/// # drop(codec);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LengthDelimitedCodec {
+    builder: Builder,
+    state: DecodeState,
+}
+
+/// Configures a [`LengthDelimitedCodec`].
+#[derive(Debug, Clone, Copy)]
+pub struct Builder {
+    max_frame_len: usize,
+    length_field_len: usize,
+    length_field_offset: usize,
+    length_adjustment: isize,
+    num_skip: Option<usize>,
+    big_endian: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum DecodeState {
+    Head,
+    Data(usize),
+}
+
+/// Error returned when a frame is longer than the configured maximum.
+pub struct LengthDelimitedCodecError {
+    _priv: (),
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a codec with the default settings
+    pub fn new() -> Self {
+        Builder::new().new_codec()
+    }
+
+    /// Returns a builder for configuring the header layout
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
+    /// Returns the maximum frame length
+    pub fn max_frame_length(&self) -> usize {
+        self.builder.max_frame_len
+    }
+
+    /// Sets the maximum frame length
+    pub fn set_max_frame_length(&mut self, val: usize) {
+        self.builder.max_frame_length(val);
+    }
+
+    fn decode_head(&mut self, src: &mut BytesMut) -> io::Result<Option<usize>> {
+        let head_len = self.builder.num_head_bytes();
+        let field_len = self.builder.length_field_len;
+
+        if src.len() < head_len {
+            return Ok(None);
+        }
+
+        let n = {
+            let mut field = &src[self.builder.length_field_offset..];
+            if self.builder.big_endian {
+                field.get_uint(field_len)
+            } else {
+                field.get_uint_le(field_len)
+            }
+        };
+
+        // The payload length, after applying the adjustment and counting any
+        // header bytes that are not skipped.
+        let n = usize::try_from(n)
+            .ok()
+            .and_then(|n| n.checked_add_signed(self.builder.length_adjustment))
+            .and_then(|n| n.checked_add(head_len))
+            .and_then(|n| n.checked_sub(self.builder.get_num_skip()))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "provided length would overflow after adjustment",
+                )
+            })?;
+
+        if n > self.builder.max_frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                LengthDelimitedCodecError { _priv: () },
+            ));
+        }
+
+        src.advance(self.builder.get_num_skip());
+        src.reserve(n.saturating_sub(src.len()));
+        Ok(Some(n))
+    }
+
+    fn decode_data(&self, n: usize, src: &mut BytesMut) -> Option<BytesMut> {
+        if src.len() < n {
+            return None;
+        }
+        Some(src.split_to(n))
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        let n = match self.state {
+            DecodeState::Head => match self.decode_head(src)? {
+                Some(n) => {
+                    self.state = DecodeState::Data(n);
+                    n
+                }
+                None => return Ok(None),
+            },
+            DecodeState::Data(n) => n,
+        };
+
+        match self.decode_data(n, src) {
+            Some(data) => {
+                self.state = DecodeState::Head;
+                // Make room for the next header.
+                src.reserve(self.builder.num_head_bytes());
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+impl Encoder<Bytes> for LengthDelimitedCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, data: Bytes, dst: &mut BytesMut) -> io::Result<()> {
+        let n = data.len();
+        if n > self.builder.max_frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                LengthDelimitedCodecError { _priv: () },
+            ));
+        }
+
+        // The value written to the length field undoes the adjustment that
+        // the decoder applies.
+        let field = n
+            .checked_add_signed(-self.builder.length_adjustment)
+            .filter(|&field| {
+                self.builder.length_field_len == 8
+                    || (field as u64) < 1 << (self.builder.length_field_len * 8)
+            })
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "frame length does not fit in the length field",
+                )
+            })?;
+
+        dst.reserve(self.builder.length_field_len + n);
+        if self.builder.big_endian {
+            dst.put_uint(field as u64, self.builder.length_field_len);
+        } else {
+            dst.put_uint_le(field as u64, self.builder.length_field_len);
+        }
+        dst.extend_from_slice(&data);
+        Ok(())
+    }
+}
+
+impl Builder {
+    /// Creates a builder with the default settings: a 4-byte big-endian
+    /// length at offset 0, no adjustment, the header skipped in decoded
+    /// frames, and a maximum frame length of 8 MiB
+    pub fn new() -> Self {
+        Self {
+            max_frame_len: DEFAULT_MAX_FRAME_LENGTH,
+            length_field_len: 4,
+            length_field_offset: 0,
+            length_adjustment: 0,
+            num_skip: None,
+            big_endian: true,
+        }
+    }
+
+    /// Reads and writes the length field as big endian (the default)
+    pub fn big_endian(&mut self) -> &mut Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Reads and writes the length field as little endian
+    pub fn little_endian(&mut self) -> &mut Self {
+        self.big_endian = false;
+        self
+    }
+
+    /// Sets the largest payload accepted by the decoder and encoder
+    pub fn max_frame_length(&mut self, val: usize) -> &mut Self {
+        self.max_frame_len = val;
+        self
+    }
+
+    /// Sets the number of bytes in the length field, from 1 to 8
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is not between 1 and 8.
+    pub fn length_field_length(&mut self, val: usize) -> &mut Self {
+        assert!((1..=8).contains(&val), "invalid length field length");
+        self.length_field_len = val;
+        self
+    }
+
+    /// Sets the number of header bytes that come before the length field
+    pub fn length_field_offset(&mut self, val: usize) -> &mut Self {
+        self.length_field_offset = val;
+        self
+    }
+
+    /// Sets a value added to the length field to get the payload length.
+    ///
+    /// Use a negative value when the length field counts header bytes too.
+    pub fn length_adjustment(&mut self, val: isize) -> &mut Self {
+        self.length_adjustment = val;
+        self
+    }
+
+    /// Sets how many bytes to strip from the start of each decoded frame.
+    ///
+    /// Defaults to the whole header, so that frames hold only the payload.
+    pub fn num_skip(&mut self, val: usize) -> &mut Self {
+        self.num_skip = Some(val);
+        self
+    }
+
+    /// Builds a codec with this configuration
+    pub fn new_codec(&self) -> LengthDelimitedCodec {
+        LengthDelimitedCodec {
+            builder: *self,
+            state: DecodeState::Head,
+        }
+    }
+
+    /// Builds a [`FramedRead`] over `inner` with this configuration
+    pub fn new_read<T: AsyncRead>(&self, inner: T) -> FramedRead<T, LengthDelimitedCodec> {
+        FramedRead::new(inner, self.new_codec())
+    }
+
+    /// Builds a [`FramedWrite`] over `inner` with this configuration
+    pub fn new_write<T: AsyncWrite>(&self, inner: T) -> FramedWrite<T, LengthDelimitedCodec> {
+        FramedWrite::new(inner, self.new_codec())
+    }
+
+    /// Builds a [`Framed`] over `inner` with this configuration
+    pub fn new_framed<T: AsyncRead + AsyncWrite>(
+        &self,
+        inner: T,
+    ) -> Framed<T, LengthDelimitedCodec> {
+        Framed::new(inner, self.new_codec())
+    }
+
+    fn num_head_bytes(&self) -> usize {
+        self.length_field_offset + self.length_field_len
+    }
+
+    fn get_num_skip(&self) -> usize {
+        self.num_skip.unwrap_or_else(|| self.num_head_bytes())
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LengthDelimitedCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LengthDelimitedCodecError").finish()
+    }
+}
+
+impl fmt::Display for LengthDelimitedCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("frame size too big")
+    }
+}
+
+impl std::error::Error for LengthDelimitedCodecError {}
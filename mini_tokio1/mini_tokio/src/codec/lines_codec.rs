@@ -0,0 +1,161 @@
+use std::{fmt, io};
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use super::{Decoder, Encoder};
+
+/// A codec for newline-delimited UTF-8 text.
+///
+/// Decoded lines have their `\n` or `\r\n` ending removed, and encoded lines
+/// get a `\n` appended. A maximum line length can be set to protect against
+/// peers that never send a newline; once a line exceeds it the decoder
+/// reports [`LinesCodecError::MaxLineLengthExceeded`] and then skips the rest
+/// of that line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinesCodec {
+    // Where to resume searching for a newline, so that bytes already
+    // scanned are not scanned again.
+    next_index: usize,
+    max_length: usize,
+    is_discarding: bool,
+}
+
+/// Errors produced by [`LinesCodec`].
+#[derive(Debug)]
+pub enum LinesCodecError {
+    /// A line was longer than the configured maximum
+    MaxLineLengthExceeded,
+    /// The underlying I/O object failed
+    Io(io::Error),
+}
+
+impl LinesCodec {
+    /// Creates a codec without a limit on line length
+    pub fn new() -> Self {
+        Self::new_with_max_length(usize::MAX)
+    }
+
+    /// Creates a codec that rejects lines longer than `max_length` bytes
+    pub fn new_with_max_length(max_length: usize) -> Self {
+        Self {
+            next_index: 0,
+            max_length,
+            is_discarding: false,
+        }
+    }
+
+    /// Returns the maximum line length
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+}
+
+impl Default for LinesCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn utf8(bytes: &[u8]) -> Result<&str, io::Error> {
+    std::str::from_utf8(bytes)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "line is not valid UTF-8"))
+}
+
+fn without_carriage_return(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+    type Error = LinesCodecError;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<String>, LinesCodecError> {
+        loop {
+            // Only look one byte past the limit: a line that long is too long
+            // whether or not its newline has arrived.
+            let read_to = self.max_length.saturating_add(1).min(buf.len());
+            let newline = buf[self.next_index..read_to]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|offset| self.next_index + offset);
+
+            match (self.is_discarding, newline) {
+                (true, Some(offset)) => {
+                    buf.advance(offset + 1);
+                    self.is_discarding = false;
+                    self.next_index = 0;
+                }
+                (true, None) => {
+                    buf.advance(read_to);
+                    self.next_index = 0;
+                    if buf.is_empty() {
+                        return Ok(None);
+                    }
+                }
+                (false, Some(offset)) => {
+                    self.next_index = 0;
+                    let line = buf.split_to(offset + 1);
+                    let line = without_carriage_return(&line[..line.len() - 1]);
+                    return Ok(Some(utf8(line)?.to_string()));
+                }
+                (false, None) if buf.len() > self.max_length => {
+                    self.is_discarding = true;
+                    return Err(LinesCodecError::MaxLineLengthExceeded);
+                }
+                (false, None) => {
+                    self.next_index = read_to;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<String>, LinesCodecError> {
+        Ok(match self.decode(buf)? {
+            Some(line) => Some(line),
+            None if buf.is_empty() || self.is_discarding => None,
+            None => {
+                // The final line had no trailing newline.
+                self.next_index = 0;
+                let line = buf.split_to(buf.len());
+                Some(utf8(without_carriage_return(&line))?.to_string())
+            }
+        })
+    }
+}
+
+impl<T: AsRef<str>> Encoder<T> for LinesCodec {
+    type Error = LinesCodecError;
+
+    fn encode(&mut self, line: T, buf: &mut BytesMut) -> Result<(), LinesCodecError> {
+        let line = line.as_ref();
+        buf.reserve(line.len() + 1);
+        buf.put(line.as_bytes());
+        buf.put_u8(b'\n');
+        Ok(())
+    }
+}
+
+impl fmt::Display for LinesCodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinesCodecError::MaxLineLengthExceeded => write!(f, "max line length exceeded"),
+            LinesCodecError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for LinesCodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LinesCodecError::MaxLineLengthExceeded => None,
+            LinesCodecError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for LinesCodecError {
+    fn from(e: io::Error) -> Self {
+        LinesCodecError::Io(e)
+    }
+}
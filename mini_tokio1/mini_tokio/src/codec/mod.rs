@@ -0,0 +1,36 @@
+//! Turning byte streams into streams of frames.
+//!
+//! A [`Decoder`] splits buffered bytes into frames and an [`Encoder`] turns
+//! frames back into bytes. [`FramedRead`], [`FramedWrite`] and [`Framed`]
+//! pair a codec with an I/O object to get a `futures::Stream` of decoded
+//! frames and a `futures::Sink` that accepts frames to encode.
+//!
+//! ```no_run
+//! use futures::{SinkExt, StreamExt};
+//! use mini_tokio::{codec::{Framed, LinesCodec}, net::UnixStream, Executor};
+//!
+//! let executor = Executor::new();
+//! executor.block_on(async {
+//!     let stream = UnixStream::connect("/tmp/service.sock").await.unwrap();
+//!     let mut lines = Framed::new(stream, LinesCodec::new());
+//!     lines.send("hello").await.unwrap();
+//!     let reply = lines.next().await;
+//! });
+//! ```
+
+mod decoder;
+mod encoder;
+mod framed;
+mod framed_impl;
+mod framed_read;
+mod framed_write;
+pub mod length_delimited;
+mod lines_codec;
+
+pub use decoder::Decoder;
+pub use encoder::Encoder;
+pub use framed::Framed;
+pub use framed_read::FramedRead;
+pub use framed_write::FramedWrite;
+pub use length_delimited::{LengthDelimitedCodec, LengthDelimitedCodecError};
+pub use lines_codec::{LinesCodec, LinesCodecError};
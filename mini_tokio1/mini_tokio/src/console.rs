@@ -0,0 +1,185 @@
+//! Live inspection of an executor's tasks over a Unix socket.
+//!
+//! An executor built with
+//! [`Builder::console_socket`](crate::executor::Builder::console_socket)
+//! listens on that socket. Every connection is answered with one [`Snapshot`]
+//! of the live tasks, encoded as a single line of JSON, after which the
+//! connection is closed.
+//!
+//! The bundled `mini-tokio-console` binary polls the socket and shows the
+//! snapshots as a refreshing table:
+//!
+//! ```text
+//! cargo run -p mini_tokio --features console --bin mini-tokio-console -- /tmp/app.sock
+//! ```
+
+use std::{
+    fs, io,
+    io::{Read, Write},
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::executor::{Schedule, TaskInfo};
+
+/// How often the server checks whether it should stop.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long a client may leave a snapshot unread before it is dropped, so
+/// that it cannot hold up the server or the executor's shutdown.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The live tasks of an executor at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Every task that has been spawned and has not completed, ordered by ID
+    pub tasks: Vec<TaskSnapshot>,
+}
+
+/// One task in a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskSnapshot {
+    /// Unique ID within the executor
+    pub id: u64,
+    /// The name given when the task was spawned
+    pub name: Option<String>,
+    /// Where the task was spawned, as `file:line:column`
+    pub location: String,
+    /// `idle`, `scheduled` or `running`
+    pub state: String,
+    /// How many times the task has been polled
+    pub polls: u64,
+    /// Total time spent polling the task, in microseconds
+    pub busy_us: u64,
+    /// Time since the task was spawned or last polled, in microseconds
+    pub idle_us: u64,
+    /// Time since the task's waker was last called, in microseconds
+    pub since_last_wake_us: Option<u64>,
+    /// What last called the task's waker, such as `task 3` or `io driver`
+    pub last_woken_by: Option<String>,
+}
+
+impl Snapshot {
+    /// Requests a snapshot from the executor listening on `path`
+    pub fn fetch(path: impl AsRef<Path>) -> io::Result<Snapshot> {
+        let mut stream = UnixStream::connect(path)?;
+        let mut json = String::new();
+        stream.read_to_string(&mut json)?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn new(infos: Vec<TaskInfo>) -> Self {
+        let tasks = infos
+            .into_iter()
+            .map(|info| TaskSnapshot {
+                id: info.id,
+                name: info.name,
+                location: info.location.to_string(),
+                state: info.state.to_string(),
+                polls: info.polls,
+                busy_us: micros(info.busy),
+                idle_us: micros(info.idle),
+                since_last_wake_us: info.since_last_wake.map(micros),
+                last_woken_by: info.last_woken_by.map(|by| by.to_string()),
+            })
+            .collect();
+        Snapshot { tasks }
+    }
+}
+
+fn micros(duration: Duration) -> u64 {
+    duration.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+/// The thread serving snapshots, stopped and joined when dropped.
+pub(crate) struct Server {
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+/// Starts a thread that serves snapshots of `shared` on `path`
+pub(crate) fn serve(path: &Path, shared: Weak<dyn Schedule>) -> io::Result<Server> {
+    // A socket left behind by an earlier run would make `bind` fail, but one
+    // that still accepts connections belongs to a live server.
+    if fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        match UnixStream::connect(path) {
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("a console is already served on {}", path.display()),
+                ))
+            }
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)?,
+            Err(e) => return Err(e),
+        }
+    }
+    let listener = UnixListener::bind(path)?;
+    listener.set_nonblocking(true)?;
+
+    let path = path.to_path_buf();
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = Arc::clone(&stop);
+        thread::Builder::new()
+            .name("mini-tokio-console".into())
+            .spawn(move || run_server(listener, path, shared, stop))?
+    };
+    Ok(Server {
+        stop,
+        thread: Some(thread),
+    })
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run_server(
+    listener: UnixListener,
+    path: PathBuf,
+    shared: Weak<dyn Schedule>,
+    stop: Arc<AtomicBool>,
+) {
+    while !stop.load(Ordering::Acquire) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let Some(shared) = shared.upgrade() else {
+                    break;
+                };
+                let snapshot = Snapshot::new(shared.task_infos());
+                drop(shared);
+                // A client that hangs up early only loses its own snapshot.
+                let _ = send(stream, &snapshot);
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+            }
+            Err(_) => break,
+        }
+    }
+    let _ = fs::remove_file(path);
+}
+
+fn send(mut stream: UnixStream, snapshot: &Snapshot) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut json = serde_json::to_vec(snapshot)?;
+    json.push(b'\n');
+    stream.write_all(&json)
+}
@@ -0,0 +1,97 @@
+use std::{cell::RefCell, future::Future, io};
+
+use crate::{
+    executor::{blocking::BlockingPool, sequence::Participant, Spawner},
+    io::driver,
+    process::Orphans,
+    task::{self, JoinHandle},
+    time,
+};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Handle>> = const { RefCell::new(None) };
+}
+
+/// Runtime resources reachable from inside `Executor::block_on` and from the
+/// executor's worker threads.
+#[derive(Clone)]
+pub(crate) struct Handle {
+    pub(crate) io: driver::Handle,
+    pub(crate) time: time::driver::Handle,
+    pub(crate) spawner: Spawner,
+    pub(crate) blocking: BlockingPool,
+    pub(crate) orphans: Orphans,
+}
+
+/// Restores the previously entered handle when dropped.
+pub(crate) struct EnterGuard {
+    prev: Option<Handle>,
+}
+
+/// Makes `handle` the current runtime for this thread until the guard is dropped.
+pub(crate) fn enter(handle: Handle) -> EnterGuard {
+    let prev = CURRENT.with(|current| current.borrow_mut().replace(handle));
+    EnterGuard { prev }
+}
+
+impl Drop for EnterGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.prev.take());
+    }
+}
+
+fn with_current<R>(f: impl FnOnce(&Handle) -> R) -> Option<R> {
+    CURRENT.with(|current| current.borrow().as_ref().map(f))
+}
+
+const NO_EXECUTOR: &str = "no mini_tokio executor is running on this thread";
+
+/// Returns the I/O driver of the executor running on this thread.
+pub(crate) fn io_handle() -> io::Result<driver::Handle> {
+    with_current(|handle| handle.io.clone()).ok_or_else(|| io::Error::other(NO_EXECUTOR))
+}
+
+/// Returns the timer driver of the executor running on this thread, if any.
+pub(crate) fn try_time_handle() -> Option<time::driver::Handle> {
+    with_current(|handle| handle.time.clone())
+}
+
+/// Returns the timer driver of the executor running on this thread.
+///
+/// Panics if there is none.
+pub(crate) fn time_handle() -> time::driver::Handle {
+    try_time_handle().expect(NO_EXECUTOR)
+}
+
+/// Reports that whatever is being polled on this thread handed its waker to
+/// `driver`, for sequence diagrams.
+pub(crate) fn waker_registered(driver: Participant) {
+    with_current(|handle| handle.spawner.waker_registered(driver));
+}
+
+/// Returns the blocking pool of the executor running on this thread.
+///
+/// Panics if there is none.
+pub(crate) fn blocking_pool() -> BlockingPool {
+    with_current(|handle| handle.blocking.clone()).expect(NO_EXECUTOR)
+}
+
+/// Returns the children dropped before they exited, reaped by the executor
+/// running on this thread.
+pub(crate) fn orphans() -> io::Result<Orphans> {
+    with_current(|handle| handle.orphans.clone()).ok_or_else(|| io::Error::other(NO_EXECUTOR))
+}
+
+/// Spawns `future` onto the executor running on this thread, configured by
+/// `builder`.
+///
+/// Panics if there is none.
+#[track_caller]
+pub(crate) fn spawn<F>(future: F, builder: &task::Builder) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let spawner = with_current(|handle| handle.spawner.clone()).expect(NO_EXECUTOR);
+    spawner.spawn(future, builder)
+}
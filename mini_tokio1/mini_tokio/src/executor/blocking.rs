@@ -0,0 +1,127 @@
+use std::{
+    collections::VecDeque,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+use super::CancelOnDrop;
+use crate::task::JoinHandle;
+
+/// How long an idle pool thread waits for work before exiting
+const KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Runs blocking closures on threads of their own, so that they do not hold
+/// up the executor.
+///
+/// Threads are started as jobs arrive, up to a limit past which jobs wait in
+/// a queue, and exit after sitting idle for a while.
+#[derive(Clone)]
+pub(crate) struct BlockingPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    job_queued: Condvar,
+    max_threads: usize,
+}
+
+struct State {
+    queue: VecDeque<Job>,
+    threads: usize,
+    idle: usize,
+    shutdown: bool,
+}
+
+impl BlockingPool {
+    pub(crate) fn new(max_threads: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    queue: VecDeque::new(),
+                    threads: 0,
+                    idle: 0,
+                    shutdown: false,
+                }),
+                job_queued: Condvar::new(),
+                max_threads,
+            }),
+        }
+    }
+
+    /// Runs `f` on a pool thread
+    pub(crate) fn spawn<F, R>(&self, f: F) -> JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let handle = JoinHandle::new();
+        // Cancels the handle if the job panics or is dropped unrun at shutdown.
+        let guard = CancelOnDrop(Some(handle.clone()));
+        self.submit(Box::new(move || guard.complete(f())));
+        handle
+    }
+
+    fn submit(&self, job: Job) {
+        let mut state = self.inner.state.lock().unwrap();
+        if state.shutdown {
+            return;
+        }
+        state.queue.push_back(job);
+        if state.idle > 0 {
+            self.inner.job_queued.notify_one();
+            return;
+        }
+        if state.threads < self.inner.max_threads {
+            let inner = Arc::clone(&self.inner);
+            let spawned = thread::Builder::new()
+                .name("mini-tokio-blocking".into())
+                .spawn(move || run(inner));
+            // Without a new thread the job waits for one of the running ones.
+            if spawned.is_ok() {
+                state.threads += 1;
+            }
+        }
+    }
+
+    /// Drops queued jobs and lets idle threads exit. Jobs already running
+    /// finish on their own.
+    pub(crate) fn shutdown(&self) {
+        let queue = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.shutdown = true;
+            std::mem::take(&mut state.queue)
+        };
+        self.inner.job_queued.notify_all();
+        drop(queue);
+    }
+}
+
+fn run(inner: Arc<Inner>) {
+    let mut state = inner.state.lock().unwrap();
+    loop {
+        if let Some(job) = state.queue.pop_front() {
+            drop(state);
+            // The panic has been reported by the hook, and the job's handle
+            // is cancelled as the closure unwinds.
+            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+            state = inner.state.lock().unwrap();
+            continue;
+        }
+        if state.shutdown {
+            break;
+        }
+        state.idle += 1;
+        let (guard, timeout) = inner.job_queued.wait_timeout(state, KEEP_ALIVE).unwrap();
+        state = guard;
+        state.idle -= 1;
+        if timeout.timed_out() && state.queue.is_empty() {
+            break;
+        }
+    }
+    state.threads -= 1;
+}
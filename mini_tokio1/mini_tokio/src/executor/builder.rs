@@ -0,0 +1,353 @@
+use std::{
+    collections::HashMap,
+    io,
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicU64},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use super::{
+    blocking::BlockingPool, hook::Hook, park::Parker, replay, run_worker, scheduler::Prioritized,
+    sequence, watchdog, DeadlineMiss, Executor, LongPoll, Scheduler, Shared, Spawner,
+};
+use crate::{
+    context,
+    io::driver::Driver,
+    process::Orphans,
+    time::{self, timerfd::TimerFd},
+};
+
+/// Configures and builds an [`Executor`].
+///
+/// ```
+/// use mini_tokio::executor::Builder;
+///
+/// let executor = Builder::new_multi_thread().worker_threads(2).build().unwrap();
+/// assert_eq!(executor.block_on(async { 1 + 1 }), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Builder {
+    flavor: Flavor,
+    worker_threads: Option<usize>,
+    max_blocking_threads: usize,
+    start_paused: bool,
+    on_deadline_miss: Option<Hook<DeadlineMiss>>,
+    watchdog: Option<watchdog::Config>,
+    replay: Option<replay::Mode>,
+    trace_sequence: bool,
+    timerfd: bool,
+    #[cfg(feature = "console")]
+    console_socket: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    CurrentThread,
+    MultiThread,
+}
+
+impl Builder {
+    /// Returns a builder for a single-threaded executor
+    pub fn new_current_thread() -> Self {
+        Self {
+            flavor: Flavor::CurrentThread,
+            worker_threads: None,
+            max_blocking_threads: 512,
+            start_paused: false,
+            on_deadline_miss: None,
+            watchdog: None,
+            replay: None,
+            trace_sequence: false,
+            timerfd: false,
+            #[cfg(feature = "console")]
+            console_socket: None,
+        }
+    }
+
+    /// Returns a builder for an executor that runs tasks on worker threads
+    pub fn new_multi_thread() -> Self {
+        Self {
+            flavor: Flavor::MultiThread,
+            ..Self::new_current_thread()
+        }
+    }
+
+    /// Sets the number of worker threads of a multi-threaded executor.
+    ///
+    /// Defaults to the number of CPUs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is 0.
+    pub fn worker_threads(&mut self, val: usize) -> &mut Self {
+        assert!(val > 0, "worker_threads cannot be 0");
+        self.worker_threads = Some(val);
+        self
+    }
+
+    /// Sets how many threads the blocking pool used by
+    /// [`spawn_blocking`](crate::task::spawn_blocking) and [`fs`](crate::fs)
+    /// may run at once. Further jobs wait for a thread to become free.
+    ///
+    /// Defaults to 512.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `val` is 0.
+    pub fn max_blocking_threads(&mut self, val: usize) -> &mut Self {
+        assert!(val > 0, "max_blocking_threads cannot be 0");
+        self.max_blocking_threads = val;
+        self
+    }
+
+    /// Starts the executor with its clock paused.
+    ///
+    /// See [`time`](crate::time) for how a paused clock behaves. Only
+    /// supported by single-threaded executors.
+    pub fn start_paused(&mut self, start_paused: bool) -> &mut Self {
+        self.start_paused = start_paused;
+        self
+    }
+
+    /// Calls `f` whenever a task spawned with a
+    /// [deadline](crate::task::Builder::deadline) has not completed by then.
+    ///
+    /// `f` runs on the thread driving the executor's timers as the deadline
+    /// passes, so it should return quickly.
+    pub fn on_deadline_miss<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(&DeadlineMiss) + Send + Sync + 'static,
+    {
+        self.on_deadline_miss = Some(Hook::new(f));
+        self
+    }
+
+    /// Starts a watchdog thread that reports any single poll of a task that
+    /// runs for longer than `threshold`.
+    ///
+    /// A task that blocks inside `poll`, for example on `std::thread::sleep`
+    /// or a blocking channel receive, holds up every other task on its
+    /// thread. Each such poll is reported once, with the task's name and
+    /// spawn location, to the [`on_long_poll`](Self::on_long_poll) hook or
+    /// else on stderr, and counted in
+    /// [`Metrics::long_polls`](super::Metrics::long_polls).
+    ///
+    /// ```
+    /// use mini_tokio::executor::Builder;
+    /// use std::time::Duration;
+    ///
+    /// let executor = Builder::new_current_thread()
+    ///     .watchdog(Duration::from_millis(10))
+    ///     .on_long_poll(|poll| eprintln!("{poll}"))
+    ///     .build()
+    ///     .unwrap();
+    /// executor.block_on(async {
+    ///     mini_tokio::spawn(async { std::thread::sleep(Duration::from_millis(50)) })
+    ///         .await
+    ///         .unwrap();
+    /// });
+    /// assert_eq!(executor.metrics().long_polls(), 1);
+    /// ```
+    pub fn watchdog(&mut self, threshold: Duration) -> &mut Self {
+        self.watchdog_config().threshold = threshold;
+        self
+    }
+
+    /// Makes the watchdog capture a backtrace of the thread stuck in a long
+    /// poll, enabling the watchdog with a one second threshold if it is not
+    /// enabled yet.
+    ///
+    /// The backtrace is taken by interrupting the thread with a real-time
+    /// signal and is best effort. Walking the stack inside the signal
+    /// handler is not async-signal-safe: if the thread was interrupted while
+    /// holding the allocator's or the dynamic loader's lock, it deadlocks.
+    /// Only turn this on to debug long polls.
+    pub fn watchdog_backtrace(&mut self, capture: bool) -> &mut Self {
+        self.watchdog_config().backtrace = capture;
+        self
+    }
+
+    /// Calls `f` for every long poll the watchdog finds instead of printing
+    /// it, enabling the watchdog with a one second threshold if it is not
+    /// enabled yet
+    pub fn on_long_poll<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(&LongPoll) + Send + Sync + 'static,
+    {
+        self.watchdog_config().hook = Some(Hook::new(f));
+        self
+    }
+
+    fn watchdog_config(&mut self) -> &mut watchdog::Config {
+        self.watchdog.get_or_insert_with(|| watchdog::Config {
+            threshold: Duration::from_secs(1),
+            backtrace: false,
+            hook: None,
+        })
+    }
+
+    /// Records every spawn, poll, wakeup and timer firing to a trace file at
+    /// `path`, to be replayed with [`replay`](Self::replay).
+    ///
+    /// See [`replay`](super::replay) for what is recorded. Only supported by
+    /// single-threaded executors.
+    pub fn record(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.replay = Some(replay::Mode::Record(path.into()));
+        self
+    }
+
+    /// Replays the trace file at `path` written by [`record`](Self::record),
+    /// polling tasks in the recorded order and firing timers at the recorded
+    /// times on a paused clock.
+    ///
+    /// Once the whole trace has been replayed the executor goes on as usual,
+    /// with its clock still paused. Only supported by single-threaded
+    /// executors.
+    pub fn replay(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.replay = Some(replay::Mode::Replay(path.into()));
+        self
+    }
+
+    /// Records polls, their results, waker registrations and wakeups, to be
+    /// drawn as a sequence diagram.
+    ///
+    /// Every step is kept in memory until the executor is dropped, so this is
+    /// meant for short runs. See [`sequence`](super::sequence).
+    pub fn trace_sequence(&mut self, enabled: bool) -> &mut Self {
+        self.trace_sequence = enabled;
+        self
+    }
+
+    /// Wakes the executor at timer deadlines with a Linux `timerfd` watched
+    /// by the I/O driver.
+    ///
+    /// An executor with nothing to do sleeps until the next timer deadline.
+    /// While it watches for I/O it sleeps in `epoll_wait`, whose timeout is
+    /// in whole milliseconds, so timers may fire up to a millisecond late.
+    /// With a timerfd the executor instead sleeps until I/O arrives or the
+    /// timerfd expires at the deadline, which is precise to the microsecond.
+    ///
+    /// Off by default. Has no effect while the clock is paused.
+    pub fn timerfd(&mut self, enabled: bool) -> &mut Self {
+        self.timerfd = enabled;
+        self
+    }
+
+    /// Serves snapshots of the executor's tasks on a Unix socket at `path`.
+    ///
+    /// See [`console`](crate::console) for the protocol.
+    ///
+    /// Building fails with [`std::io::ErrorKind::AddrInUse`] if another
+    /// executor is already serving on `path`.
+    #[cfg(feature = "console")]
+    pub fn console_socket(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.console_socket = Some(path.into());
+        self
+    }
+
+    /// Creates the executor with the default [`Prioritized`] scheduler
+    pub fn build(&mut self) -> io::Result<Executor> {
+        self.build_with_scheduler(Prioritized::new())
+    }
+
+    /// Creates the executor, ordering woken tasks with `scheduler`
+    pub fn build_with_scheduler<S: Scheduler>(&mut self, scheduler: S) -> io::Result<Executor<S>> {
+        if self.start_paused && self.flavor == Flavor::MultiThread {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "start_paused requires a current_thread executor",
+            ));
+        }
+        if self.replay.is_some() && self.flavor == Flavor::MultiThread {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "record and replay require a current_thread executor",
+            ));
+        }
+
+        let driver = Driver::new()?;
+        let multi_thread = self.flavor == Flavor::MultiThread;
+        let parker = Parker::new(driver.handle(), multi_thread);
+        let replaying = matches!(self.replay, Some(replay::Mode::Replay(_)));
+        let time = time::driver::Handle::new(self.start_paused || replaying, parker.clone());
+        let session = self
+            .replay
+            .as_ref()
+            .map(|mode| replay::Session::new(mode, time.clock().now()))
+            .transpose()?;
+        let shared = Arc::new(Shared {
+            run_queue: Mutex::new(scheduler),
+            task_queued: Condvar::new(),
+            tasks: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(0),
+            shutdown: AtomicBool::new(false),
+            time: time.clone(),
+            deadline_misses: AtomicU64::new(0),
+            on_deadline_miss: self.on_deadline_miss.clone(),
+            watchdog: self
+                .watchdog
+                .clone()
+                .map(|config| Arc::new(watchdog::State::new(config))),
+            replay: session,
+            sequence: self
+                .trace_sequence
+                .then(|| sequence::Recorder::new(time.clone())),
+            parker: (!multi_thread).then(|| parker.clone()),
+        });
+        let spawner = Spawner {
+            shared: Arc::clone(&shared) as _,
+        };
+        let handle = context::Handle {
+            io: driver.handle(),
+            time,
+            orphans: Orphans::new(spawner.clone()),
+            spawner,
+            blocking: BlockingPool::new(self.max_blocking_threads),
+        };
+
+        let timerfd = self.timerfd.then(|| TimerFd::new(&handle.io)).transpose()?;
+        let mut executor = Executor {
+            shared,
+            driver: Mutex::new(driver),
+            timerfd,
+            parker,
+            handle,
+            workers: Vec::new(),
+            watchdog: None,
+            #[cfg(feature = "console")]
+            console: None,
+        };
+        if self.flavor == Flavor::MultiThread {
+            let workers = self
+                .worker_threads
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get));
+            for i in 0..workers {
+                let shared = Arc::clone(&executor.shared);
+                let handle = executor.handle.clone();
+                let worker = thread::Builder::new()
+                    .name(format!("mini-tokio-worker-{i}"))
+                    .spawn(move || run_worker(shared, handle))?;
+                // Pushed one at a time so that dropping the executor on error
+                // joins the workers already started.
+                executor.workers.push(worker);
+            }
+        }
+
+        if let Some(state) = &executor.shared.watchdog {
+            executor.watchdog = Some(watchdog::spawn(Arc::clone(state))?);
+        }
+
+        #[cfg(feature = "console")]
+        if let Some(path) = &self.console_socket {
+            let server = crate::console::serve(path, Arc::downgrade(&executor.shared) as _)?;
+            executor.console = Some(server);
+        }
+
+        Ok(executor)
+    }
+}
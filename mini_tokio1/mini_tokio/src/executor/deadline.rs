@@ -0,0 +1,68 @@
+use std::{
+    panic::Location,
+    sync::{Arc, Weak},
+    task::Wake,
+};
+
+use super::task::Task;
+use crate::time::Instant;
+
+/// A task that was still running when its deadline passed, reported to the
+/// hook set with
+/// [`Builder::on_deadline_miss`](super::Builder::on_deadline_miss).
+#[derive(Debug, Clone)]
+pub struct DeadlineMiss {
+    id: u64,
+    name: Option<String>,
+    location: &'static Location<'static>,
+    deadline: Instant,
+}
+
+impl DeadlineMiss {
+    pub(super) fn new(task: &Task, deadline: Instant) -> Self {
+        Self {
+            id: task.id,
+            name: task.name.clone(),
+            location: task.location,
+            deadline,
+        }
+    }
+
+    /// Returns the task's ID, unique within the executor
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the name given with [`task::Builder::name`](crate::task::Builder::name)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns where the task was spawned
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the deadline the task missed
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+}
+
+/// Registered with the timer driver at a task's deadline. Firing means the
+/// task did not complete in time, since completing cancels the timer.
+pub(super) struct DeadlineWatch {
+    pub(super) task: Weak<Task>,
+}
+
+impl Wake for DeadlineWatch {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Some(task) = self.task.upgrade() {
+            task.deadline_missed();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fmt, panic::Location, time::Duration};
+
+use super::{TaskInfo, TaskState};
+use crate::task::Priority;
+
+/// The live tasks of an executor, returned by
+/// [`Executor::dump`](super::Executor::dump).
+///
+/// Its `Display` implementation prints one line per task.
+#[derive(Debug, Clone)]
+pub struct Dump {
+    tasks: Vec<TaskDump>,
+}
+
+/// One task in a [`Dump`].
+#[derive(Debug, Clone)]
+pub struct TaskDump {
+    id: u64,
+    name: Option<String>,
+    priority: Priority,
+    location: &'static Location<'static>,
+    state: TaskState,
+    polls: u64,
+    busy: Duration,
+    idle: Duration,
+}
+
+impl Dump {
+    pub(super) fn new(infos: Vec<TaskInfo>) -> Self {
+        let tasks = infos
+            .into_iter()
+            .map(|info| TaskDump {
+                id: info.id,
+                name: info.name,
+                priority: info.priority,
+                location: info.location,
+                state: info.state,
+                polls: info.polls,
+                busy: info.busy,
+                idle: info.idle,
+            })
+            .collect();
+        Dump { tasks }
+    }
+
+    /// Returns the tasks, ordered by ID
+    pub fn tasks(&self) -> &[TaskDump] {
+        &self.tasks
+    }
+}
+
+impl TaskDump {
+    /// Returns the task's ID, unique within the executor
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the name given with [`task::Builder::name`](crate::task::Builder::name)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the task's priority class
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Returns where the task was spawned
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns what the task was doing when the dump was taken
+    pub fn state(&self) -> TaskState {
+        self.state
+    }
+
+    /// Returns how many times the task has been polled
+    pub fn polls(&self) -> u64 {
+        self.polls
+    }
+
+    /// Returns the total time spent polling the task
+    pub fn busy(&self) -> Duration {
+        self.busy
+    }
+
+    /// Returns how long the task has gone without being polled, or zero if
+    /// it was being polled
+    pub fn idle(&self) -> Duration {
+        self.idle
+    }
+}
+
+impl fmt::Display for Dump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tasks.len() {
+            1 => writeln!(f, "1 task")?,
+            n => writeln!(f, "{n} tasks")?,
+        }
+        for task in &self.tasks {
+            writeln!(f, "  {task}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TaskDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {}", self.id)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name:?}")?;
+        }
+        if self.priority != Priority::Normal {
+            write!(f, " ({})", self.priority)?;
+        }
+        match self.state {
+            TaskState::Running => f.write_str(": running")?,
+            state => write!(f, ": {state} for {:?}", self.idle)?,
+        }
+        write!(f, ", spawned at {}", self.location)
+    }
+}
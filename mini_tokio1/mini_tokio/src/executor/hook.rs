@@ -0,0 +1,27 @@
+use std::{fmt, sync::Arc};
+
+/// A callback the executor invokes to report an event, such as
+/// [`Builder::on_deadline_miss`](super::Builder::on_deadline_miss).
+pub(crate) struct Hook<T>(Arc<dyn Fn(&T) + Send + Sync>);
+
+impl<T> Hook<T> {
+    pub(crate) fn new(f: impl Fn(&T) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, event: &T) {
+        (self.0)(event)
+    }
+}
+
+impl<T> Clone for Hook<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for Hook<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Hook(..)")
+    }
+}
@@ -0,0 +1,20 @@
+/// Counters describing what an executor has done so far, returned by
+/// [`Executor::metrics`](super::Executor::metrics).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub(super) deadline_misses: u64,
+    pub(super) long_polls: u64,
+}
+
+impl Metrics {
+    /// Returns how many tasks were still running when their deadline passed
+    pub fn deadline_misses(&self) -> u64 {
+        self.deadline_misses
+    }
+
+    /// Returns how many polls the [watchdog](super::Builder::watchdog) found
+    /// running for longer than its threshold
+    pub fn long_polls(&self) -> u64 {
+        self.long_polls
+    }
+}
@@ -0,0 +1,642 @@
+//! The executor, its [`Builder`] and its [`scheduler`] policies.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Condvar, Mutex, Weak,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    context,
+    io::driver::Driver,
+    task::{JoinHandle, Priority},
+    time,
+};
+
+pub(crate) mod blocking;
+mod builder;
+mod deadline;
+mod dump;
+mod hook;
+mod metrics;
+pub(crate) mod park;
+pub mod replay;
+pub mod scheduler;
+pub mod sequence;
+mod task;
+mod watchdog;
+
+pub use builder::Builder;
+pub use deadline::DeadlineMiss;
+pub use dump::{Dump, TaskDump};
+pub use metrics::Metrics;
+pub use scheduler::Scheduler;
+pub(crate) use task::TaskInfo;
+pub use task::TaskState;
+pub use watchdog::LongPoll;
+
+use deadline::DeadlineWatch;
+use hook::Hook;
+use park::Parker;
+use replay::{Session, Target};
+use scheduler::{Prioritized, Runnable};
+use task::{BoxFuture, Running, Task};
+
+/// An executor for running async tasks.
+///
+/// By default the executor is single-threaded: spawned tasks, timers and I/O
+/// all make progress on the thread inside [`Executor::block_on`]. A
+/// multi-threaded executor built with [`Builder::new_multi_thread`] runs
+/// spawned tasks on a set of worker threads instead, while timers and I/O are
+/// still driven by `block_on`.
+///
+/// Which woken task is polled next is decided by a [`Scheduler`], by default
+/// [`Prioritized`].
+pub struct Executor<S: Scheduler = Prioritized> {
+    shared: Arc<Shared<S>>,
+    driver: Mutex<Driver>,
+    /// Ends idle waits at timer deadlines, if enabled
+    timerfd: Option<time::timerfd::TimerFd>,
+    /// Lets the thread in `block_on` sleep while it has nothing to do
+    parker: Parker,
+    handle: context::Handle,
+    workers: Vec<thread::JoinHandle<()>>,
+    watchdog: Option<watchdog::Watchdog>,
+    #[cfg(feature = "console")]
+    console: Option<crate::console::Server>,
+}
+
+/// Spawns tasks onto an executor from anywhere.
+#[derive(Clone)]
+pub(crate) struct Spawner {
+    shared: Arc<dyn Schedule>,
+}
+
+/// Executor state reachable from task wakers.
+pub(crate) struct Shared<S> {
+    /// Tasks that have been woken and are waiting to be polled
+    run_queue: Mutex<S>,
+    /// Signalled when a task is queued, for idle worker threads
+    task_queued: Condvar,
+    /// Every task that has not completed yet, so that they can be listed
+    /// and dropped together with the executor
+    tasks: Mutex<HashMap<u64, Arc<Task>>>,
+    next_id: AtomicU64,
+    shutdown: AtomicBool,
+    /// Used to watch task deadlines
+    time: time::driver::Handle,
+    deadline_misses: AtomicU64,
+    on_deadline_miss: Option<Hook<DeadlineMiss>>,
+    /// Polls in progress, if the watchdog is enabled
+    watchdog: Option<Arc<watchdog::State>>,
+    /// The execution being recorded or replayed, if any
+    replay: Option<Session>,
+    /// Steps for a sequence diagram, if enabled
+    sequence: Option<sequence::Recorder>,
+    /// Wakes `block_on` when a task is queued, if it runs the tasks
+    parker: Option<Parker>,
+}
+
+/// The part of [`Shared`] that does not depend on the scheduler, so that
+/// tasks, spawners and the console need not be generic over it.
+pub(crate) trait Schedule: Send + Sync + 'static {
+    /// Registers a new task and queues it for its first poll
+    fn spawn_task(
+        self: Arc<Self>,
+        future: BoxFuture,
+        builder: &crate::task::Builder,
+        location: &'static Location<'static>,
+    );
+
+    /// Queues a woken task
+    fn schedule(&self, task: Arc<Task>);
+
+    /// Forgets a task that completed
+    fn release(&self, task: &Task);
+
+    /// Records that `task` was still running when its deadline passed
+    fn deadline_missed(&self, task: &Task);
+
+    /// Lets the watchdog, if enabled, see that `task` is being polled on
+    /// this thread until the guard is dropped
+    fn watch_poll(&self, task: &Arc<Task>) -> Option<watchdog::PollGuard<'_>>;
+
+    /// Returns a snapshot of every live task, ordered by ID
+    fn task_infos(&self) -> Vec<TaskInfo>;
+
+    /// Called just before `target` is polled
+    fn poll_started(&self, target: Target);
+
+    /// Called once `target` has been polled
+    fn poll_finished(&self, target: Target, ready: bool);
+
+    /// Called when the waker of `target` is called
+    fn woken(&self, target: Target);
+
+    /// Called when whatever is being polled on this thread hands its waker
+    /// to `driver`
+    fn waker_registered(&self, driver: sequence::Participant);
+}
+
+impl Executor {
+    /// Creates a new single-threaded executor
+    pub fn new() -> Self {
+        Builder::new_current_thread()
+            .build()
+            .expect("failed to create the executor")
+    }
+}
+
+impl<S: Scheduler> Executor<S> {
+    /// Creates a new single-threaded executor that orders tasks with
+    /// `scheduler`
+    pub fn with_scheduler(scheduler: S) -> Self {
+        Builder::new_current_thread()
+            .build_with_scheduler(scheduler)
+            .expect("failed to create the executor")
+    }
+
+    /// Spawns a new task onto the executor
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle
+            .spawner
+            .spawn(future, &crate::task::Builder::new())
+    }
+
+    /// Spawns a new task with the given priority onto the executor
+    #[track_caller]
+    pub fn spawn_with_priority<F>(&self, priority: Priority, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle
+            .spawner
+            .spawn(future, crate::task::Builder::new().priority(priority))
+    }
+
+    /// Spawns a new task that should complete by `deadline`.
+    ///
+    /// See [`task::Builder::deadline`](crate::task::Builder::deadline).
+    #[track_caller]
+    pub fn spawn_with_deadline<F>(
+        &self,
+        deadline: time::Instant,
+        future: F,
+    ) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle
+            .spawner
+            .spawn(future, crate::task::Builder::new().deadline(deadline))
+    }
+
+    /// Runs the blocking closure `f` on a thread of the executor's blocking
+    /// pool.
+    ///
+    /// See [`task::spawn_blocking`](crate::task::spawn_blocking).
+    pub fn spawn_blocking<F, R>(&self, f: F) -> JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.handle.blocking.spawn(f)
+    }
+
+    /// Returns the executor's counters
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            deadline_misses: self.shared.deadline_misses.load(Ordering::Relaxed),
+            long_polls: self
+                .shared
+                .watchdog
+                .as_ref()
+                .map_or(0, |state| state.long_polls.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Lists every task that has been spawned and has not completed yet.
+    ///
+    /// Useful when `block_on` never returns: the dump shows which tasks are
+    /// still waiting, where they were spawned and how long they have been
+    /// idle.
+    ///
+    /// ```
+    /// use mini_tokio::{task, Executor};
+    ///
+    /// let executor = Executor::new();
+    /// executor.block_on(async {
+    ///     task::Builder::new()
+    ///         .name("stuck")
+    ///         .spawn(std::future::pending::<()>());
+    /// });
+    ///
+    /// let dump = executor.dump();
+    /// assert_eq!(dump.tasks()[0].name(), Some("stuck"));
+    /// println!("{dump}");
+    /// ```
+    pub fn dump(&self) -> Dump {
+        Dump::new(self.shared.task_infos())
+    }
+
+    /// Returns the steps recorded so far if the executor was built with
+    /// [`Builder::trace_sequence`].
+    ///
+    /// See [`sequence`] for what is recorded.
+    pub fn sequence_trace(&self) -> Option<sequence::SequenceTrace> {
+        self.shared
+            .sequence
+            .as_ref()
+            .map(sequence::Recorder::snapshot)
+    }
+
+    /// Runs the executor until the given future completes
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        let mut driver = self.driver.lock().unwrap();
+        let _enter = context::enter(self.handle.clone());
+
+        let shared = Arc::downgrade(&self.shared);
+        let main = Arc::new(MainWaker {
+            woken: AtomicBool::new(true),
+            shared,
+            parker: self.parker.clone(),
+        });
+        let waker = Waker::from(Arc::clone(&main));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = std::pin::pin!(future);
+        loop {
+            if self.main_due() && main.woken.swap(false, Ordering::AcqRel) {
+                self.shared.poll_started(Target::Main);
+                let poll = {
+                    let _running = task::enter(Running::BlockOn);
+                    future.as_mut().poll(&mut cx)
+                };
+                self.shared.poll_finished(Target::Main, poll.is_ready());
+                if let Poll::Ready(output) = poll {
+                    if let Some(session) = &self.shared.replay {
+                        session.flush();
+                    }
+                    return output;
+                }
+            }
+
+            if self.workers.is_empty() {
+                self.run_queued();
+            }
+
+            {
+                let timeout = self.idle_timeout(&main);
+                let _running = task::enter(Running::IoDriver);
+                self.parker
+                    .park(&mut driver, timeout)
+                    .expect("failed to poll the I/O driver");
+            }
+
+            let _running = task::enter(Running::Timer);
+            let time = &self.handle.time;
+            if let Some(replayer) = self.replayer() {
+                replayer.fire_timers(time);
+                continue;
+            }
+            self.timers_fired(time.process());
+            let idle = !main.woken.load(Ordering::Acquire)
+                && self.shared.run_queue.lock().unwrap().is_empty();
+            if idle && time.auto_advance() {
+                self.timers_fired(time.process());
+            }
+        }
+    }
+
+    /// Returns how long the thread may sleep before the next turn, `None`
+    /// meaning until it is woken. With a timerfd, arms it for the next
+    /// deadline instead.
+    ///
+    /// The thread only sleeps while it has nothing to run. Wakers, new
+    /// timers and I/O wake it early.
+    fn idle_timeout(&self, main: &MainWaker) -> Option<Duration> {
+        let time = &self.handle.time;
+        let busy = main.woken.load(Ordering::Acquire)
+            || (self.workers.is_empty() && !self.shared.run_queue.lock().unwrap().is_empty());
+        if busy || self.replayer().is_some() {
+            return Some(Duration::ZERO);
+        }
+        let after = match time.next_deadline() {
+            // A paused clock jumps straight to the deadline.
+            Some(_) if time.clock().is_paused() => return Some(Duration::ZERO),
+            Some(deadline) => match deadline.checked_duration_since(time.clock().now()) {
+                Some(after) if !after.is_zero() => Some(after),
+                _ => return Some(Duration::ZERO),
+            },
+            None => None,
+        };
+        match &self.timerfd {
+            Some(timerfd) => {
+                timerfd.arm(after).expect("failed to arm the timerfd");
+                None
+            }
+            None => after,
+        }
+    }
+
+    /// Returns the replayer while a trace is being replayed
+    fn replayer(&self) -> Option<&replay::Replayer> {
+        self.shared.replay.as_ref().and_then(Session::replayer)
+    }
+
+    /// Returns whether the `block_on` future may be polled now, which during
+    /// a replay is only when the trace polls it next
+    fn main_due(&self) -> bool {
+        self.replayer()
+            .is_none_or(|replayer| replayer.main_due(&self.handle.time))
+    }
+
+    fn timers_fired(&self, fired: Vec<u64>) {
+        if let Some(session) = &self.shared.replay {
+            session.timers_fired(fired, self.handle.time.clock().now());
+        }
+    }
+
+    /// Polls as many tasks as were queued when the call started
+    fn run_queued(&self) {
+        if let Some(session) = &self.shared.replay {
+            if let Some(replayer) = session.replayer() {
+                // The trace decides which task runs next, not the scheduler.
+                while let Some(task) = replayer.next_task(&self.handle.time) {
+                    task.run();
+                }
+                return;
+            }
+            // Tasks woken before the replay ran out go back to the scheduler.
+            for task in session.take_held() {
+                self.shared
+                    .run_queue
+                    .lock()
+                    .unwrap()
+                    .schedule(Runnable(task));
+            }
+        }
+
+        // Bounded so that tasks that keep waking themselves wait for the next
+        // pass instead of starving the main future, while an urgent task
+        // woken during the pass can still go ahead of less urgent ones.
+        let budget = self.shared.run_queue.lock().unwrap().len();
+        for _ in 0..budget {
+            let Some(task) = self.shared.run_queue.lock().unwrap().next() else {
+                break;
+            };
+            task.run();
+        }
+    }
+}
+
+impl Default for Executor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Scheduler> Drop for Executor<S> {
+    fn drop(&mut self) {
+        #[cfg(feature = "console")]
+        drop(self.console.take());
+        drop(self.watchdog.take());
+        self.handle.blocking.shutdown();
+        self.handle.orphans.close();
+        if let Some(session) = &self.shared.replay {
+            session.flush();
+        }
+
+        self.shared.shutdown.store(true, Ordering::Release);
+        // Take the lock so that no worker misses the notification between
+        // checking the flag and waiting.
+        drop(self.shared.run_queue.lock().unwrap());
+        self.shared.task_queued.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+
+        // Tasks still waiting on a wakeup are kept alive by their wakers, which
+        // may in turn be owned by other tasks. Dropping the futures breaks
+        // those cycles and cancels the tasks' join handles.
+        let tasks = std::mem::take(&mut *self.shared.tasks.lock().unwrap());
+        for task in tasks.into_values() {
+            task.shutdown();
+        }
+        let mut queue = self.shared.run_queue.lock().unwrap();
+        while queue.next().is_some() {}
+    }
+}
+
+impl<S: Scheduler> Schedule for Shared<S> {
+    fn spawn_task(
+        self: Arc<Self>,
+        future: BoxFuture,
+        builder: &crate::task::Builder,
+        location: &'static Location<'static>,
+    ) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Some(session) = &self.replay {
+            session.spawned(id);
+        }
+        if let Some(sequence) = &self.sequence {
+            sequence.spawned(id, builder.name.as_deref());
+        }
+        let weak = Arc::downgrade(&self);
+        let task = Task::new(id, builder, location, future, weak);
+        if let Some(deadline) = task.deadline {
+            let watch = Waker::from(Arc::new(DeadlineWatch {
+                task: Arc::downgrade(&task),
+            }));
+            let key = self.time.register(None, deadline, &watch);
+            *task.deadline_timer.lock().unwrap() = Some(key);
+        }
+        self.tasks.lock().unwrap().insert(id, Arc::clone(&task));
+        task.schedule();
+    }
+
+    fn schedule(&self, task: Arc<Task>) {
+        let task = match self.replay.as_ref().and_then(Session::replayer) {
+            Some(replayer) => match replayer.hold(task) {
+                Ok(()) => return,
+                Err(task) => task,
+            },
+            None => task,
+        };
+        self.run_queue.lock().unwrap().schedule(Runnable(task));
+        self.task_queued.notify_one();
+        if let Some(parker) = &self.parker {
+            parker.unpark();
+        }
+    }
+
+    fn release(&self, task: &Task) {
+        if let Some(key) = task.deadline_timer.lock().unwrap().take() {
+            self.time.cancel(key);
+        }
+        self.tasks.lock().unwrap().remove(&task.id);
+    }
+
+    fn deadline_missed(&self, task: &Task) {
+        let Some(deadline) = task.deadline else {
+            return;
+        };
+        // The timer may have fired just as the task completed.
+        if !self.tasks.lock().unwrap().contains_key(&task.id) {
+            return;
+        }
+        self.deadline_misses.fetch_add(1, Ordering::Relaxed);
+        if let Some(hook) = &self.on_deadline_miss {
+            hook.call(&DeadlineMiss::new(task, deadline));
+        }
+    }
+
+    fn watch_poll(&self, task: &Arc<Task>) -> Option<watchdog::PollGuard<'_>> {
+        self.watchdog.as_ref().map(|state| state.watch(task))
+    }
+
+    fn task_infos(&self) -> Vec<TaskInfo> {
+        let tasks: Vec<_> = self.tasks.lock().unwrap().values().cloned().collect();
+        let mut infos: Vec<_> = tasks.iter().map(|task| task.info()).collect();
+        infos.sort_by_key(|info| info.id);
+        infos
+    }
+
+    fn poll_started(&self, target: Target) {
+        if let Some(session) = &self.replay {
+            session.poll_started(target, self.time.clock().now());
+        }
+        if let Some(sequence) = &self.sequence {
+            sequence.poll_started(target);
+        }
+    }
+
+    fn poll_finished(&self, target: Target, ready: bool) {
+        if let Some(session) = &self.replay {
+            session.poll_finished(target, ready);
+        }
+        if let Some(sequence) = &self.sequence {
+            sequence.poll_finished(target, ready);
+        }
+    }
+
+    fn woken(&self, target: Target) {
+        if let (Some(session), Target::Task(id)) = (&self.replay, target) {
+            session.woken(id);
+        }
+        if let Some(sequence) = &self.sequence {
+            sequence.woken(target);
+        }
+    }
+
+    fn waker_registered(&self, driver: sequence::Participant) {
+        if let Some(sequence) = &self.sequence {
+            sequence.registered(driver);
+        }
+    }
+}
+
+/// Runs queued tasks until the executor shuts down
+fn run_worker<S: Scheduler>(shared: Arc<Shared<S>>, handle: context::Handle) {
+    let _enter = context::enter(handle);
+    loop {
+        let task = {
+            let mut queue = shared.run_queue.lock().unwrap();
+            loop {
+                if shared.shutdown.load(Ordering::Acquire) {
+                    return;
+                }
+                if let Some(task) = queue.next() {
+                    break task;
+                }
+                queue = shared.task_queued.wait(queue).unwrap();
+            }
+        };
+        task.run();
+    }
+}
+
+impl Spawner {
+    #[track_caller]
+    pub(crate) fn spawn<F>(
+        &self,
+        future: F,
+        builder: &crate::task::Builder,
+    ) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = JoinHandle::new();
+        let handle_clone = handle.clone();
+
+        let future = async move {
+            // Resolves the handle with `Cancelled` if the task is dropped
+            // before it completes.
+            let guard = CancelOnDrop(Some(handle_clone));
+            let output = future.await;
+            guard.complete(output);
+        };
+
+        Arc::clone(&self.shared).spawn_task(Box::pin(future), builder, Location::caller());
+        handle
+    }
+
+    /// Reports that whatever is being polled handed its waker to `driver`
+    pub(crate) fn waker_registered(&self, driver: sequence::Participant) {
+        self.shared.waker_registered(driver);
+    }
+}
+
+/// Wakes the future passed to `block_on`.
+struct MainWaker {
+    woken: AtomicBool,
+    shared: Weak<dyn Schedule>,
+    parker: Parker,
+}
+
+impl Wake for MainWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.woken(Target::Main);
+        }
+        self.woken.store(true, Ordering::Release);
+        self.parker.unpark();
+    }
+}
+
+struct CancelOnDrop<T>(Option<JoinHandle<T>>);
+
+impl<T> CancelOnDrop<T> {
+    fn complete(mut self, output: T) {
+        if let Some(handle) = self.0.take() {
+            handle.set_output(output);
+        }
+    }
+}
+
+impl<T> Drop for CancelOnDrop<T> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.cancel();
+        }
+    }
+}
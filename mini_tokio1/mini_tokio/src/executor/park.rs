@@ -0,0 +1,109 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, Thread},
+    time::Duration,
+};
+
+use crate::io::driver::{self, Driver};
+
+const EMPTY: u8 = 0;
+const PARKED_DRIVER: u8 = 1;
+const PARKED_THREAD: u8 = 2;
+const NOTIFIED: u8 = 3;
+
+/// Puts the thread inside `block_on` to sleep while it has nothing to do,
+/// and lets any thread wake it.
+///
+/// The thread blocks in the I/O driver if it has to watch for I/O, and is
+/// woken through the driver's eventfd. Otherwise it is parked, which also
+/// wakes it at timer deadlines more precisely than `epoll_wait`'s
+/// millisecond timeout.
+#[derive(Clone)]
+pub(crate) struct Parker {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    state: AtomicU8,
+    /// The thread that parks, recorded before it does
+    thread: Mutex<Option<Thread>>,
+    io: driver::Handle,
+    /// Whether worker threads may register I/O while the thread sleeps
+    workers: bool,
+}
+
+impl Parker {
+    pub(crate) fn new(io: driver::Handle, workers: bool) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: AtomicU8::new(EMPTY),
+                thread: Mutex::new(None),
+                io,
+                workers,
+            }),
+        }
+    }
+
+    /// Turns `driver`, waiting up to `timeout` for an [`unpark`](Self::unpark)
+    /// or for I/O. A `timeout` of `None` waits as long as it takes.
+    ///
+    /// Returns at once if `unpark` was called since the last wait.
+    pub(crate) fn park(&self, driver: &mut Driver, timeout: Option<Duration>) -> io::Result<()> {
+        let state = &self.inner.state;
+        if timeout == Some(Duration::ZERO) || state.swap(EMPTY, Ordering::AcqRel) == NOTIFIED {
+            return driver.turn(Some(Duration::ZERO));
+        }
+
+        if self.inner.workers || driver.has_registrations() {
+            if !self.enter(PARKED_DRIVER) {
+                return driver.turn(Some(Duration::ZERO));
+            }
+            let res = driver.turn(timeout);
+            state.store(EMPTY, Ordering::Release);
+            return res;
+        }
+
+        // Without workers, resources are only registered by this thread, so
+        // none can show up while it is parked.
+        *self.inner.thread.lock().unwrap() = Some(thread::current());
+        if self.enter(PARKED_THREAD) {
+            match timeout {
+                Some(timeout) => thread::park_timeout(timeout),
+                None => thread::park(),
+            }
+            state.store(EMPTY, Ordering::Release);
+        }
+        driver.turn(Some(Duration::ZERO))
+    }
+
+    /// Moves from empty to `parked`, or consumes a notification that raced
+    /// in and returns false
+    fn enter(&self, parked: u8) -> bool {
+        let state = &self.inner.state;
+        match state.compare_exchange(EMPTY, parked, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => true,
+            Err(_) => {
+                state.store(EMPTY, Ordering::Release);
+                false
+            }
+        }
+    }
+
+    /// Wakes the parked thread, or makes its next [`park`](Self::park)
+    /// return at once. Can be called from any thread.
+    pub(crate) fn unpark(&self) {
+        match self.inner.state.swap(NOTIFIED, Ordering::AcqRel) {
+            PARKED_DRIVER => self.inner.io.wake(),
+            PARKED_THREAD => {
+                if let Some(thread) = &*self.inner.thread.lock().unwrap() {
+                    thread.unpark();
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! The binary trace format.
+//!
+//! A trace starts with the magic bytes `MTREPLAY` and a version byte,
+//! followed by one record per event: a tag byte and LEB128-encoded integers.
+//! Times are stored as nanoseconds since the previous timed event, which
+//! keeps most of them to two or three bytes.
+
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use super::{Event, Target};
+
+const MAGIC: &[u8; 8] = b"MTREPLAY";
+const VERSION: u8 = 1;
+
+const SPAWN: u8 = 0;
+const POLL_MAIN: u8 = 1;
+const POLL_TASK: u8 = 2;
+const READY_MAIN: u8 = 3;
+const READY_TASK: u8 = 4;
+const WAKE: u8 = 5;
+const TIMERS: u8 = 6;
+
+pub(super) fn write_header(out: &mut impl Write) -> io::Result<()> {
+    out.write_all(MAGIC)?;
+    out.write_all(&[VERSION])
+}
+
+/// Writes events, remembering the time of the last timed one.
+#[derive(Debug, Default)]
+pub(super) struct Encoder {
+    last: Duration,
+}
+
+impl Encoder {
+    pub(super) fn encode(&mut self, event: &Event, out: &mut impl Write) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(16);
+        match event {
+            Event::Spawn { task } => {
+                buf.push(SPAWN);
+                write_varint(&mut buf, *task);
+            }
+            Event::Poll { target, at } => {
+                match target {
+                    Target::Main => buf.push(POLL_MAIN),
+                    Target::Task(id) => {
+                        buf.push(POLL_TASK);
+                        write_varint(&mut buf, *id);
+                    }
+                }
+                self.write_time(&mut buf, *at);
+            }
+            Event::Ready { target } => match target {
+                Target::Main => buf.push(READY_MAIN),
+                Target::Task(id) => {
+                    buf.push(READY_TASK);
+                    write_varint(&mut buf, *id);
+                }
+            },
+            Event::Wake { task } => {
+                buf.push(WAKE);
+                write_varint(&mut buf, *task);
+            }
+            Event::Timers { at, fired } => {
+                buf.push(TIMERS);
+                self.write_time(&mut buf, *at);
+                write_varint(&mut buf, fired.len() as u64);
+                for id in fired {
+                    write_varint(&mut buf, *id);
+                }
+            }
+        }
+        out.write_all(&buf)
+    }
+
+    fn write_time(&mut self, buf: &mut Vec<u8>, at: Duration) {
+        let delta = at.saturating_sub(self.last);
+        self.last = at;
+        write_varint(buf, u64::try_from(delta.as_nanos()).unwrap_or(u64::MAX));
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut val: u64) {
+    while val >= 0x80 {
+        buf.push(val as u8 | 0x80);
+        val >>= 7;
+    }
+    buf.push(val as u8);
+}
+
+/// Parses a whole trace
+pub(super) fn decode(bytes: &[u8]) -> io::Result<Vec<Event>> {
+    let rest = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or_else(|| invalid("not a mini_tokio trace"))?;
+    let (&version, rest) = rest.split_first().ok_or_else(truncated)?;
+    if version != VERSION {
+        return Err(invalid(format!("unsupported trace version {version}")));
+    }
+
+    let mut reader = Reader {
+        bytes: rest,
+        last: Duration::ZERO,
+    };
+    let mut events = Vec::new();
+    while let Some(tag) = reader.next_byte() {
+        let event = match tag {
+            SPAWN => Event::Spawn {
+                task: reader.varint()?,
+            },
+            POLL_MAIN => Event::Poll {
+                target: Target::Main,
+                at: reader.time()?,
+            },
+            POLL_TASK => Event::Poll {
+                target: Target::Task(reader.varint()?),
+                at: reader.time()?,
+            },
+            READY_MAIN => Event::Ready {
+                target: Target::Main,
+            },
+            READY_TASK => Event::Ready {
+                target: Target::Task(reader.varint()?),
+            },
+            WAKE => Event::Wake {
+                task: reader.varint()?,
+            },
+            TIMERS => {
+                let at = reader.time()?;
+                let count = reader.varint()?;
+                let fired = (0..count)
+                    .map(|_| reader.varint())
+                    .collect::<io::Result<_>>()?;
+                Event::Timers { at, fired }
+            }
+            tag => return Err(invalid(format!("unknown event tag {tag}"))),
+        };
+        events.push(event);
+    }
+    Ok(events)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    last: Duration,
+}
+
+impl Reader<'_> {
+    fn next_byte(&mut self) -> Option<u8> {
+        let (&byte, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(byte)
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut val = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.next_byte().ok_or_else(truncated)?;
+            val |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(val);
+            }
+        }
+        Err(invalid("integer too long"))
+    }
+
+    fn time(&mut self) -> io::Result<Duration> {
+        let delta = Duration::from_nanos(self.varint()?);
+        self.last += delta;
+        Ok(self.last)
+    }
+}
+
+fn truncated() -> io::Error {
+    invalid("truncated trace")
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
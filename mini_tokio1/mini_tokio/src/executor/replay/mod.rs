@@ -0,0 +1,398 @@
+//! Recording an execution and replaying it.
+//!
+//! An executor built with [`Builder::record`](super::Builder::record) writes
+//! a [`Trace`] of every spawn, poll, completion, wakeup and timer firing to a
+//! file. One built with [`Builder::replay`](super::Builder::replay) reads the
+//! trace back and polls tasks in the recorded order, firing the recorded
+//! timers at the recorded times on a paused clock. A failure that depends on
+//! one particular interleaving, say one that only shows up in CI, can then be
+//! reproduced from the trace file.
+//!
+//! The replayed program must make the same decisions as the recorded one:
+//! spawn the same tasks in the same order and have its polls return the same
+//! results. If it does not, the replay panics with the first event that
+//! differs. Wakeups are recorded but not replayed, since a task that the
+//! trace polls next is simply waited for until something wakes it.
+//!
+//! ```
+//! use mini_tokio::executor::{replay::Trace, Builder};
+//!
+//! let path = std::env::temp_dir().join(format!("doc-{}.trace", std::process::id()));
+//! let program = || async {
+//!     let a = mini_tokio::spawn(async { 1 });
+//!     let b = mini_tokio::spawn(async { 2 });
+//!     a.await.unwrap() + b.await.unwrap()
+//! };
+//!
+//! let executor = Builder::new_current_thread().record(&path).build().unwrap();
+//! assert_eq!(executor.block_on(program()), 3);
+//! drop(executor);
+//!
+//! let executor = Builder::new_current_thread().replay(&path).build().unwrap();
+//! assert_eq!(executor.block_on(program()), 3);
+//!
+//! for event in Trace::load(&path).unwrap().events() {
+//!     println!("{event}");
+//! }
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use super::task::Task;
+use crate::time::{self, Instant};
+
+mod format;
+
+/// A recorded execution, as written by
+/// [`Builder::record`](super::Builder::record).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    events: Vec<Event>,
+}
+
+/// Something that happened on a recording executor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A task was spawned
+    Spawn { task: u64 },
+    /// `target` was polled, `at` after the executor was built
+    Poll { target: Target, at: Duration },
+    /// The last poll of `target` completed it
+    Ready { target: Target },
+    /// A task's waker was called
+    Wake { task: u64 },
+    /// The timers with the IDs in `fired` expired, `at` after the executor
+    /// was built
+    Timers { at: Duration, fired: Vec<u64> },
+}
+
+/// A future polled by the executor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The future passed to `block_on`
+    Main,
+    /// A spawned task, by ID
+    Task(u64),
+}
+
+impl Trace {
+    /// Reads a trace file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Ok(Self {
+            events: format::decode(&bytes)?,
+        })
+    }
+
+    /// Returns the recorded events in the order they happened
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+/// Whether to record or replay, as set on the builder.
+#[derive(Debug, Clone)]
+pub(super) enum Mode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// A recording or a replay in progress.
+pub(super) enum Session {
+    Record(Recorder),
+    Replay(Replayer),
+}
+
+pub(super) struct Recorder {
+    origin: Instant,
+    /// `None` once writing has failed
+    out: Mutex<Output>,
+}
+
+type Output = Option<(BufWriter<File>, format::Encoder)>;
+
+pub(super) struct Replayer {
+    origin: Instant,
+    events: Vec<Event>,
+    /// Index of the next event to replay
+    cursor: Mutex<usize>,
+    /// Tasks that have been woken, until the trace polls them
+    woken: Mutex<HashMap<u64, Arc<Task>>>,
+}
+
+impl Session {
+    /// Opens the trace file, taking `origin` as the time the executor was built
+    pub(super) fn new(mode: &Mode, origin: Instant) -> io::Result<Self> {
+        match mode {
+            Mode::Record(path) => {
+                let mut file = BufWriter::new(File::create(path)?);
+                format::write_header(&mut file)?;
+                Ok(Session::Record(Recorder {
+                    origin,
+                    out: Mutex::new(Some((file, format::Encoder::default()))),
+                }))
+            }
+            Mode::Replay(path) => Ok(Session::Replay(Replayer {
+                origin,
+                events: Trace::load(path)?.events,
+                cursor: Mutex::new(0),
+                woken: Mutex::new(HashMap::new()),
+            })),
+        }
+    }
+
+    /// Returns the replayer until it has replayed the whole trace
+    pub(super) fn replayer(&self) -> Option<&Replayer> {
+        match self {
+            Session::Replay(replayer) if !replayer.is_finished() => Some(replayer),
+            _ => None,
+        }
+    }
+
+    pub(super) fn spawned(&self, task: u64) {
+        match self {
+            Session::Record(recorder) => recorder.write(Event::Spawn { task }),
+            Session::Replay(replayer) => replayer.expect(
+                |event| *event == Event::Spawn { task },
+                format_args!("task {task} was spawned"),
+            ),
+        }
+    }
+
+    pub(super) fn poll_started(&self, target: Target, now: Instant) {
+        match self {
+            Session::Record(recorder) => recorder.write(Event::Poll {
+                target,
+                at: now.duration_since(recorder.origin),
+            }),
+            Session::Replay(replayer) => replayer.expect(
+                |event| matches!(event, Event::Poll { target: t, .. } if *t == target),
+                format_args!("{target} was polled"),
+            ),
+        }
+    }
+
+    pub(super) fn poll_finished(&self, target: Target, ready: bool) {
+        match self {
+            Session::Record(recorder) => {
+                if ready {
+                    recorder.write(Event::Ready { target });
+                }
+            }
+            Session::Replay(replayer) => replayer.check_result(target, ready),
+        }
+    }
+
+    pub(super) fn woken(&self, task: u64) {
+        if let Session::Record(recorder) = self {
+            recorder.write(Event::Wake { task });
+        }
+    }
+
+    pub(super) fn timers_fired(&self, fired: Vec<u64>, now: Instant) {
+        if let Session::Record(recorder) = self {
+            if !fired.is_empty() {
+                recorder.write(Event::Timers {
+                    at: now.duration_since(recorder.origin),
+                    fired,
+                });
+            }
+        }
+    }
+
+    /// Takes the tasks that were woken but not polled when the replay ran out
+    pub(super) fn take_held(&self) -> Vec<Arc<Task>> {
+        match self {
+            Session::Replay(replayer) => {
+                let mut tasks: Vec<_> = replayer.woken.lock().unwrap().drain().collect();
+                tasks.sort_by_key(|(id, _)| *id);
+                tasks.into_iter().map(|(_, task)| task).collect()
+            }
+            Session::Record(_) => Vec::new(),
+        }
+    }
+
+    pub(super) fn flush(&self) {
+        if let Session::Record(recorder) = self {
+            let mut out = recorder.out.lock().unwrap();
+            if let Some((file, _)) = out.as_mut() {
+                if let Err(err) = file.flush() {
+                    recorder.fail(&mut out, err);
+                }
+            }
+        }
+    }
+}
+
+impl Recorder {
+    fn write(&self, event: Event) {
+        let mut out = self.out.lock().unwrap();
+        if let Some((file, encoder)) = out.as_mut() {
+            if let Err(err) = encoder.encode(&event, file) {
+                self.fail(&mut out, err);
+            }
+        }
+    }
+
+    fn fail(&self, out: &mut Output, err: io::Error) {
+        eprintln!("mini_tokio replay: stopped recording: {err}");
+        *out = None;
+    }
+}
+
+impl Replayer {
+    fn is_finished(&self) -> bool {
+        self.current(&mut self.cursor.lock().unwrap()).is_none()
+    }
+
+    /// Returns the event at the cursor, moving the cursor past wakeups,
+    /// which are not replayed
+    fn current(&self, cursor: &mut usize) -> Option<&Event> {
+        while let Some(Event::Wake { .. }) = self.events.get(*cursor) {
+            *cursor += 1;
+        }
+        self.events.get(*cursor)
+    }
+
+    /// Keeps a woken task until the trace polls it, handing it back once the
+    /// replay is over
+    pub(super) fn hold(&self, task: Arc<Task>) -> Result<(), Arc<Task>> {
+        let mut woken = self.woken.lock().unwrap();
+        if self.is_finished() {
+            return Err(task);
+        }
+        woken.insert(task.id, task);
+        Ok(())
+    }
+
+    /// Returns whether the trace polls the `block_on` future next, moving
+    /// the clock to the time of that poll if so
+    pub(super) fn main_due(&self, time: &time::driver::Handle) -> bool {
+        match self.peek() {
+            Some(Event::Poll {
+                target: Target::Main,
+                at,
+            }) => {
+                time.clock().advance_to(self.origin + at);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the task the trace polls next once it has been woken, moving
+    /// the clock to the time of that poll
+    pub(super) fn next_task(&self, time: &time::driver::Handle) -> Option<Arc<Task>> {
+        let Some(Event::Poll {
+            target: Target::Task(id),
+            at,
+        }) = self.peek()
+        else {
+            return None;
+        };
+        let task = self.woken.lock().unwrap().remove(&id)?;
+        time.clock().advance_to(self.origin + at);
+        Some(task)
+    }
+
+    /// Fires the timers the trace fires next, if it does so before polling
+    /// anything else
+    pub(super) fn fire_timers(&self, time: &time::driver::Handle) {
+        let (index, at, fired) = {
+            let mut cursor = self.cursor.lock().unwrap();
+            let Some(Event::Timers { at, fired }) = self.current(&mut cursor) else {
+                return;
+            };
+            *cursor += 1;
+            (*cursor - 1, *at, fired)
+        };
+        time.clock().advance_to(self.origin + at);
+        let missing = time.fire(fired);
+        if !missing.is_empty() {
+            diverged(
+                index,
+                &self.events[index],
+                format_args!("timers {missing:?} were not registered"),
+            );
+        }
+    }
+
+    fn peek(&self) -> Option<Event> {
+        self.current(&mut self.cursor.lock().unwrap()).cloned()
+    }
+
+    /// Moves past the next event if `matches` accepts it, and panics
+    /// otherwise
+    fn expect(&self, matches: impl FnOnce(&Event) -> bool, found: fmt::Arguments<'_>) {
+        let index = {
+            let mut cursor = self.cursor.lock().unwrap();
+            match self.current(&mut cursor) {
+                // Past the end of the trace, anything goes.
+                None => return,
+                Some(event) if matches(event) => {
+                    *cursor += 1;
+                    return;
+                }
+                Some(_) => *cursor,
+            }
+        };
+        diverged(index, &self.events[index], found);
+    }
+
+    fn check_result(&self, target: Target, ready: bool) {
+        let index = {
+            let mut cursor = self.cursor.lock().unwrap();
+            let Some(event) = self.current(&mut cursor) else {
+                return;
+            };
+            let expected = *event == Event::Ready { target };
+            if ready == expected {
+                if ready {
+                    *cursor += 1;
+                }
+                return;
+            }
+            *cursor
+        };
+        let result = if ready { "Ready" } else { "Pending" };
+        diverged(
+            index,
+            &self.events[index],
+            format_args!("{target} returned {result}"),
+        );
+    }
+}
+
+fn diverged(index: usize, expected: &Event, found: fmt::Arguments<'_>) -> ! {
+    panic!("replay diverged at event {index}: the trace has `{expected}`, but {found}");
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Spawn { task } => write!(f, "spawn task {task}"),
+            Event::Poll { target, at } => write!(f, "poll {target} at {at:?}"),
+            Event::Ready { target } => write!(f, "{target} ready"),
+            Event::Wake { task } => write!(f, "wake task {task}"),
+            Event::Timers { at, fired } => write!(f, "fire timers {fired:?} at {at:?}"),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Main => f.write_str("block_on"),
+            Target::Task(id) => write!(f, "task {id}"),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use super::{Runnable, Scheduler};
+use crate::time::Instant;
+
+/// Polls the woken task with the earliest deadline first.
+///
+/// Tasks spawned with a [deadline](crate::task::Builder::deadline) are
+/// ordered by it, ties going to the task woken first. Tasks without a
+/// deadline only run when no task with one is waiting, in the order they
+/// were woken, so a steady stream of deadline tasks can starve them.
+#[derive(Debug, Default)]
+pub struct Edf {
+    deadlines: BinaryHeap<Entry>,
+    rest: VecDeque<Runnable>,
+    /// Breaks ties between equal deadlines in wake order
+    next_seq: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    deadline: Instant,
+    seq: u64,
+    task: Runnable,
+}
+
+impl Edf {
+    /// Creates an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Scheduler for Edf {
+    fn schedule(&mut self, task: Runnable) {
+        match task.deadline() {
+            Some(deadline) => {
+                let seq = self.next_seq;
+                self.next_seq += 1;
+                self.deadlines.push(Entry {
+                    deadline,
+                    seq,
+                    task,
+                });
+            }
+            None => self.rest.push_back(task),
+        }
+    }
+
+    fn next(&mut self) -> Option<Runnable> {
+        match self.deadlines.pop() {
+            Some(entry) => Some(entry.task),
+            None => self.rest.pop_front(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.deadlines.len() + self.rest.len()
+    }
+}
+
+impl Entry {
+    fn key(&self) -> (Instant, u64) {
+        (self.deadline, self.seq)
+    }
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    // Reversed, since `BinaryHeap` pops the greatest entry first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::VecDeque;
+
+use super::{Runnable, Scheduler};
+
+/// Polls tasks in the order they were woken.
+#[derive(Debug, Default)]
+pub struct Fifo {
+    queue: VecDeque<Runnable>,
+}
+
+impl Fifo {
+    /// Creates an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Scheduler for Fifo {
+    fn schedule(&mut self, task: Runnable) {
+        self.queue.push_back(task);
+    }
+
+    fn next(&mut self) -> Option<Runnable> {
+        self.queue.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
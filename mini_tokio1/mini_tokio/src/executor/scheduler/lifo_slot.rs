@@ -0,0 +1,52 @@
+use std::collections::VecDeque;
+
+use super::{Runnable, Scheduler};
+
+/// How many tasks in a row can be taken from the slot before the FIFO queue
+/// gets a turn.
+const MAX_SLOT_POLLS: u32 = 3;
+
+/// Polls the most recently woken task next, and the others in FIFO order.
+///
+/// When one task wakes another, for example by sending it a message, the
+/// woken task is likely to find the message still in cache. A newly woken
+/// task therefore takes a single LIFO slot, pushing the task it displaces to
+/// the back of a FIFO queue. So that two tasks waking each other cannot
+/// monopolise the slot, the queue is served after a few slot polls in a row.
+#[derive(Debug, Default)]
+pub struct LifoSlot {
+    slot: Option<Runnable>,
+    queue: VecDeque<Runnable>,
+    /// Tasks taken from the slot since the queue was last served
+    slot_polls: u32,
+}
+
+impl LifoSlot {
+    /// Creates an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Scheduler for LifoSlot {
+    fn schedule(&mut self, task: Runnable) {
+        if let Some(prev) = self.slot.replace(task) {
+            self.queue.push_back(prev);
+        }
+    }
+
+    fn next(&mut self) -> Option<Runnable> {
+        if self.slot_polls < MAX_SLOT_POLLS || self.queue.is_empty() {
+            if let Some(task) = self.slot.take() {
+                self.slot_polls += 1;
+                return Some(task);
+            }
+        }
+        self.slot_polls = 0;
+        self.queue.pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len() + usize::from(self.slot.is_some())
+    }
+}
@@ -0,0 +1,142 @@
+//! Run queue policies for the [`Executor`](super::Executor).
+//!
+//! Whenever a task is woken the executor hands it to its [`Scheduler`], and
+//! whenever it is ready to poll a task it asks the scheduler for the next
+//! one. Which woken task runs next is therefore entirely up to the
+//! scheduler. The crate ships five policies:
+//!
+//! - [`Prioritized`], the default, keeps one FIFO queue per
+//!   [`Priority`](crate::task::Priority) and ages less urgent classes so that
+//!   they are not starved.
+//! - [`Fifo`] polls tasks in the order they were woken.
+//! - [`LifoSlot`] polls the most recently woken task next, which keeps
+//!   message passing between two tasks cache-hot, and falls back to FIFO.
+//! - [`Random`] polls a random woken task, which is handy for shaking out
+//!   code that depends on a particular polling order.
+//! - [`Edf`] polls the woken task with the earliest
+//!   [deadline](crate::task::Builder::deadline) first.
+//!
+//! An executor with a different policy is built with
+//! [`Builder::build_with_scheduler`](super::Builder::build_with_scheduler):
+//!
+//! ```
+//! use mini_tokio::executor::{scheduler::Fifo, Builder};
+//!
+//! let executor = Builder::new_current_thread()
+//!     .build_with_scheduler(Fifo::new())
+//!     .unwrap();
+//! let handle = executor.spawn(async { 1 + 1 });
+//! assert_eq!(executor.block_on(handle).unwrap(), 2);
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use super::task::Task;
+use crate::{task::Priority, time::Instant};
+
+mod edf;
+mod fifo;
+mod lifo_slot;
+mod prioritized;
+mod random;
+
+pub use edf::Edf;
+pub use fifo::Fifo;
+pub use lifo_slot::LifoSlot;
+pub use prioritized::Prioritized;
+pub use random::Random;
+
+/// Decides which woken task an executor polls next.
+///
+/// A task is handed to [`schedule`](Scheduler::schedule) at most once until
+/// it is returned from [`next`](Scheduler::next) again, so a scheduler never
+/// sees duplicates. On a multi-threaded executor the scheduler is shared by
+/// the worker threads behind a lock.
+///
+/// A scheduler that polls the most recently woken task first:
+///
+/// ```
+/// use mini_tokio::executor::{
+///     scheduler::{Runnable, Scheduler},
+///     Builder,
+/// };
+///
+/// #[derive(Default)]
+/// struct Stack(Vec<Runnable>);
+///
+/// impl Scheduler for Stack {
+///     fn schedule(&mut self, task: Runnable) {
+///         self.0.push(task);
+///     }
+///
+///     fn next(&mut self) -> Option<Runnable> {
+///         self.0.pop()
+///     }
+///
+///     fn len(&self) -> usize {
+///         self.0.len()
+///     }
+/// }
+///
+/// let executor = Builder::new_current_thread()
+///     .build_with_scheduler(Stack::default())
+///     .unwrap();
+/// assert_eq!(executor.block_on(async { 7 }), 7);
+/// ```
+pub trait Scheduler: Send + 'static {
+    /// Queues a task that was woken
+    fn schedule(&mut self, task: Runnable);
+
+    /// Removes and returns the task to poll next
+    fn next(&mut self) -> Option<Runnable>;
+
+    /// Returns the number of queued tasks
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no task is queued
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A woken task waiting in a [`Scheduler`].
+pub struct Runnable(pub(super) Arc<Task>);
+
+impl Runnable {
+    /// Returns the task's ID, unique within the executor
+    pub fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    /// Returns the name given with [`task::Builder::name`](crate::task::Builder::name)
+    pub fn name(&self) -> Option<&str> {
+        self.0.name.as_deref()
+    }
+
+    /// Returns the task's priority class
+    pub fn priority(&self) -> Priority {
+        self.0.priority
+    }
+
+    /// Returns the deadline given with
+    /// [`task::Builder::deadline`](crate::task::Builder::deadline)
+    pub fn deadline(&self) -> Option<Instant> {
+        self.0.deadline
+    }
+
+    /// Polls the task once
+    pub(super) fn run(self) {
+        self.0.run();
+    }
+}
+
+impl fmt::Debug for Runnable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runnable")
+            .field("id", &self.id())
+            .field("name", &self.name())
+            .field("priority", &self.priority())
+            .field("deadline", &self.deadline())
+            .finish()
+    }
+}
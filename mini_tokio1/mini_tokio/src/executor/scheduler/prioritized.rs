@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+
+use super::{Runnable, Scheduler};
+use crate::task::Priority;
+
+/// How many times in a row a class with queued tasks can be passed over for
+/// more urgent ones before it gets a turn.
+const AGING_LIMIT: u32 = 8;
+
+/// Keeps one FIFO queue per [`Priority`] and polls the most urgent class
+/// first.
+///
+/// To keep a steady stream of urgent tasks from starving the rest, every
+/// pick that skips over a non-empty class ages it, and a class that has been
+/// skipped several times in a row is served next. This is the default policy.
+#[derive(Debug, Default)]
+pub struct Prioritized {
+    queues: [VecDeque<Runnable>; Priority::COUNT],
+    /// Consecutive picks that passed over each class while it had tasks
+    skipped: [u32; Priority::COUNT],
+}
+
+impl Prioritized {
+    /// Creates an empty queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Scheduler for Prioritized {
+    fn schedule(&mut self, task: Runnable) {
+        self.queues[task.priority().index()].push_back(task);
+    }
+
+    fn next(&mut self) -> Option<Runnable> {
+        let ready = |class: &usize| !self.queues[*class].is_empty();
+        // The least urgent aged class goes first, so that aging `Normal`
+        // cannot hold back an aged `Background`.
+        let class = (0..Priority::COUNT)
+            .rev()
+            .filter(ready)
+            .find(|&class| self.skipped[class] >= AGING_LIMIT)
+            .or_else(|| (0..Priority::COUNT).find(ready))?;
+
+        for lower in class + 1..Priority::COUNT {
+            if !self.queues[lower].is_empty() {
+                self.skipped[lower] += 1;
+            }
+        }
+        self.skipped[class] = 0;
+        self.queues[class].pop_front()
+    }
+
+    fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+}
@@ -0,0 +1,56 @@
+use super::{Runnable, Scheduler};
+use crate::rand::FastRand;
+
+/// Polls a woken task picked at random.
+///
+/// Code that only works for one polling order, such as a test that assumes
+/// spawned tasks run in spawn order, tends to fail quickly under this policy.
+/// [`Random::with_seed`] makes a failing order reproducible.
+#[derive(Debug)]
+pub struct Random {
+    tasks: Vec<Runnable>,
+    rng: FastRand,
+}
+
+impl Random {
+    /// Creates an empty queue with a random seed
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            rng: FastRand::new(),
+        }
+    }
+
+    /// Creates an empty queue that picks tasks in an order determined by
+    /// `seed`
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            tasks: Vec::new(),
+            rng: FastRand::with_seed(seed),
+        }
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheduler for Random {
+    fn schedule(&mut self, task: Runnable) {
+        self.tasks.push(task);
+    }
+
+    fn next(&mut self) -> Option<Runnable> {
+        if self.tasks.is_empty() {
+            return None;
+        }
+        let index = self.rng.next_n(self.tasks.len());
+        Some(self.tasks.swap_remove(index))
+    }
+
+    fn len(&self) -> usize {
+        self.tasks.len()
+    }
+}
@@ -0,0 +1,266 @@
+//! Sequence diagrams of real executions.
+//!
+//! An executor built with
+//! [`Builder::trace_sequence`](super::Builder::trace_sequence) records every
+//! spawn, every poll and whether it returned `Pending` or `Ready`, every waker
+//! handed to the timer or I/O driver and every wakeup, as messages between
+//! the executor, the `block_on` future, each task and the drivers.
+//! [`Executor::sequence_trace`](super::Executor::sequence_trace) returns what
+//! has been recorded so far, and [`SequenceTrace::to_svg`] draws it as a
+//! sequence diagram with one lifeline per task.
+//!
+//! Only wakers handed to the executor's own timer and I/O drivers show up as
+//! registrations. A future that keeps its waker elsewhere, such as a channel,
+//! is only seen once the waker is called.
+//!
+//! ```
+//! use mini_tokio::executor::{sequence::StepKind, Builder};
+//!
+//! let executor = Builder::new_current_thread()
+//!     .trace_sequence(true)
+//!     .build()
+//!     .unwrap();
+//! executor.block_on(async {
+//!     mini_tokio::task::Builder::new()
+//!         .name("sleeper")
+//!         .spawn(mini_tokio::delay(1))
+//!         .await
+//!         .unwrap();
+//! });
+//!
+//! let trace = executor.sequence_trace().unwrap();
+//! assert!(trace.steps().iter().any(|step| step.kind() == StepKind::Register));
+//! std::fs::write(std::env::temp_dir().join("sequence.svg"), trace.to_svg()).unwrap();
+//! ```
+
+use std::{collections::HashMap, fmt, sync::Mutex, time::Duration};
+
+use super::{
+    replay::Target,
+    task::{self, Running},
+};
+use crate::time;
+
+mod svg;
+
+/// The steps recorded by an executor, in the order they happened.
+#[derive(Debug, Clone)]
+pub struct SequenceTrace {
+    steps: Vec<Step>,
+    names: HashMap<u64, String>,
+}
+
+/// A message from one participant to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    at: Duration,
+    from: Participant,
+    to: Participant,
+    kind: StepKind,
+}
+
+/// What a [`Step`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    /// `from` spawned the task `to`
+    Spawn,
+    /// The executor polled `to`
+    Poll,
+    /// A poll of `from` returned `Pending`
+    Pending,
+    /// A poll of `from` returned `Ready`
+    Ready,
+    /// `from` handed its waker to the timer or I/O driver `to`
+    Register,
+    /// `from` called the waker of `to`
+    Wake,
+}
+
+/// A lifeline in the diagram.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Participant {
+    /// The executor, which polls everything else
+    Executor,
+    /// The future passed to `block_on`
+    Main,
+    /// A spawned task, by ID
+    Task(u64),
+    /// The timer driver
+    Timer,
+    /// The I/O driver
+    Io,
+    /// A thread outside the executor, by name
+    Thread(String),
+}
+
+impl SequenceTrace {
+    /// Returns the recorded steps
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns every participant, the executor first and the others in the
+    /// order they first appear
+    pub fn participants(&self) -> Vec<Participant> {
+        let mut participants = vec![Participant::Executor];
+        for step in &self.steps {
+            for participant in [&step.from, &step.to] {
+                if !participants.contains(participant) {
+                    participants.push(participant.clone());
+                }
+            }
+        }
+        participants
+    }
+
+    /// Returns the name given to a task with
+    /// [`task::Builder::name`](crate::task::Builder::name)
+    pub fn task_name(&self, id: u64) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    /// Draws the trace as an SVG sequence diagram
+    pub fn to_svg(&self) -> String {
+        svg::render(self)
+    }
+
+    /// Returns the heading of a participant's lifeline
+    fn label(&self, participant: &Participant) -> String {
+        match participant {
+            Participant::Task(id) => match self.task_name(*id) {
+                Some(name) => format!("{participant} {name:?}"),
+                None => participant.to_string(),
+            },
+            _ => participant.to_string(),
+        }
+    }
+}
+
+impl Step {
+    /// Returns when the step happened, measured on the executor's clock from
+    /// when the executor was built
+    pub fn at(&self) -> Duration {
+        self.at
+    }
+
+    /// Returns the participant the message comes from
+    pub fn from(&self) -> &Participant {
+        &self.from
+    }
+
+    /// Returns the participant the message goes to
+    pub fn to(&self) -> &Participant {
+        &self.to
+    }
+
+    /// Returns what the step stands for
+    pub fn kind(&self) -> StepKind {
+        self.kind
+    }
+}
+
+/// Collects steps while the executor runs.
+pub(super) struct Recorder {
+    time: time::driver::Handle,
+    origin: time::Instant,
+    steps: Mutex<Vec<Step>>,
+    names: Mutex<HashMap<u64, String>>,
+}
+
+impl Recorder {
+    pub(super) fn new(time: time::driver::Handle) -> Self {
+        let origin = time.clock().now();
+        Self {
+            time,
+            origin,
+            steps: Mutex::new(Vec::new()),
+            names: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(super) fn spawned(&self, id: u64, name: Option<&str>) {
+        if let Some(name) = name {
+            self.names.lock().unwrap().insert(id, name.to_string());
+        }
+        self.push(current(), Participant::Task(id), StepKind::Spawn);
+    }
+
+    pub(super) fn poll_started(&self, target: Target) {
+        self.push(Participant::Executor, target.into(), StepKind::Poll);
+    }
+
+    pub(super) fn poll_finished(&self, target: Target, ready: bool) {
+        let kind = if ready {
+            StepKind::Ready
+        } else {
+            StepKind::Pending
+        };
+        self.push(target.into(), Participant::Executor, kind);
+    }
+
+    pub(super) fn woken(&self, target: Target) {
+        self.push(current(), target.into(), StepKind::Wake);
+    }
+
+    pub(super) fn registered(&self, driver: Participant) {
+        self.push(current(), driver, StepKind::Register);
+    }
+
+    pub(super) fn snapshot(&self) -> SequenceTrace {
+        SequenceTrace {
+            steps: self.steps.lock().unwrap().clone(),
+            names: self.names.lock().unwrap().clone(),
+        }
+    }
+
+    fn push(&self, from: Participant, to: Participant, kind: StepKind) {
+        let at = self.time.clock().now().duration_since(self.origin);
+        self.steps.lock().unwrap().push(Step { at, from, to, kind });
+    }
+}
+
+/// Returns the participant running on this thread
+fn current() -> Participant {
+    match task::running() {
+        Some(Running::Task(id)) => Participant::Task(id),
+        Some(Running::BlockOn) => Participant::Main,
+        Some(Running::IoDriver) => Participant::Io,
+        Some(Running::Timer) => Participant::Timer,
+        None => Participant::Thread(task::thread_name()),
+    }
+}
+
+impl From<Target> for Participant {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::Main => Participant::Main,
+            Target::Task(id) => Participant::Task(id),
+        }
+    }
+}
+
+impl fmt::Display for StepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StepKind::Spawn => "spawn",
+            StepKind::Poll => "poll()",
+            StepKind::Pending => "Pending",
+            StepKind::Ready => "Ready",
+            StepKind::Register => "register waker",
+            StepKind::Wake => "wake()",
+        })
+    }
+}
+
+impl fmt::Display for Participant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Participant::Executor => f.write_str("executor"),
+            Participant::Main => f.write_str("block_on"),
+            Participant::Task(id) => write!(f, "task {id}"),
+            Participant::Timer => f.write_str("timer"),
+            Participant::Io => f.write_str("I/O driver"),
+            Participant::Thread(name) => write!(f, "thread {name}"),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Drawing a [`SequenceTrace`] as SVG.
+
+use std::fmt::Write;
+
+use super::{Participant, SequenceTrace, StepKind};
+
+/// Width of the gutter holding the time of each step
+const GUTTER: usize = 70;
+const COLUMN: usize = 150;
+const HEADER: usize = 50;
+const ROW: usize = 28;
+
+pub(super) fn render(trace: &SequenceTrace) -> String {
+    let participants = trace.participants();
+    let column = |participant: &Participant| {
+        let index = participants.iter().position(|p| p == participant).unwrap();
+        GUTTER + index * COLUMN + COLUMN / 2
+    };
+    let row = |index: usize| HEADER + (index + 1) * ROW;
+    let width = GUTTER + participants.len() * COLUMN;
+    let height = row(trace.steps.len()) + ROW;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="sans-serif" font-size="12">
+<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="context-stroke"/></marker></defs>
+<rect width="100%" height="100%" fill="#fff"/>
+"##
+    );
+
+    // Lifelines run from the heading to the participant's last step.
+    for participant in &participants {
+        let x = column(participant);
+        let last = trace
+            .steps
+            .iter()
+            .rposition(|step| step.from == *participant || step.to == *participant)
+            .map_or(HEADER, row);
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{}" y="10" width="{}" height="26" rx="4" fill="#f0f0f0" stroke="#000"/><text x="{x}" y="28" text-anchor="middle">{}</text><line x1="{x}" y1="36" x2="{x}" y2="{}" stroke="#999" stroke-dasharray="4 3"/>"##,
+            x - COLUMN / 2 + 8,
+            COLUMN - 16,
+            escape(&trace.label(participant)),
+            last + ROW / 2,
+        );
+    }
+
+    // A bar on the lifeline of whatever is being polled, from the poll to
+    // its result.
+    let mut polled = Vec::new();
+    for (index, step) in trace.steps.iter().enumerate() {
+        match step.kind {
+            StepKind::Poll => polled.push((step.to.clone(), index)),
+            StepKind::Pending | StepKind::Ready => {
+                if let Some(open) = polled.iter().rposition(|(p, _)| *p == step.from) {
+                    let (participant, start) = polled.remove(open);
+                    let _ = writeln!(
+                        svg,
+                        r##"<rect x="{}" y="{}" width="10" height="{}" fill="#dde8ff" stroke="#000"/>"##,
+                        column(&participant) - 5,
+                        row(start),
+                        row(index) - row(start),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (index, step) in trace.steps.iter().enumerate() {
+        let y = row(index);
+        let (from, to) = (column(&step.from), column(&step.to));
+        let (color, dash) = style(step.kind);
+        let _ = writeln!(
+            svg,
+            r##"<text x="{}" y="{}" text-anchor="end" fill="#666">{:.1}ms</text>"##,
+            GUTTER - 6,
+            y + 4,
+            step.at.as_secs_f64() * 1000.0,
+        );
+        if from == to {
+            // A participant sending itself a message, like a task waking
+            // itself to yield.
+            let _ = writeln!(
+                svg,
+                r##"<path d="M{from},{} h30 v12 h-30" fill="none" stroke="{color}"{dash} marker-end="url(#arrow)"/><text x="{}" y="{}">{}</text>"##,
+                y - 6,
+                from + 34,
+                y + 4,
+                step.kind,
+            );
+        } else {
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{from}" y1="{y}" x2="{to}" y2="{y}" stroke="{color}"{dash} marker-end="url(#arrow)"/><text x="{}" y="{}" text-anchor="middle" fill="{color}">{}</text>"##,
+                (from + to) / 2,
+                y - 4,
+                step.kind,
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn style(kind: StepKind) -> (&'static str, &'static str) {
+    match kind {
+        StepKind::Spawn => ("#000", r#" stroke-dasharray="6 3""#),
+        StepKind::Poll => ("#000", ""),
+        StepKind::Pending => ("#888", r#" stroke-dasharray="3 3""#),
+        StepKind::Ready => ("#080", r#" stroke-dasharray="3 3""#),
+        StepKind::Register => ("#06c", ""),
+        StepKind::Wake => ("#c60", ""),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,315 @@
+use std::{
+    cell::Cell,
+    fmt,
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Wake, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{replay::Target, Schedule};
+use crate::{
+    task::Priority,
+    time::{self, driver::TimerKey},
+};
+
+pub(crate) type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A spawned future together with its place in the run queue.
+pub(crate) struct Task {
+    pub(super) id: u64,
+    pub(super) name: Option<String>,
+    pub(super) priority: Priority,
+    pub(super) deadline: Option<time::Instant>,
+    /// The timer that reports a missed deadline, cancelled on completion
+    pub(super) deadline_timer: Mutex<Option<TimerKey>>,
+    pub(super) location: &'static Location<'static>,
+    future: Mutex<Option<BoxFuture>>,
+    /// Set while the task sits in the run queue, so that repeated wakeups
+    /// queue it only once
+    queued: AtomicBool,
+    stats: Mutex<TaskStats>,
+    shared: Weak<dyn Schedule>,
+}
+
+struct TaskStats {
+    polls: u64,
+    busy: Duration,
+    running: bool,
+    /// When the task was spawned or last finished a poll
+    idle_since: Instant,
+    last_wake: Option<Instant>,
+    last_woken_by: Option<WokenBy>,
+}
+
+/// What a task is doing at the moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// Waiting to be woken
+    Idle,
+    /// Woken and waiting in the run queue
+    Scheduled,
+    /// Being polled
+    Running,
+}
+
+/// Who called a task's waker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WokenBy {
+    /// Another task, while it was being polled
+    Task(u64),
+    /// The future passed to `block_on`
+    BlockOn,
+    /// The I/O driver, after epoll reported readiness
+    IoDriver,
+    /// The timer driver, after a deadline passed
+    Timer,
+    /// A thread outside the executor
+    Thread(String),
+}
+
+/// A point-in-time view of a live task.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskInfo {
+    pub(crate) id: u64,
+    pub(crate) name: Option<String>,
+    pub(crate) priority: Priority,
+    pub(crate) location: &'static Location<'static>,
+    pub(crate) state: TaskState,
+    pub(crate) polls: u64,
+    pub(crate) busy: Duration,
+    /// Time since the task was spawned or last polled, zero while running
+    pub(crate) idle: Duration,
+    #[cfg_attr(not(feature = "console"), allow(dead_code))]
+    pub(crate) since_last_wake: Option<Duration>,
+    #[cfg_attr(not(feature = "console"), allow(dead_code))]
+    pub(crate) last_woken_by: Option<WokenBy>,
+}
+
+thread_local! {
+    /// What the executor is running on this thread, used to attribute wakeups.
+    static RUNNING: Cell<Option<Running>> = const { Cell::new(None) };
+}
+
+/// The work an executor thread can be doing while a waker is called.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Running {
+    Task(u64),
+    BlockOn,
+    IoDriver,
+    Timer,
+}
+
+/// Restores what was running before [`enter`] when dropped.
+pub(super) struct RunningGuard {
+    prev: Option<Running>,
+}
+
+/// Records that `running` is executing on this thread until the guard is dropped
+pub(super) fn enter(running: Running) -> RunningGuard {
+    RunningGuard {
+        prev: RUNNING.with(|cell| cell.replace(Some(running))),
+    }
+}
+
+/// Returns what the executor is running on this thread, if anything
+pub(super) fn running() -> Option<Running> {
+    RUNNING.with(Cell::get)
+}
+
+/// Returns the current thread's name, or its ID if it has none
+pub(super) fn thread_name() -> String {
+    let thread = thread::current();
+    match thread.name() {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", thread.id()),
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.with(|cell| cell.set(self.prev));
+    }
+}
+
+impl Task {
+    pub(super) fn new(
+        id: u64,
+        builder: &crate::task::Builder,
+        location: &'static Location<'static>,
+        future: BoxFuture,
+        shared: Weak<dyn Schedule>,
+    ) -> Arc<Self> {
+        Arc::new(Task {
+            id,
+            name: builder.name.clone(),
+            priority: builder.priority,
+            deadline: builder.deadline,
+            deadline_timer: Mutex::new(None),
+            location,
+            future: Mutex::new(Some(future)),
+            queued: AtomicBool::new(false),
+            stats: Mutex::new(TaskStats {
+                polls: 0,
+                busy: Duration::ZERO,
+                running: false,
+                idle_since: Instant::now(),
+                last_wake: None,
+                last_woken_by: None,
+            }),
+            shared,
+        })
+    }
+
+    /// Pushes the task onto the run queue unless it is already there
+    pub(super) fn schedule(self: &Arc<Self>) {
+        if self.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Some(shared) = self.shared.upgrade() {
+            shared.schedule(Arc::clone(self));
+        }
+    }
+
+    /// Polls the task once, releasing it if it completes
+    pub(super) fn run(self: Arc<Self>) {
+        // Cleared before polling so that a wakeup during the poll queues the
+        // task again.
+        self.queued.store(false, Ordering::Release);
+
+        let waker = Waker::from(Arc::clone(&self));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut slot = self.future.lock().unwrap();
+        let Some(future) = slot.as_mut() else {
+            return;
+        };
+        let shared = self.shared.upgrade();
+
+        self.stats.lock().unwrap().running = true;
+        let start = Instant::now();
+        if let Some(shared) = &shared {
+            shared.poll_started(Target::Task(self.id));
+        }
+        let poll = {
+            let _running = enter(Running::Task(self.id));
+            let _watch = shared.as_ref().and_then(|shared| shared.watch_poll(&self));
+            future.as_mut().poll(&mut cx)
+        };
+        if let Some(shared) = &shared {
+            shared.poll_finished(Target::Task(self.id), poll.is_ready());
+        }
+        {
+            let mut stats = self.stats.lock().unwrap();
+            let now = Instant::now();
+            stats.running = false;
+            stats.polls += 1;
+            stats.busy += now - start;
+            stats.idle_since = now;
+        }
+
+        if poll.is_ready() {
+            let future = slot.take();
+            drop(slot);
+            drop(future);
+            if let Some(shared) = shared {
+                shared.release(&self);
+            }
+        }
+    }
+
+    /// Drops the future without polling it again
+    pub(super) fn shutdown(&self) {
+        let future = self.future.lock().unwrap().take();
+        drop(future);
+    }
+
+    pub(super) fn info(&self) -> TaskInfo {
+        let stats = self.stats.lock().unwrap();
+        let state = if stats.running {
+            TaskState::Running
+        } else if self.queued.load(Ordering::Acquire) {
+            TaskState::Scheduled
+        } else {
+            TaskState::Idle
+        };
+        TaskInfo {
+            id: self.id,
+            name: self.name.clone(),
+            priority: self.priority,
+            location: self.location,
+            state,
+            polls: stats.polls,
+            busy: stats.busy,
+            idle: if stats.running {
+                Duration::ZERO
+            } else {
+                stats.idle_since.elapsed()
+            },
+            since_last_wake: stats.last_wake.map(|t| t.elapsed()),
+            last_woken_by: stats.last_woken_by.clone(),
+        }
+    }
+
+    /// Reports that the task's deadline passed before it completed
+    pub(super) fn deadline_missed(&self) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.deadline_missed(self);
+        }
+    }
+
+    fn record_wake(&self) {
+        let woken_by = match running() {
+            Some(Running::Task(id)) => WokenBy::Task(id),
+            Some(Running::BlockOn) => WokenBy::BlockOn,
+            Some(Running::IoDriver) => WokenBy::IoDriver,
+            Some(Running::Timer) => WokenBy::Timer,
+            None => WokenBy::Thread(thread_name()),
+        };
+        let mut stats = self.stats.lock().unwrap();
+        stats.last_wake = Some(Instant::now());
+        stats.last_woken_by = Some(woken_by);
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.record_wake();
+        if let Some(shared) = self.shared.upgrade() {
+            shared.woken(Target::Task(self.id));
+        }
+        self.schedule();
+    }
+}
+
+impl fmt::Display for TaskState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TaskState::Idle => "idle",
+            TaskState::Scheduled => "scheduled",
+            TaskState::Running => "running",
+        })
+    }
+}
+
+impl fmt::Display for WokenBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WokenBy::Task(id) => write!(f, "task {id}"),
+            WokenBy::BlockOn => f.write_str("block_on"),
+            WokenBy::IoDriver => f.write_str("io driver"),
+            WokenBy::Timer => f.write_str("timer"),
+            WokenBy::Thread(name) => write!(f, "thread {name}"),
+        }
+    }
+}
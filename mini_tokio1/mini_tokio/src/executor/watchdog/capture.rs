@@ -0,0 +1,196 @@
+//! Captures the backtrace of another thread.
+//!
+//! The watchdog sends the thread a signal whose handler walks its own stack,
+//! storing the raw instruction pointers in static buffers. Symbols are then
+//! resolved on the watchdog thread, since that allocates and takes locks,
+//! which a signal handler must not do. Walking the stack takes locks too, so
+//! it can deadlock the interrupted thread, which is why capturing is opt-in.
+
+use std::{
+    ffi::c_void,
+    fmt::Write,
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+const MAX_FRAMES: usize = 128;
+
+/// How long to wait for the signalled thread to walk its stack.
+const CAPTURE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// No capture is waiting for frames
+const IDLE: usize = 0;
+/// A handler is filling the buffers
+const WRITING: usize = usize::MAX;
+/// A handler has filled the buffers
+const WRITTEN: usize = usize::MAX - 1;
+
+static FRAMES: [AtomicUsize; MAX_FRAMES] = [const { AtomicUsize::new(0) }; MAX_FRAMES];
+static FRAME_COUNT: AtomicUsize = AtomicUsize::new(0);
+/// The generation of the capture whose handler may fill the buffers next,
+/// or one of [`IDLE`], [`WRITING`] and [`WRITTEN`]
+static REQUESTED: AtomicUsize = AtomicUsize::new(IDLE);
+static NEXT_GENERATION: AtomicUsize = AtomicUsize::new(1);
+/// Held for a whole capture, since there is only one set of buffers
+static CAPTURE: Mutex<()> = Mutex::new(());
+/// Whether the signal handler was installed
+static INSTALLED: OnceLock<bool> = OnceLock::new();
+
+fn signal() -> libc::c_int {
+    libc::SIGRTMIN() + 1
+}
+
+/// Returns the instruction pointers of `thread`'s stack, innermost first, or
+/// `None` if they could not be captured in time.
+///
+/// `keep_alive` is called right before signalling the thread. It returns a
+/// guard that keeps the thread alive until dropped, or `None` if the thread
+/// may be gone, in which case it is not signalled.
+pub(super) fn frames<G>(
+    thread: libc::pthread_t,
+    keep_alive: impl FnOnce() -> Option<G>,
+) -> Option<Vec<usize>> {
+    if !*INSTALLED.get_or_init(install) {
+        return None;
+    }
+
+    let _capture = CAPTURE.lock().unwrap_or_else(|e| e.into_inner());
+    let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+    // Fails while the handler of a timed out capture is still writing.
+    let request =
+        |from| REQUESTED.compare_exchange(from, generation, Ordering::AcqRel, Ordering::Acquire);
+    if request(IDLE).or_else(|_| request(WRITTEN)).is_err() {
+        return None;
+    }
+
+    let sent = keep_alive().is_some_and(|_alive| {
+        let value = libc::sigval {
+            sival_ptr: generation as *mut c_void,
+        };
+        // SAFETY: `_alive` keeps the thread from exiting, and the handler
+        // for `signal()` has been installed above.
+        unsafe { libc::pthread_sigqueue(thread, signal(), value) == 0 }
+    });
+    if !sent {
+        REQUESTED.store(IDLE, Ordering::Release);
+        return None;
+    }
+
+    let start = Instant::now();
+    while REQUESTED.load(Ordering::Acquire) != WRITTEN {
+        if start.elapsed() > CAPTURE_TIMEOUT {
+            // A handler running late must not fill the buffers, unless it
+            // has started already.
+            let _ =
+                REQUESTED.compare_exchange(generation, IDLE, Ordering::AcqRel, Ordering::Acquire);
+            return None;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Only the handler of this capture can have claimed the buffers, and
+    // none writes to them until the next capture requests frames.
+    let count = FRAME_COUNT.load(Ordering::Relaxed);
+    let frames = FRAMES[..count]
+        .iter()
+        .map(|ip| ip.load(Ordering::Relaxed))
+        .collect();
+    Some(frames)
+}
+
+fn install() -> bool {
+    // SAFETY: `sigaction` is plain data for which all zeroes is a valid
+    // value, and `handle` has the signature `sa_sigaction` expects with
+    // `SA_SIGINFO`.
+    unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handle as Handler as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(signal(), &action, std::ptr::null_mut()) == 0
+    }
+}
+
+type Handler = extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut c_void);
+
+extern "C" fn handle(_: libc::c_int, info: *mut libc::siginfo_t, _: *mut c_void) {
+    // SAFETY: the kernel passes a valid `siginfo_t`, whose value is the one
+    // `frames` queued the signal with.
+    let generation = unsafe { (*info).si_value().sival_ptr as usize };
+    // Only the handler of the capture still waiting may write, so a late
+    // one cannot overwrite frames that a later capture is reading.
+    if REQUESTED
+        .compare_exchange(generation, WRITING, Ordering::AcqRel, Ordering::Relaxed)
+        .is_err()
+    {
+        return;
+    }
+
+    // SAFETY: reading and restoring errno is always sound. The stack walk
+    // is not async-signal-safe, though: the unwinder takes the dynamic
+    // loader's lock to find the unwind tables, and may allocate the first
+    // time it meets a module. If the interrupted thread holds either lock,
+    // the walk deadlocks and the thread never returns to its poll. That is
+    // the price of opting into backtraces; the buffers themselves are only
+    // written by the one handler that claimed them above.
+    unsafe {
+        let errno = *libc::__errno_location();
+        let mut count = 0;
+        backtrace::trace_unsynchronized(|frame| {
+            FRAMES[count].store(frame.ip() as usize, Ordering::Relaxed);
+            count += 1;
+            count < MAX_FRAMES
+        });
+        FRAME_COUNT.store(count, Ordering::Relaxed);
+        *libc::__errno_location() = errno;
+    }
+    REQUESTED.store(WRITTEN, Ordering::Release);
+}
+
+/// Resolves `frames` into lines like those of `std::backtrace::Backtrace`,
+/// leaving out the signal handler's own frames
+pub(super) fn format(frames: &[usize]) -> String {
+    let mut resolved = Vec::new();
+    for &ip in frames {
+        let mut symbols = Vec::new();
+        backtrace::resolve(ip as *mut c_void, |symbol| {
+            let name = symbol
+                .name()
+                .map_or_else(|| format!("{ip:#x}"), |name| format!("{name:#}"));
+            let location = symbol
+                .filename()
+                .map(|file| format!("{}:{}", file.display(), symbol.lineno().unwrap_or(0)));
+            symbols.push((name, location));
+        });
+        if symbols.is_empty() {
+            symbols.push((format!("{ip:#x}"), None));
+        }
+        resolved.push(symbols);
+    }
+
+    // The handler's frames come first, followed by the signal trampoline.
+    let skip = resolved
+        .iter()
+        .position(|symbols| {
+            symbols
+                .iter()
+                .any(|(name, _)| name.contains("watchdog::capture::handle"))
+        })
+        .map_or(0, |handler| handler + 2);
+
+    let mut out = String::new();
+    for (i, symbols) in resolved[skip..].iter().enumerate() {
+        for (name, location) in symbols {
+            let _ = writeln!(out, "{i:4}: {name}");
+            if let Some(location) = location {
+                let _ = writeln!(out, "             at {location}");
+            }
+        }
+    }
+    out
+}
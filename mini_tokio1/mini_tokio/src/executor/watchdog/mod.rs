@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    fmt, io,
+    panic::Location,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+use super::{hook::Hook, task::Task};
+
+mod capture;
+
+/// A poll that ran for longer than the watchdog's threshold, reported to the
+/// hook set with [`Builder::on_long_poll`](super::Builder::on_long_poll).
+///
+/// Its `Display` implementation describes the task and, if one was
+/// captured, includes the backtrace.
+#[derive(Debug, Clone)]
+pub struct LongPoll {
+    id: u64,
+    name: Option<String>,
+    location: &'static Location<'static>,
+    elapsed: Duration,
+    backtrace: Option<String>,
+}
+
+impl LongPoll {
+    /// Returns the task's ID, unique within the executor
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the name given with [`task::Builder::name`](crate::task::Builder::name)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns where the task was spawned
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns how long the poll had been running when the watchdog noticed
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the backtrace of the thread running the poll, if the watchdog
+    /// was asked to capture one and managed to
+    pub fn backtrace(&self) -> Option<&str> {
+        self.backtrace.as_deref()
+    }
+}
+
+impl fmt::Display for LongPoll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {}", self.id)?;
+        if let Some(name) = &self.name {
+            write!(f, " {name:?}")?;
+        }
+        write!(
+            f,
+            " spawned at {} has been in a single poll for {:?}; \
+             is it blocking the executor thread?",
+            self.location, self.elapsed
+        )?;
+        if let Some(backtrace) = &self.backtrace {
+            write!(f, "\n{backtrace}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the watchdog was configured on the [`Builder`](super::Builder).
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub(crate) threshold: Duration,
+    pub(crate) backtrace: bool,
+    pub(crate) hook: Option<Hook<LongPoll>>,
+}
+
+/// The polls in progress on the executor's threads, shared with the
+/// watchdog thread.
+pub(crate) struct State {
+    config: Config,
+    active: Mutex<HashMap<ThreadId, ActivePoll>>,
+    next_poll: AtomicU64,
+    pub(super) long_polls: AtomicU64,
+}
+
+struct ActivePoll {
+    /// Tells this poll apart from later polls on the same thread
+    poll: u64,
+    task: Arc<Task>,
+    started: Instant,
+    thread: libc::pthread_t,
+    reported: bool,
+}
+
+/// Marks the end of a poll when dropped.
+pub(crate) struct PollGuard<'a> {
+    state: &'a State,
+}
+
+impl State {
+    pub(crate) fn new(config: Config) -> Self {
+        Self {
+            config,
+            active: Mutex::new(HashMap::new()),
+            next_poll: AtomicU64::new(0),
+            long_polls: AtomicU64::new(0),
+        }
+    }
+
+    /// Records that `task` is being polled on this thread until the guard is
+    /// dropped
+    pub(crate) fn watch(&self, task: &Arc<Task>) -> PollGuard<'_> {
+        let poll = ActivePoll {
+            poll: self.next_poll.fetch_add(1, Ordering::Relaxed),
+            task: Arc::clone(task),
+            started: Instant::now(),
+            // SAFETY: `pthread_self` has no preconditions.
+            thread: unsafe { libc::pthread_self() },
+            reported: false,
+        };
+        let id = thread::current().id();
+        self.active.lock().unwrap().insert(id, poll);
+        PollGuard { state: self }
+    }
+
+    /// Reports every poll that has just gone over the threshold
+    fn check(&self) {
+        let overdue: Vec<_> = {
+            let mut active = self.active.lock().unwrap();
+            active
+                .iter_mut()
+                .filter(|(_, poll)| {
+                    !poll.reported && poll.started.elapsed() >= self.config.threshold
+                })
+                .map(|(&id, poll)| {
+                    poll.reported = true;
+                    (
+                        id,
+                        poll.poll,
+                        Arc::clone(&poll.task),
+                        poll.started,
+                        poll.thread,
+                    )
+                })
+                .collect()
+        };
+
+        for (thread_id, poll, task, started, thread) in overdue {
+            let mut frames = None;
+            if self.config.backtrace {
+                let still_polling = |active: &HashMap<ThreadId, ActivePoll>| {
+                    active
+                        .get(&thread_id)
+                        .is_some_and(|active| active.poll == poll)
+                };
+                // The thread cannot leave the poll, let alone exit, while the
+                // lock is held, so it is only signalled while polling.
+                frames = capture::frames(thread, || {
+                    let active = self.active.lock().unwrap();
+                    still_polling(&active).then_some(active)
+                });
+                // A stack walked after the poll ended shows something else.
+                if !still_polling(&self.active.lock().unwrap()) {
+                    frames = None;
+                }
+            }
+            // Resolving symbols is slow, so it happens after the check above.
+            let backtrace = frames.map(|frames| capture::format(&frames));
+
+            let report = LongPoll {
+                id: task.id,
+                name: task.name.clone(),
+                location: task.location,
+                elapsed: started.elapsed(),
+                backtrace,
+            };
+            self.long_polls.fetch_add(1, Ordering::Relaxed);
+            match &self.config.hook {
+                Some(hook) => hook.call(&report),
+                None => eprintln!("mini_tokio watchdog: {report}"),
+            }
+        }
+    }
+}
+
+impl Drop for PollGuard<'_> {
+    fn drop(&mut self) {
+        let id = thread::current().id();
+        self.state.active.lock().unwrap().remove(&id);
+    }
+}
+
+/// The watchdog thread, stopped and joined when dropped.
+pub(crate) struct Watchdog {
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+/// Starts a thread that checks the polls in `state` against its threshold
+pub(crate) fn spawn(state: Arc<State>) -> io::Result<Watchdog> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = Arc::clone(&stop);
+        // Checking a few times per threshold keeps the reported durations
+        // close to the threshold.
+        let interval = (state.config.threshold / 4).max(Duration::from_millis(1));
+        thread::Builder::new()
+            .name("mini-tokio-watchdog".into())
+            .spawn(move || {
+                while !stop.load(Ordering::Acquire) {
+                    thread::park_timeout(interval);
+                    state.check();
+                }
+            })?
+    };
+    Ok(Watchdog {
+        stop,
+        thread: Some(thread),
+    })
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
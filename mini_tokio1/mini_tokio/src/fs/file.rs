@@ -0,0 +1,412 @@
+use std::{
+    fmt,
+    fs::{Metadata, Permissions},
+    future::poll_fn,
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use super::{asyncify, blocking_failed, OpenOptions};
+use crate::{
+    io::{AsyncRead, AsyncSeek, AsyncWrite},
+    task::spawn_blocking,
+    time::sleep,
+    JoinHandle,
+};
+
+/// Most bytes a single read or write hands to the blocking pool
+const MAX_BUF: usize = 2 * 1024 * 1024;
+
+/// An open file, the asynchronous counterpart of [`std::fs::File`].
+///
+/// Reads, writes and seeks run on the blocking pool one at a time, through
+/// a buffer owned by the file:
+///
+/// - A write copies the bytes into the buffer and returns at once, while the
+///   blocking pool writes them out. An error from that write is returned by
+///   the next operation on the file, so call
+///   [`flush`](crate::io::AsyncWriteExt::flush) to learn whether the data
+///   made it before dropping the file.
+/// - A read fills the buffer with up to as many bytes as asked for, and
+///   later reads are served from it until it runs out.
+///
+/// Writing or seeking while read data is still buffered moves the cursor
+/// back over the unread bytes first, so the cursor always ends up where a
+/// [`std::fs::File`] would have it.
+///
+/// ```
+/// use mini_tokio::{
+///     fs::File,
+///     io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+///     Executor,
+/// };
+///
+/// let path = std::env::temp_dir().join(format!("mini-tokio-doc-file-{}", std::process::id()));
+/// let executor = Executor::new();
+/// let contents = executor.block_on(async {
+///     let mut file = File::options()
+///         .read(true)
+///         .write(true)
+///         .create(true)
+///         .truncate(true)
+///         .open(&path)
+///         .await?;
+///     file.write_all(b"hello world").await?;
+///     file.rewind().await?;
+///     let mut contents = String::new();
+///     file.read_to_string(&mut contents).await?;
+///     std::io::Result::Ok(contents)
+/// });
+/// assert_eq!(contents.unwrap(), "hello world");
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct File {
+    std: Arc<std::fs::File>,
+    state: State,
+}
+
+enum State {
+    Idle(Buf),
+    Busy(JoinHandle<(Operation, Buf)>),
+}
+
+/// A blocking operation and its result
+enum Operation {
+    Read(io::Result<usize>),
+    Write(io::Result<()>),
+    /// Also holds the position the caller asked for
+    Seek(SeekFrom, io::Result<u64>),
+}
+
+/// Bytes read but not yet returned, or written but not yet flushed.
+#[derive(Default)]
+struct Buf {
+    bytes: Vec<u8>,
+    /// How much of `bytes` has been returned by reads
+    pos: usize,
+}
+
+impl File {
+    /// Opens the file at `path` for reading
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<File> {
+        OpenOptions::new().read(true).open(path).await
+    }
+
+    /// Opens the file at `path` for writing, creating it if needed and
+    /// emptying it otherwise
+    pub async fn create(path: impl AsRef<Path>) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .await
+    }
+
+    /// Returns a blank set of [`OpenOptions`]
+    pub fn options() -> OpenOptions {
+        OpenOptions::new()
+    }
+
+    /// Wraps an open [`std::fs::File`]
+    pub fn from_std(std: std::fs::File) -> File {
+        File {
+            std: Arc::new(std),
+            state: State::Idle(Buf::default()),
+        }
+    }
+
+    /// Waits for the operation in flight and returns the underlying
+    /// [`std::fs::File`].
+    ///
+    /// If a dropped future, such as that of [`metadata`](File::metadata),
+    /// still has the file on the blocking pool, a duplicate of its
+    /// descriptor is returned instead.
+    ///
+    /// Errors of buffered writes are lost; call
+    /// [`flush`](crate::io::AsyncWriteExt::flush) first to see them.
+    pub async fn into_std(mut self) -> std::fs::File {
+        let _ = poll_fn(|cx| self.poll_complete(cx)).await;
+        let mut std = self.std;
+        loop {
+            std = match Arc::try_unwrap(std) {
+                Ok(std) => return std,
+                Err(std) => match std.try_clone() {
+                    Ok(clone) => return clone,
+                    // Out of descriptors, so wait for the pool to let go.
+                    Err(_) => {
+                        sleep(Duration::from_millis(1)).await;
+                        std
+                    }
+                },
+            };
+        }
+    }
+
+    /// Returns the file's metadata
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        let std = Arc::clone(&self.std);
+        asyncify(move || std.metadata()).await
+    }
+
+    /// Changes the file's permissions
+    pub async fn set_permissions(&self, perm: Permissions) -> io::Result<()> {
+        let std = Arc::clone(&self.std);
+        asyncify(move || std.set_permissions(perm)).await
+    }
+
+    /// Writes out buffered data, then waits until the file's contents and
+    /// metadata have reached the disk
+    pub async fn sync_all(&mut self) -> io::Result<()> {
+        poll_fn(|cx| self.poll_flush_inner(cx)).await?;
+        let std = Arc::clone(&self.std);
+        asyncify(move || std.sync_all()).await
+    }
+
+    /// Writes out buffered data, then waits until the file's contents have
+    /// reached the disk
+    pub async fn sync_data(&mut self) -> io::Result<()> {
+        poll_fn(|cx| self.poll_flush_inner(cx)).await?;
+        let std = Arc::clone(&self.std);
+        asyncify(move || std.sync_data()).await
+    }
+
+    /// Truncates or extends the file to `size` bytes, leaving the cursor
+    /// where it is
+    pub async fn set_len(&mut self, size: u64) -> io::Result<()> {
+        poll_fn(|cx| self.poll_flush_inner(cx)).await?;
+        let State::Idle(buf) = &mut self.state else {
+            unreachable!("flushing leaves the file idle");
+        };
+        let unread = buf.discard_unread();
+        let std = Arc::clone(&self.std);
+        asyncify(move || {
+            if unread != 0 {
+                (&*std).seek(SeekFrom::Current(unread))?;
+            }
+            std.set_len(size)
+        })
+        .await
+    }
+
+    /// Waits for the operation in flight, if any, and returns it.
+    ///
+    /// A failed write is returned as the error, since its `poll_write` has
+    /// already reported it as done.
+    fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<Operation>>> {
+        let State::Busy(handle) = &mut self.state else {
+            return Poll::Ready(Ok(None));
+        };
+        let res = ready!(handle.poll(cx));
+        match res {
+            Ok((Operation::Write(Err(err)), buf)) => {
+                self.state = State::Idle(buf);
+                Poll::Ready(Err(err))
+            }
+            Ok((op, buf)) => {
+                self.state = State::Idle(buf);
+                Poll::Ready(Ok(Some(op)))
+            }
+            Err(cancelled) => {
+                self.state = State::Idle(Buf::default());
+                Poll::Ready(Err(blocking_failed(cancelled)))
+            }
+        }
+    }
+
+    /// Waits for buffered data to be written out
+    fn poll_flush_inner(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.poll_complete(cx))?;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Runs `f` on the blocking pool with the buffer, leaving the file busy
+    fn spawn<F>(&mut self, mut buf: Buf, f: F)
+    where
+        F: FnOnce(&std::fs::File, &mut Buf) -> Operation + Send + 'static,
+    {
+        let std = Arc::clone(&self.std);
+        self.state = State::Busy(spawn_blocking(move || {
+            let op = f(&std, &mut buf);
+            (op, buf)
+        }));
+    }
+}
+
+impl AsyncRead for File {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        dst: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let me = self.get_mut();
+        loop {
+            match &mut me.state {
+                State::Idle(buf) => {
+                    if !buf.is_empty() || dst.is_empty() {
+                        return Poll::Ready(Ok(buf.copy_to(dst)));
+                    }
+                    let buf = mem::take(buf);
+                    let len = dst.len().min(MAX_BUF);
+                    me.spawn(buf, move |std, buf| {
+                        Operation::Read(buf.read_from(std, len))
+                    });
+                }
+                // Otherwise reading carries on from wherever the cursor
+                // ended up.
+                State::Busy(_) => {
+                    if let Some(Operation::Read(res)) = ready!(me.poll_complete(cx))? {
+                        res?;
+                        let State::Idle(buf) = &mut me.state else {
+                            unreachable!("a completed operation leaves the file idle");
+                        };
+                        // Zero bytes read means end of file.
+                        return Poll::Ready(Ok(buf.copy_to(dst)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AsyncWrite for File {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        src: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let me = self.get_mut();
+        loop {
+            match &mut me.state {
+                State::Idle(buf) => {
+                    let unread = buf.discard_unread();
+                    let n = buf.copy_from(src);
+                    let buf = mem::take(buf);
+                    me.spawn(buf, move |mut std, buf| {
+                        let mut res = Ok(());
+                        if unread != 0 {
+                            res = std.seek(SeekFrom::Current(unread)).map(drop);
+                        }
+                        Operation::Write(res.and_then(|()| buf.write_to(std)))
+                    });
+                    return Poll::Ready(Ok(n));
+                }
+                State::Busy(_) => {
+                    ready!(me.poll_complete(cx))?;
+                }
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_inner(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_inner(cx)
+    }
+}
+
+impl AsyncSeek for File {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        let me = self.get_mut();
+        loop {
+            match &mut me.state {
+                State::Idle(buf) => {
+                    // The cursor is past the unread bytes, which a relative
+                    // seek must not count.
+                    let unread = buf.discard_unread();
+                    let target = match pos {
+                        SeekFrom::Current(offset) => SeekFrom::Current(offset + unread),
+                        pos => pos,
+                    };
+                    let buf = mem::take(buf);
+                    me.spawn(buf, move |mut std, _| {
+                        Operation::Seek(pos, std.seek(target))
+                    });
+                }
+                State::Busy(_) => {
+                    // A seek whose future was dropped leaves its result
+                    // behind; a seek elsewhere must not return it.
+                    if let Some(Operation::Seek(requested, res)) = ready!(me.poll_complete(cx))? {
+                        if requested == pos {
+                            return Poll::Ready(res);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("File")
+            .field("std", &self.std)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Buf {
+    fn is_empty(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    /// Copies unread bytes to `dst`, returning how many
+    fn copy_to(&mut self, dst: &mut [u8]) -> usize {
+        let n = dst.len().min(self.bytes.len() - self.pos);
+        dst[..n].copy_from_slice(&self.bytes[self.pos..self.pos + n]);
+        self.pos += n;
+        if self.is_empty() {
+            self.clear();
+        }
+        n
+    }
+
+    /// Copies as much of `src` as one write may carry, returning how much
+    fn copy_from(&mut self, src: &[u8]) -> usize {
+        let n = src.len().min(MAX_BUF);
+        self.bytes.extend_from_slice(&src[..n]);
+        n
+    }
+
+    /// Drops unread bytes, returning how far the cursor must move to get
+    /// back to the first of them
+    fn discard_unread(&mut self) -> i64 {
+        let unread = self.bytes.len() - self.pos;
+        self.clear();
+        -(unread as i64)
+    }
+
+    fn clear(&mut self) {
+        self.bytes.clear();
+        self.pos = 0;
+    }
+
+    fn read_from(&mut self, mut std: &std::fs::File, len: usize) -> io::Result<usize> {
+        self.bytes.resize(len, 0);
+        let res = loop {
+            match std.read(&mut self.bytes) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                res => break res,
+            }
+        };
+        self.bytes.truncate(*res.as_ref().unwrap_or(&0));
+        res
+    }
+
+    fn write_to(&mut self, mut std: &std::fs::File) -> io::Result<()> {
+        let res = std.write_all(&self.bytes);
+        self.clear();
+        res
+    }
+}
@@ -0,0 +1,150 @@
+//! Asynchronous file system access.
+//!
+//! The functions here mirror those of [`std::fs`], and [`File`] implements
+//! [`AsyncRead`](crate::io::AsyncRead), [`AsyncWrite`](crate::io::AsyncWrite)
+//! and [`AsyncSeek`](crate::io::AsyncSeek). Operating systems offer no
+//! readiness notifications for regular files, so every call runs on the
+//! executor's blocking thread pool (see [`spawn_blocking`]) while the calling
+//! task waits without holding up the others.
+//!
+//! [`watch`] reports changes to files and directories as they happen,
+//! through inotify.
+//!
+//! ```
+//! use mini_tokio::{fs, Executor};
+//!
+//! let dir = std::env::temp_dir().join(format!("mini-tokio-doc-fs-{}", std::process::id()));
+//! let executor = Executor::new();
+//! executor.block_on(async {
+//!     fs::create_dir_all(&dir).await?;
+//!     fs::write(dir.join("hello.txt"), "hello").await?;
+//!     assert_eq!(fs::read_to_string(dir.join("hello.txt")).await?, "hello");
+//!
+//!     let mut entries = fs::read_dir(&dir).await?;
+//!     while let Some(entry) = entries.next_entry().await? {
+//!         assert_eq!(entry.file_name(), "hello.txt");
+//!     }
+//!     fs::remove_dir_all(&dir).await
+//! })
+//! .unwrap();
+//! ```
+
+use std::{
+    fs::{Metadata, Permissions},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{task::spawn_blocking, Cancelled};
+
+mod file;
+mod open_options;
+mod read_dir;
+mod watch;
+
+pub use file::File;
+pub use open_options::OpenOptions;
+pub use read_dir::{read_dir, DirEntry, ReadDir};
+pub use watch::{watch, WatchEvent, WatchEventKind, Watcher};
+
+/// Reads the whole file at `path`
+pub async fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::read(path)).await
+}
+
+/// Reads the whole file at `path` into a string
+pub async fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::read_to_string(path)).await
+}
+
+/// Writes `contents` to the file at `path`, creating it if needed and
+/// replacing what it held otherwise
+pub async fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    let contents = contents.as_ref().to_owned();
+    asyncify(move || std::fs::write(path, contents)).await
+}
+
+/// Returns the metadata of the file or directory at `path`, following
+/// symbolic links
+pub async fn metadata(path: impl AsRef<Path>) -> io::Result<Metadata> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::metadata(path)).await
+}
+
+/// Returns the metadata of the file, directory or symbolic link at `path`
+pub async fn symlink_metadata(path: impl AsRef<Path>) -> io::Result<Metadata> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::symlink_metadata(path)).await
+}
+
+/// Changes the permissions of the file or directory at `path`
+pub async fn set_permissions(path: impl AsRef<Path>, perm: Permissions) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::set_permissions(path, perm)).await
+}
+
+/// Returns the absolute form of `path` with all symbolic links resolved
+pub async fn canonicalize(path: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::canonicalize(path)).await
+}
+
+/// Creates a directory, whose parent must exist
+pub async fn create_dir(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::create_dir(path)).await
+}
+
+/// Creates a directory along with any missing parents
+pub async fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::create_dir_all(path)).await
+}
+
+/// Removes a file
+pub async fn remove_file(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::remove_file(path)).await
+}
+
+/// Removes an empty directory
+pub async fn remove_dir(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::remove_dir(path)).await
+}
+
+/// Removes a directory and everything in it
+pub async fn remove_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref().to_owned();
+    asyncify(move || std::fs::remove_dir_all(path)).await
+}
+
+/// Renames a file or directory, replacing `to` if it exists
+pub async fn rename(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()> {
+    let (from, to) = (from.as_ref().to_owned(), to.as_ref().to_owned());
+    asyncify(move || std::fs::rename(from, to)).await
+}
+
+/// Copies the contents and permissions of one file to another, returning
+/// the number of bytes copied
+pub async fn copy(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<u64> {
+    let (from, to) = (from.as_ref().to_owned(), to.as_ref().to_owned());
+    asyncify(move || std::fs::copy(from, to)).await
+}
+
+/// Runs the blocking file system call `f` on the blocking pool
+pub(crate) async fn asyncify<F, T>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_blocking(f).await.map_err(blocking_failed)?
+}
+
+/// The error for a blocking call whose result was lost
+fn blocking_failed(_: Cancelled) -> io::Error {
+    io::Error::other("the blocking file operation panicked or its executor shut down")
+}
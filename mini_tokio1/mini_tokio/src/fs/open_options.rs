@@ -0,0 +1,85 @@
+use std::{io, os::unix::fs::OpenOptionsExt, path::Path};
+
+use super::{asyncify, File};
+
+/// Options for opening a [`File`], like [`std::fs::OpenOptions`].
+///
+/// ```no_run
+/// # async fn doc() -> std::io::Result<()> {
+/// use mini_tokio::fs::OpenOptions;
+///
+/// let log = OpenOptions::new().append(true).create(true).open("app.log").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenOptions(std::fs::OpenOptions);
+
+impl OpenOptions {
+    /// Starts with every option off
+    pub fn new() -> Self {
+        Self(std::fs::OpenOptions::new())
+    }
+
+    /// Opens the file for reading
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.0.read(read);
+        self
+    }
+
+    /// Opens the file for writing
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.0.write(write);
+        self
+    }
+
+    /// Opens the file for writing at its end
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.0.append(append);
+        self
+    }
+
+    /// Empties the file if it exists
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.0.truncate(truncate);
+        self
+    }
+
+    /// Creates the file if it does not exist
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.0.create(create);
+        self
+    }
+
+    /// Creates the file, failing if it exists
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.0.create_new(create_new);
+        self
+    }
+
+    /// Sets the permission bits of a newly created file
+    pub fn mode(&mut self, mode: u32) -> &mut Self {
+        self.0.mode(mode);
+        self
+    }
+
+    /// Opens the file at `path` with these options
+    pub async fn open(&self, path: impl AsRef<Path>) -> io::Result<File> {
+        let path = path.as_ref().to_owned();
+        let options = self.0.clone();
+        let std = asyncify(move || options.open(path)).await?;
+        Ok(File::from_std(std))
+    }
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<std::fs::OpenOptions> for OpenOptions {
+    fn from(options: std::fs::OpenOptions) -> Self {
+        Self(options)
+    }
+}
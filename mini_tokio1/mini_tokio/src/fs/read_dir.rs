@@ -0,0 +1,136 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    fmt,
+    fs::{FileType, Metadata},
+    future::poll_fn,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
+use super::{asyncify, blocking_failed};
+use crate::{task::spawn_blocking, JoinHandle};
+
+/// How many entries one trip to the blocking pool reads
+const BATCH: usize = 32;
+
+/// Returns the entries of the directory at `path`, in no particular order
+pub async fn read_dir(path: impl AsRef<Path>) -> io::Result<ReadDir> {
+    let path = path.as_ref().to_owned();
+    let std = asyncify(move || std::fs::read_dir(path)).await?;
+    Ok(ReadDir {
+        state: State::Idle {
+            entries: VecDeque::new(),
+            std: Some(std),
+        },
+    })
+}
+
+/// The entries of a directory, returned by [`read_dir`].
+///
+/// Entries are read in batches on the blocking pool. Besides
+/// [`next_entry`](ReadDir::next_entry), `ReadDir` is a [`Stream`] of entries.
+///
+/// [`Stream`]: futures::Stream
+pub struct ReadDir {
+    state: State,
+}
+
+enum State {
+    Idle {
+        entries: VecDeque<io::Result<DirEntry>>,
+        /// `None` once the directory has been read to the end
+        std: Option<std::fs::ReadDir>,
+    },
+    Reading(JoinHandle<Batch>),
+}
+
+type Batch = (VecDeque<io::Result<DirEntry>>, Option<std::fs::ReadDir>);
+
+impl ReadDir {
+    /// Returns the next entry, or `None` once there are no more
+    pub async fn next_entry(&mut self) -> io::Result<Option<DirEntry>> {
+        poll_fn(|cx| self.poll_next_entry(cx)).await
+    }
+
+    /// Polls for the next entry, or `None` once there are no more
+    pub fn poll_next_entry(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<DirEntry>>> {
+        loop {
+            match &mut self.state {
+                State::Idle { entries, std } => {
+                    if let Some(entry) = entries.pop_front() {
+                        return Poll::Ready(entry.map(Some));
+                    }
+                    let Some(mut std) = std.take() else {
+                        return Poll::Ready(Ok(None));
+                    };
+                    self.state = State::Reading(spawn_blocking(move || {
+                        let entries: VecDeque<_> = std
+                            .by_ref()
+                            .take(BATCH)
+                            .map(|entry| entry.map(|entry| DirEntry(Arc::new(entry))))
+                            .collect();
+                        let more = entries.len() == BATCH;
+                        (entries, more.then_some(std))
+                    }));
+                }
+                State::Reading(handle) => match ready!(handle.poll(cx)) {
+                    Ok((entries, std)) => self.state = State::Idle { entries, std },
+                    Err(cancelled) => {
+                        self.state = State::Idle {
+                            entries: VecDeque::new(),
+                            std: None,
+                        };
+                        return Poll::Ready(Err(blocking_failed(cancelled)));
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ReadDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadDir").finish_non_exhaustive()
+    }
+}
+
+impl futures::Stream for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_entry(cx).map(Result::transpose)
+    }
+}
+
+/// An entry of a directory, returned by [`ReadDir`].
+#[derive(Debug, Clone)]
+pub struct DirEntry(Arc<std::fs::DirEntry>);
+
+impl DirEntry {
+    /// Returns the entry's full path, the directory's path joined with the
+    /// entry's name
+    pub fn path(&self) -> PathBuf {
+        self.0.path()
+    }
+
+    /// Returns the entry's name within its directory
+    pub fn file_name(&self) -> OsString {
+        self.0.file_name()
+    }
+
+    /// Returns the entry's metadata, without following symbolic links
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        let entry = Arc::clone(&self.0);
+        asyncify(move || entry.metadata()).await
+    }
+
+    /// Returns the entry's type, without following symbolic links
+    pub async fn file_type(&self) -> io::Result<FileType> {
+        let entry = Arc::clone(&self.0);
+        asyncify(move || entry.file_type()).await
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::{CString, OsStr},
+    fmt,
+    fs::File,
+    future::poll_fn,
+    io::{self, Read},
+    mem,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Path, PathBuf},
+    pin::Pin,
+    ptr,
+    task::{ready, Context, Poll},
+};
+
+use crate::io::{
+    driver::{cvt, Direction},
+    PollEvented,
+};
+
+/// The inotify events a watch subscribes to
+const MASK: u32 = libc::IN_CREATE
+    | libc::IN_MODIFY
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF;
+
+/// Room for many events; a single one takes at most 16 bytes plus a
+/// file name
+const READ_BUF: usize = 4096;
+
+/// Watches the file or directory at `path` for changes.
+///
+/// A watched directory reports changes to the entries directly inside it,
+/// not to those further down. More paths can be added to the same
+/// [`Watcher`] with [`Watcher::add`].
+///
+/// The watcher is an inotify descriptor registered with the executor's I/O
+/// driver, so waiting for events takes no thread of its own.
+///
+/// Fails outside of an executor and if `path` does not exist.
+///
+/// ```
+/// use mini_tokio::{fs, Executor};
+///
+/// let dir = std::env::temp_dir().join(format!("mini-tokio-doc-watch-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+///
+/// let executor = Executor::new();
+/// executor.block_on(async {
+///     let mut watcher = fs::watch(&dir).unwrap();
+///     fs::write(dir.join("config.toml"), "").await.unwrap();
+///
+///     let event = watcher.recv().await.unwrap().unwrap();
+///     assert_eq!(event.kind(), &fs::WatchEventKind::Create);
+///     assert_eq!(event.path(), dir.join("config.toml"));
+/// });
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn watch(path: impl AsRef<Path>) -> io::Result<Watcher> {
+    // SAFETY: `inotify_init1` takes no pointers.
+    let fd = cvt(unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) })?;
+    // SAFETY: `fd` was just opened and nothing else owns it.
+    let inotify = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+    let mut watcher = Watcher {
+        inotify: PollEvented::new(inotify)?,
+        watches: HashMap::new(),
+        events: VecDeque::new(),
+    };
+    watcher.add(path)?;
+    Ok(watcher)
+}
+
+/// Changes to watched paths, returned by [`watch`].
+///
+/// Besides [`recv`](Watcher::recv), `Watcher` is a [`Stream`] of events. It
+/// ends once none of its paths are watched any more, which happens when they
+/// are deleted.
+///
+/// [`Stream`]: futures::Stream
+pub struct Watcher {
+    inotify: PollEvented<File>,
+    /// The watched paths by watch descriptor
+    watches: HashMap<libc::c_int, PathBuf>,
+    /// Events read but not yet returned
+    events: VecDeque<io::Result<WatchEvent>>,
+}
+
+/// A change to a watched path, or to an entry of a watched directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchEvent {
+    kind: WatchEventKind,
+    path: PathBuf,
+}
+
+/// What happened to the path of a [`WatchEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEventKind {
+    /// The path was created, or moved in from an unwatched directory
+    Create,
+    /// The file's contents were written to
+    Modify,
+    /// The path was deleted, or moved out to an unwatched directory
+    Delete,
+    /// The path is the new name of `from`
+    Rename { from: PathBuf },
+}
+
+impl WatchEvent {
+    /// Returns what happened
+    pub fn kind(&self) -> &WatchEventKind {
+        &self.kind
+    }
+
+    /// Returns the path that changed, the watched path joined with the
+    /// entry's name for entries of a watched directory
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Watcher {
+    /// Watches another file or directory
+    pub fn add(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a nul byte"))?;
+        // SAFETY: `c_path` is a valid C string that outlives the call.
+        let wd = cvt(unsafe {
+            libc::inotify_add_watch(self.inotify.as_raw_fd(), c_path.as_ptr(), MASK)
+        })?;
+        // Watching a path twice reuses its descriptor.
+        self.watches.insert(wd, path.to_owned());
+        Ok(())
+    }
+
+    /// Waits for the next event, or returns `None` once nothing is watched.
+    ///
+    /// Fails if the kernel's event queue overflowed, in which case events
+    /// were lost; the watcher keeps working afterwards.
+    pub async fn recv(&mut self) -> Option<io::Result<WatchEvent>> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next event, or `None` once nothing is watched
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<io::Result<WatchEvent>>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Poll::Ready(Some(event));
+            }
+            if self.watches.is_empty() {
+                return Poll::Ready(None);
+            }
+            let mut buf = [0; READ_BUF];
+            let read = self
+                .inotify
+                .registration()
+                .poll_io(cx, Direction::Read, || (&*self.inotify).read(&mut buf));
+            match ready!(read) {
+                Ok(n) => self.parse(&buf[..n]),
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+
+    /// Turns the raw events in `buf` into queued [`WatchEvent`]s.
+    ///
+    /// A rename within the watched directories shows up as a moved-from
+    /// event directly followed by a moved-to event with the same cookie,
+    /// which are merged. A moved-from event without a partner is a move out
+    /// of the watched directories.
+    fn parse(&mut self, mut buf: &[u8]) {
+        const HEADER: usize = mem::size_of::<libc::inotify_event>();
+        let mut moved_from: Option<(u32, PathBuf)> = None;
+
+        while buf.len() >= HEADER {
+            // SAFETY: the kernel only returns whole events, each a header
+            // followed by its name. `buf` has no alignment guarantees.
+            let raw = unsafe { ptr::read_unaligned(buf.as_ptr().cast::<libc::inotify_event>()) };
+            let (name, rest) = buf[HEADER..].split_at(raw.len as usize);
+            buf = rest;
+            // Names are padded with nul bytes.
+            let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+
+            if raw.mask & libc::IN_Q_OVERFLOW != 0 {
+                self.events.push_back(Err(io::Error::other(
+                    "the inotify event queue overflowed and events were lost",
+                )));
+                continue;
+            }
+            if raw.mask & libc::IN_IGNORED != 0 {
+                self.watches.remove(&raw.wd);
+                continue;
+            }
+            let Some(watched) = self.watches.get(&raw.wd) else {
+                continue;
+            };
+            let path = if name.is_empty() {
+                watched.clone()
+            } else {
+                watched.join(OsStr::from_bytes(name))
+            };
+
+            if let Some((cookie, from)) = moved_from.take() {
+                if raw.mask & libc::IN_MOVED_TO != 0 && raw.cookie == cookie {
+                    self.push(WatchEventKind::Rename { from }, path);
+                    continue;
+                }
+                self.push(WatchEventKind::Delete, from);
+            }
+            let kind = if raw.mask & libc::IN_MOVED_FROM != 0 {
+                moved_from = Some((raw.cookie, path));
+                continue;
+            } else if raw.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                WatchEventKind::Create
+            } else if raw.mask & (libc::IN_DELETE | libc::IN_DELETE_SELF) != 0 {
+                WatchEventKind::Delete
+            } else if raw.mask & libc::IN_MODIFY != 0 {
+                WatchEventKind::Modify
+            } else {
+                continue;
+            };
+            self.push(kind, path);
+        }
+
+        if let Some((_, from)) = moved_from {
+            self.push(WatchEventKind::Delete, from);
+        }
+    }
+
+    fn push(&mut self, kind: WatchEventKind, path: PathBuf) {
+        self.events.push_back(Ok(WatchEvent { kind, path }));
+    }
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("paths", &self.watches.values().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl futures::Stream for Watcher {
+    type Item = io::Result<WatchEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_recv(cx)
+    }
+}
@@ -0,0 +1,421 @@
+use std::{
+    collections::HashMap,
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{context, executor::sequence::Participant};
+
+const EVENTS_CAPACITY: usize = 1024;
+
+/// The epoll token of the eventfd that interrupts a blocked turn. Tokens of
+/// registered resources count up from 0 and never get this far.
+const WAKE_TOKEN: u64 = u64::MAX;
+
+/// The I/O reactor.
+///
+/// Wraps an epoll instance. Every registered file descriptor is tracked by a
+/// [`ScheduledIo`] that records its readiness and the wakers of the tasks
+/// waiting on it. Turning the driver collects events from epoll and wakes
+/// those tasks. An eventfd lets other threads interrupt a turn that blocks.
+pub(crate) struct Driver {
+    handle: Handle,
+    events: Vec<libc::epoll_event>,
+}
+
+/// A cheap, cloneable reference to the reactor used to register I/O resources.
+#[derive(Clone)]
+pub(crate) struct Handle {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    epoll: OwnedFd,
+    /// Written to by [`Handle::wake`]
+    wake: OwnedFd,
+    resources: Mutex<HashMap<u64, Arc<ScheduledIo>>>,
+    next_token: AtomicU64,
+}
+
+/// The direction a task is waiting in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Read,
+    Write,
+}
+
+/// Readiness observed for a resource, tagged with the tick it was observed at.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReadyEvent {
+    tick: u64,
+}
+
+const READABLE: u8 = 0b0001;
+const WRITABLE: u8 = 0b0010;
+const READ_CLOSED: u8 = 0b0100;
+const WRITE_CLOSED: u8 = 0b1000;
+
+/// Per-resource readiness state shared between the reactor and the resource.
+struct ScheduledIo {
+    state: Mutex<IoState>,
+}
+
+#[derive(Default)]
+struct IoState {
+    readiness: u8,
+    tick: u64,
+    reader: Option<Waker>,
+    writer: Option<Waker>,
+}
+
+impl Direction {
+    fn mask(self) -> u8 {
+        match self {
+            Direction::Read => READABLE | READ_CLOSED,
+            Direction::Write => WRITABLE | WRITE_CLOSED,
+        }
+    }
+}
+
+impl Driver {
+    /// Creates a new epoll instance
+    pub(crate) fn new() -> io::Result<Self> {
+        let fd = cvt(unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) })?;
+        // SAFETY: `epoll_create1` returned a fresh descriptor that nothing else owns.
+        let epoll = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let fd = cvt(unsafe { libc::eventfd(0, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) })?;
+        // SAFETY: `eventfd` returned a fresh descriptor that nothing else owns.
+        let wake = unsafe { OwnedFd::from_raw_fd(fd) };
+        let mut event = libc::epoll_event {
+            events: (libc::EPOLLIN | libc::EPOLLET) as u32,
+            u64: WAKE_TOKEN,
+        };
+        cvt(unsafe {
+            libc::epoll_ctl(
+                epoll.as_raw_fd(),
+                libc::EPOLL_CTL_ADD,
+                wake.as_raw_fd(),
+                &mut event,
+            )
+        })?;
+
+        Ok(Self {
+            handle: Handle {
+                inner: Arc::new(Inner {
+                    epoll,
+                    wake,
+                    resources: Mutex::new(HashMap::new()),
+                    next_token: AtomicU64::new(0),
+                }),
+            },
+            events: Vec::with_capacity(EVENTS_CAPACITY),
+        })
+    }
+
+    pub(crate) fn handle(&self) -> Handle {
+        self.handle.clone()
+    }
+
+    /// Waits up to `timeout` for I/O events and wakes the tasks interested in them.
+    ///
+    /// A `timeout` of `None` blocks until at least one event arrives.
+    pub(crate) fn turn(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        // Rounded up, so that a timer due in less than a millisecond is not
+        // polled for in a busy loop.
+        let timeout_ms = match timeout {
+            Some(timeout) => timeout
+                .as_nanos()
+                .div_ceil(1_000_000)
+                .try_into()
+                .unwrap_or(libc::c_int::MAX),
+            None => -1,
+        };
+
+        self.events.clear();
+        let inner = &self.handle.inner;
+        let n = unsafe {
+            libc::epoll_wait(
+                inner.epoll.as_raw_fd(),
+                self.events.as_mut_ptr(),
+                EVENTS_CAPACITY as libc::c_int,
+                timeout_ms,
+            )
+        };
+        let n = match cvt(n) {
+            Ok(n) => n as usize,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        // SAFETY: epoll_wait initialised the first `n` entries of the buffer.
+        unsafe { self.events.set_len(n) };
+
+        let resources = inner.resources.lock().unwrap();
+        for event in &self.events {
+            let token = event.u64;
+            if token == WAKE_TOKEN {
+                inner.drain_wake();
+                continue;
+            }
+            let flags = event.events as libc::c_int;
+            if let Some(io) = resources.get(&token) {
+                io.set_readiness(flags);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether any I/O resources are registered, which a blocked
+    /// turn would wait on
+    pub(crate) fn has_registrations(&self) -> bool {
+        !self.handle.inner.resources.lock().unwrap().is_empty()
+    }
+}
+
+impl Inner {
+    /// Resets the eventfd, so that the next [`Handle::wake`] is a new event
+    fn drain_wake(&self) {
+        let mut count = [0u8; 8];
+        // SAFETY: the buffer is valid for writes of its length. A counter
+        // that is already zero fails with `EAGAIN`, which is fine.
+        unsafe { libc::read(self.wake.as_raw_fd(), count.as_mut_ptr().cast(), 8) };
+    }
+}
+
+impl Handle {
+    /// Makes a turn of the driver that is blocked, or the next one, return
+    /// early. Can be called from any thread.
+    pub(crate) fn wake(&self) {
+        let one = 1u64.to_ne_bytes();
+        // SAFETY: the buffer is valid for reads of its length. The write only
+        // fails if the counter is about to overflow, in which case it is
+        // readable anyway.
+        unsafe { libc::write(self.inner.wake.as_raw_fd(), one.as_ptr().cast(), 8) };
+    }
+
+    /// Registers `fd` with epoll for both read and write readiness.
+    pub(crate) fn register(&self, fd: RawFd) -> io::Result<Registration> {
+        let token = self.inner.next_token.fetch_add(1, Ordering::Relaxed);
+        let shared = Arc::new(ScheduledIo {
+            state: Mutex::new(IoState::default()),
+        });
+        self.inner
+            .resources
+            .lock()
+            .unwrap()
+            .insert(token, Arc::clone(&shared));
+
+        let mut event = libc::epoll_event {
+            events: (libc::EPOLLIN | libc::EPOLLOUT | libc::EPOLLRDHUP | libc::EPOLLET) as u32,
+            u64: token,
+        };
+        let res = cvt(unsafe {
+            libc::epoll_ctl(
+                self.inner.epoll.as_raw_fd(),
+                libc::EPOLL_CTL_ADD,
+                fd,
+                &mut event,
+            )
+        });
+        if let Err(e) = res {
+            self.inner.resources.lock().unwrap().remove(&token);
+            return Err(e);
+        }
+
+        Ok(Registration {
+            handle: self.clone(),
+            token,
+            fd,
+            shared,
+        })
+    }
+}
+
+/// Associates an I/O resource with the reactor.
+///
+/// Dropping the registration removes the descriptor from epoll, so it must be
+/// dropped before the descriptor itself is closed.
+pub(crate) struct Registration {
+    handle: Handle,
+    token: u64,
+    fd: RawFd,
+    shared: Arc<ScheduledIo>,
+}
+
+impl Registration {
+    /// Polls for readiness in `direction`, registering the task's waker if
+    /// the resource is not ready yet.
+    pub(crate) fn poll_ready(
+        &self,
+        cx: &mut Context<'_>,
+        direction: Direction,
+    ) -> Poll<io::Result<ReadyEvent>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.readiness & direction.mask() != 0 {
+            return Poll::Ready(Ok(ReadyEvent { tick: state.tick }));
+        }
+
+        let slot = match direction {
+            Direction::Read => &mut state.reader,
+            Direction::Write => &mut state.writer,
+        };
+        match slot {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => *slot = Some(cx.waker().clone()),
+        }
+        drop(state);
+        context::waker_registered(Participant::Io);
+        Poll::Pending
+    }
+
+    /// Clears readiness in `direction` after an operation returned `WouldBlock`.
+    ///
+    /// Readiness delivered by the reactor after `event` was observed is kept,
+    /// so an edge that raced with the failed operation is not lost.
+    pub(crate) fn clear_readiness(&self, event: ReadyEvent, direction: Direction) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.tick == event.tick {
+            state.readiness &= !(direction.mask() & (READABLE | WRITABLE));
+        }
+    }
+
+    /// Runs `f` once the resource is ready in `direction`, retrying whenever it
+    /// reports `WouldBlock`.
+    pub(crate) fn poll_io<R>(
+        &self,
+        cx: &mut Context<'_>,
+        direction: Direction,
+        mut f: impl FnMut() -> io::Result<R>,
+    ) -> Poll<io::Result<R>> {
+        loop {
+            let event = match self.poll_ready(cx, direction) {
+                Poll::Ready(Ok(event)) => event,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match f() {
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.clear_readiness(event, direction);
+                }
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+
+    /// Attempts `f` without waiting, clearing readiness if it would block.
+    pub(crate) fn try_io<R>(
+        &self,
+        direction: Direction,
+        f: impl FnOnce() -> io::Result<R>,
+    ) -> io::Result<R> {
+        let event = {
+            let state = self.shared.state.lock().unwrap();
+            if state.readiness & direction.mask() == 0 {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            ReadyEvent { tick: state.tick }
+        };
+
+        let res = f();
+        if let Err(e) = &res {
+            if e.kind() == io::ErrorKind::WouldBlock {
+                self.clear_readiness(event, direction);
+            }
+        }
+        res
+    }
+
+    /// Waits until the resource is ready in `direction`.
+    pub(crate) async fn ready(&self, direction: Direction) -> io::Result<()> {
+        std::future::poll_fn(|cx| self.poll_ready(cx, direction).map_ok(|_| ())).await
+    }
+
+    /// Runs `f` until it stops returning `WouldBlock`.
+    pub(crate) async fn async_io<R>(
+        &self,
+        direction: Direction,
+        mut f: impl FnMut() -> io::Result<R>,
+    ) -> io::Result<R> {
+        std::future::poll_fn(|cx| self.poll_io(cx, direction, &mut f)).await
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // The descriptor may already be gone if the owner closed it first;
+        // either way the token must not be reused for new events.
+        unsafe {
+            libc::epoll_ctl(
+                self.handle.inner.epoll.as_raw_fd(),
+                libc::EPOLL_CTL_DEL,
+                self.fd,
+                std::ptr::null_mut(),
+            );
+        }
+        self.handle
+            .inner
+            .resources
+            .lock()
+            .unwrap()
+            .remove(&self.token);
+    }
+}
+
+impl ScheduledIo {
+    fn set_readiness(&self, flags: libc::c_int) {
+        let mut ready = 0;
+        if flags & (libc::EPOLLIN | libc::EPOLLPRI) != 0 {
+            ready |= READABLE;
+        }
+        if flags & libc::EPOLLOUT != 0 {
+            ready |= WRITABLE;
+        }
+        if flags & (libc::EPOLLRDHUP | libc::EPOLLHUP | libc::EPOLLERR) != 0 {
+            ready |= READ_CLOSED;
+        }
+        if flags & (libc::EPOLLHUP | libc::EPOLLERR) != 0 {
+            ready |= WRITE_CLOSED;
+        }
+
+        let (reader, writer) = {
+            let mut state = self.state.lock().unwrap();
+            state.readiness |= ready;
+            state.tick = state.tick.wrapping_add(1);
+            let reader = if ready & Direction::Read.mask() != 0 {
+                state.reader.take()
+            } else {
+                None
+            };
+            let writer = if ready & Direction::Write.mask() != 0 {
+                state.writer.take()
+            } else {
+                None
+            };
+            (reader, writer)
+        };
+
+        if let Some(waker) = reader {
+            waker.wake();
+        }
+        if let Some(waker) = writer {
+            waker.wake();
+        }
+    }
+}
+
+/// Converts a libc return value into an `io::Result`.
+pub(crate) fn cvt(res: libc::c_int) -> io::Result<libc::c_int> {
+    if res == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(res)
+    }
+}
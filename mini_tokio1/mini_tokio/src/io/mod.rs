@@ -0,0 +1,24 @@
+//! Asynchronous I/O traits, utilities and the reactor that drives them.
+//!
+//! [`AsyncRead`], [`AsyncWrite`], [`AsyncBufRead`] and [`AsyncSeek`] are the asynchronous
+//! counterparts of the `std::io` traits; the `*Ext` traits add `read_exact`,
+//! `write_all`, `lines` and friends on top. [`Compat`] converts to and from
+//! the [`futures::io`] traits.
+//!
+//! The reactor in `driver` is turned by the executor and wakes tasks whose
+//! file descriptors became ready.
+
+mod compat;
+pub(crate) mod driver;
+mod poll_evented;
+mod traits;
+mod util;
+
+pub use compat::Compat;
+pub(crate) use poll_evented::PollEvented;
+pub use traits::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+pub use util::{
+    copy, duplex, split, AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader,
+    BufWriter, DuplexStream, Flush, Lines, Read, ReadExact, ReadHalf, ReadLine, ReadToEnd,
+    ReadToString, ReadUntil, Seek, Shutdown, Write, WriteAll, WriteHalf,
+};
@@ -0,0 +1,41 @@
+use std::{io, ops::Deref, os::fd::AsRawFd};
+
+use super::driver::Registration;
+use crate::context;
+
+/// A non-blocking I/O object registered with the current executor's reactor.
+pub(crate) struct PollEvented<E: AsRawFd> {
+    // Declared before `io` so that the descriptor is deregistered before it
+    // is closed.
+    registration: Option<Registration>,
+    io: Option<E>,
+}
+
+impl<E: AsRawFd> PollEvented<E> {
+    /// Registers `io` with the reactor. `io` must already be in non-blocking mode.
+    pub(crate) fn new(io: E) -> io::Result<Self> {
+        let registration = context::io_handle()?.register(io.as_raw_fd())?;
+        Ok(Self {
+            registration: Some(registration),
+            io: Some(io),
+        })
+    }
+
+    pub(crate) fn registration(&self) -> &Registration {
+        self.registration.as_ref().unwrap()
+    }
+
+    /// Deregisters the object and hands it back.
+    pub(crate) fn into_inner(mut self) -> E {
+        self.registration.take();
+        self.io.take().unwrap()
+    }
+}
+
+impl<E: AsRawFd> Deref for PollEvented<E> {
+    type Target = E;
+
+    fn deref(&self) -> &E {
+        self.io.as_ref().unwrap()
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures::Stream;
+
+use crate::io::AsyncBufRead;
+
+/// Convenience methods for [`AsyncBufRead`] types.
+pub trait AsyncBufReadExt: AsyncBufRead {
+    /// Reads until `byte` or end of stream, appending everything read
+    /// (including the delimiter) to `buf`
+    fn read_until<'a>(&'a mut self, byte: u8, buf: &'a mut Vec<u8>) -> ReadUntil<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadUntil {
+            reader: self,
+            delimiter: byte,
+            buf,
+            read: 0,
+        }
+    }
+
+    /// Reads a line, including its trailing newline, and appends it to `buf`.
+    ///
+    /// Fails with `InvalidData` if the line is not valid UTF-8, in which case
+    /// `buf` is left unchanged.
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> ReadLine<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadLine {
+            reader: self,
+            buf,
+            bytes: Vec::new(),
+            read: 0,
+        }
+    }
+
+    /// Returns a stream over the lines of this reader, without their line endings
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines {
+            reader: self,
+            bytes: Vec::new(),
+            read: 0,
+        }
+    }
+}
+
+impl<R: AsyncBufRead + ?Sized> AsyncBufReadExt for R {}
+
+/// Future returned by [`AsyncBufReadExt::read_until`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadUntil<'a, R: ?Sized> {
+    reader: &'a mut R,
+    delimiter: u8,
+    buf: &'a mut Vec<u8>,
+    read: usize,
+}
+
+impl<R: AsyncBufRead + Unpin + ?Sized> Future for ReadUntil<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        read_until_internal(
+            Pin::new(&mut *me.reader),
+            cx,
+            me.delimiter,
+            me.buf,
+            &mut me.read,
+        )
+    }
+}
+
+/// Future returned by [`AsyncBufReadExt::read_line`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadLine<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+impl<R: AsyncBufRead + Unpin + ?Sized> Future for ReadLine<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        let n = ready!(read_until_internal(
+            Pin::new(&mut *me.reader),
+            cx,
+            b'\n',
+            &mut me.bytes,
+            &mut me.read,
+        ))?;
+
+        match std::str::from_utf8(&me.bytes) {
+            Ok(s) => {
+                me.buf.push_str(s);
+                Poll::Ready(Ok(n))
+            }
+            Err(e) => Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+        }
+    }
+}
+
+/// Stream of lines returned by [`AsyncBufReadExt::lines`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Lines<R> {
+    reader: R,
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+impl<R: AsyncBufRead + Unpin> Lines<R> {
+    /// Returns the next line, or `None` at end of stream
+    pub async fn next_line(&mut self) -> io::Result<Option<String>> {
+        std::future::poll_fn(|cx| self.poll_next_line(cx)).await
+    }
+
+    /// Polls for the next line, or `None` at end of stream
+    pub fn poll_next_line(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<Option<String>>> {
+        let n = ready!(read_until_internal(
+            Pin::new(&mut self.reader),
+            cx,
+            b'\n',
+            &mut self.bytes,
+            &mut self.read,
+        ))?;
+        if n == 0 && self.bytes.is_empty() {
+            return Poll::Ready(Ok(None));
+        }
+
+        let mut bytes = std::mem::take(&mut self.bytes);
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(line) => Poll::Ready(Ok(Some(line))),
+            Err(e) => Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+        }
+    }
+
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for Lines<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_line(cx).map(Result::transpose)
+    }
+}
+
+/// Reads until `delimiter`, tracking the running total in `read` so that the
+/// operation can resume after `Pending`.
+fn read_until_internal<R: AsyncBufRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    delimiter: u8,
+    buf: &mut Vec<u8>,
+    read: &mut usize,
+) -> Poll<io::Result<usize>> {
+    loop {
+        let (done, used) = {
+            let available = ready!(reader.as_mut().poll_fill_buf(cx))?;
+            match available.iter().position(|&b| b == delimiter) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    (true, i + 1)
+                }
+                None => {
+                    buf.extend_from_slice(available);
+                    (available.is_empty(), available.len())
+                }
+            }
+        };
+        reader.as_mut().consume(used);
+        *read += used;
+        if done {
+            return Poll::Ready(Ok(std::mem::take(read)));
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::io;
+
+use super::{AsyncReadExt, AsyncWriteExt};
+use crate::io::{AsyncRead, AsyncWrite};
+
+const COPY_BUF_SIZE: usize = 8 * 1024;
+
+/// Copies everything from `reader` into `writer` and flushes it.
+///
+/// Returns the number of bytes copied once `reader` reaches end of stream.
+pub async fn copy<R, W>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut buf = vec![0; COPY_BUF_SIZE];
+    let mut copied = 0;
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            writer.flush().await?;
+            return Ok(copied);
+        }
+        writer.write_all(&buf[..n]).await?;
+        copied += n as u64;
+    }
+}
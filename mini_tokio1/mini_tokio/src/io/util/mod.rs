@@ -0,0 +1,19 @@
+mod buf_read;
+mod buf_reader;
+mod buf_writer;
+mod copy;
+mod duplex;
+mod read;
+mod seek;
+mod split;
+mod write;
+
+pub use buf_read::{AsyncBufReadExt, Lines, ReadLine, ReadUntil};
+pub use buf_reader::BufReader;
+pub use buf_writer::BufWriter;
+pub use copy::copy;
+pub use duplex::{duplex, DuplexStream};
+pub use read::{AsyncReadExt, Read, ReadExact, ReadToEnd, ReadToString};
+pub use seek::{AsyncSeekExt, Seek};
+pub use split::{split, ReadHalf, WriteHalf};
+pub use write::{AsyncWriteExt, Flush, Shutdown, Write, WriteAll};
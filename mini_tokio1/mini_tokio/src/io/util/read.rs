@@ -0,0 +1,187 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use crate::io::AsyncRead;
+
+/// Convenience methods for [`AsyncRead`] types.
+pub trait AsyncReadExt: AsyncRead {
+    /// Reads some bytes into `buf`, returning how many were read
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> Read<'a, Self>
+    where
+        Self: Unpin,
+    {
+        Read { reader: self, buf }
+    }
+
+    /// Reads exactly `buf.len()` bytes, failing with `UnexpectedEof` if the
+    /// stream ends first
+    fn read_exact<'a>(&'a mut self, buf: &'a mut [u8]) -> ReadExact<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadExact {
+            reader: self,
+            buf,
+            filled: 0,
+        }
+    }
+
+    /// Reads until end of stream, appending to `buf`
+    fn read_to_end<'a>(&'a mut self, buf: &'a mut Vec<u8>) -> ReadToEnd<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadToEnd {
+            reader: self,
+            buf,
+            read: 0,
+        }
+    }
+
+    /// Reads until end of stream, appending to `buf`.
+    ///
+    /// Fails with `InvalidData` if the bytes read are not valid UTF-8, in which
+    /// case `buf` is left unchanged.
+    fn read_to_string<'a>(&'a mut self, buf: &'a mut String) -> ReadToString<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadToString {
+            reader: self,
+            buf,
+            bytes: Vec::new(),
+            read: 0,
+        }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadExt for R {}
+
+/// Future returned by [`AsyncReadExt::read`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Read<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> Future for Read<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        Pin::new(&mut *me.reader).poll_read(cx, me.buf)
+    }
+}
+
+/// Future returned by [`AsyncReadExt::read_exact`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadExact<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+    filled: usize,
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> Future for ReadExact<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        while me.filled < me.buf.len() {
+            let n = ready!(Pin::new(&mut *me.reader).poll_read(cx, &mut me.buf[me.filled..]))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+            me.filled += n;
+        }
+        Poll::Ready(Ok(me.filled))
+    }
+}
+
+/// Future returned by [`AsyncReadExt::read_to_end`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadToEnd<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut Vec<u8>,
+    read: usize,
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> Future for ReadToEnd<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        read_to_end_internal(Pin::new(&mut *me.reader), cx, me.buf, &mut me.read)
+    }
+}
+
+/// Future returned by [`AsyncReadExt::read_to_string`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadToString<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+impl<R: AsyncRead + Unpin + ?Sized> Future for ReadToString<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        let n = ready!(read_to_end_internal(
+            Pin::new(&mut *me.reader),
+            cx,
+            &mut me.bytes,
+            &mut me.read
+        ))?;
+
+        match std::str::from_utf8(&me.bytes) {
+            Ok(s) => {
+                me.buf.push_str(s);
+                Poll::Ready(Ok(n))
+            }
+            Err(e) => Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+        }
+    }
+}
+
+/// Reads into the spare capacity of `buf` until end of stream, tracking the
+/// running total in `read` so that the operation can resume after `Pending`.
+fn read_to_end_internal<R: AsyncRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    buf: &mut Vec<u8>,
+    read: &mut usize,
+) -> Poll<io::Result<usize>> {
+    loop {
+        if buf.len() == buf.capacity() {
+            buf.reserve(32);
+        }
+
+        let len = buf.len();
+        buf.resize(buf.capacity(), 0);
+        let res = reader.as_mut().poll_read(cx, &mut buf[len..]);
+        match res {
+            Poll::Ready(Ok(0)) => {
+                buf.truncate(len);
+                return Poll::Ready(Ok(std::mem::take(read)));
+            }
+            Poll::Ready(Ok(n)) => {
+                buf.truncate(len + n);
+                *read += n;
+            }
+            Poll::Ready(Err(e)) => {
+                buf.truncate(len);
+                return Poll::Ready(Err(e));
+            }
+            Poll::Pending => {
+                buf.truncate(len);
+                return Poll::Pending;
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::{
+    future::Future,
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::io::AsyncSeek;
+
+/// Convenience methods for [`AsyncSeek`] types.
+pub trait AsyncSeekExt: AsyncSeek {
+    /// Moves the cursor to `pos`, returning the new position from the start
+    fn seek(&mut self, pos: SeekFrom) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Seek { seeker: self, pos }
+    }
+
+    /// Moves the cursor back to the start
+    fn rewind(&mut self) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        self.seek(SeekFrom::Start(0))
+    }
+
+    /// Returns the cursor's position from the start
+    fn stream_position(&mut self) -> Seek<'_, Self>
+    where
+        Self: Unpin,
+    {
+        self.seek(SeekFrom::Current(0))
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> AsyncSeekExt for S {}
+
+/// Future returned by [`AsyncSeekExt::seek`] and friends.
+#[must_use = "futures do nothing unless polled"]
+pub struct Seek<'a, S: ?Sized> {
+    seeker: &'a mut S,
+    pos: SeekFrom,
+}
+
+impl<S: AsyncSeek + Unpin + ?Sized> Future for Seek<'_, S> {
+    type Output = io::Result<u64>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = &mut *self;
+        Pin::new(&mut *me.seeker).poll_seek(cx, me.pos)
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use crate::io::{AsyncRead, AsyncWrite};
+
+/// Splits a read/write stream into separately owned read and write halves.
+///
+/// The halves share the stream behind a lock that is only held for the
+/// duration of each poll, so they can be moved into different tasks.
+pub fn split<T>(stream: T) -> (ReadHalf<T>, WriteHalf<T>)
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let inner = Arc::new(Mutex::new(stream));
+    (
+        ReadHalf {
+            inner: Arc::clone(&inner),
+        },
+        WriteHalf { inner },
+    )
+}
+
+/// The read half returned by [`split`].
+pub struct ReadHalf<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+/// The write half returned by [`split`].
+pub struct WriteHalf<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+impl<T> ReadHalf<T> {
+    /// Returns whether `other` was split from the same stream
+    pub fn is_pair_of(&self, other: &WriteHalf<T>) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Reunites the two halves into the original stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` was split from a different stream.
+    pub fn unsplit(self, other: WriteHalf<T>) -> T {
+        assert!(
+            self.is_pair_of(&other),
+            "unrelated `WriteHalf` passed to `ReadHalf::unsplit`"
+        );
+        drop(other);
+        let inner = Arc::try_unwrap(self.inner)
+            .ok()
+            .expect("both halves were consumed");
+        inner.into_inner().unwrap()
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for ReadHalf<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut inner = self.inner.lock().unwrap();
+        Pin::new(&mut *inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for WriteHalf<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut inner = self.inner.lock().unwrap();
+        Pin::new(&mut *inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut inner = self.inner.lock().unwrap();
+        Pin::new(&mut *inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut inner = self.inner.lock().unwrap();
+        Pin::new(&mut *inner).poll_shutdown(cx)
+    }
+}
+
+impl<T> std::fmt::Debug for ReadHalf<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadHalf").finish_non_exhaustive()
+    }
+}
+
+impl<T> std::fmt::Debug for WriteHalf<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WriteHalf").finish_non_exhaustive()
+    }
+}
@@ -3,14 +3,34 @@
 //! A minimal async runtime implementation for educational purposes.
 //!
 //! This crate provides a single-threaded, cooperative async runtime that
-//! implements the core functionality of task spawning, execution, and timing.
+//! implements the core functionality of task spawning, execution, timing, and
+//! networking.
 
-mod executor;
-mod task;
-mod time;
+pub mod actor;
+pub mod codec;
+#[cfg(feature = "console")]
+pub mod console;
+mod context;
+pub mod executor;
+pub mod fs;
+pub mod io;
+#[doc(hidden)]
+pub mod macros;
+pub mod net;
+pub mod pool;
+pub mod process;
+mod rand;
+pub mod resilience;
+pub mod signal;
+pub mod stream;
+pub mod supervisor;
+pub mod sync;
+pub mod task;
+pub mod time;
 
 pub use executor::Executor;
-pub use task::JoinHandle;
+pub use mini_tokio_macros::{main, stream, test, try_stream};
+pub use task::{spawn, spawn_blocking, spawn_with_deadline, spawn_with_priority, JoinHandle};
 pub use time::delay;
 
 /// Error type for cancelled tasks
@@ -23,4 +43,4 @@ impl std::fmt::Display for Cancelled {
     }
 }
 
-impl std::error::Error for Cancelled {}
\ No newline at end of file
+impl std::error::Error for Cancelled {}
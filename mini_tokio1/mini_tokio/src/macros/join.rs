@@ -0,0 +1,78 @@
+/// Waits on several futures concurrently, returning all of their outputs.
+///
+/// The futures run on the current task rather than being spawned, so they do
+/// not need to be `Send` or `'static`. Each branch gets its own waker, and
+/// when the task is woken only the branches that were woken are polled again.
+///
+/// Outputs are returned as a tuple in the order the futures were given.
+///
+/// ```
+/// use mini_tokio::{delay, join, Executor};
+///
+/// let executor = Executor::new();
+/// let (a, b) = executor.block_on(async {
+///     join!(
+///         async {
+///             delay(20).await;
+///             1
+///         },
+///         async { "two" },
+///     )
+/// });
+/// assert_eq!((a, b), (1, "two"));
+/// ```
+///
+/// At most 64 futures can be joined at once.
+#[macro_export]
+macro_rules! join {
+    // All futures collected. Each branch carries one `_` for every branch
+    // before it, which is how it finds its own slot in the tuples below.
+    (@ { ( $($count:tt)* ) $( ( $($skip:tt)* ) $e:expr, )* }) => {{
+        let mut futures = ( $(
+            $crate::macros::support::pin!($crate::macros::support::maybe_done(
+                $crate::macros::support::IntoFuture::into_future($e),
+            )),
+        )* );
+        let wakers = $crate::macros::support::BranchWakers::new(
+            [$(::core::stringify!($count)),*].len(),
+        );
+
+        $crate::macros::support::poll_fn(move |cx| {
+            let woken = wakers.take_woken(cx);
+            let mut is_pending = false;
+            let mut branch = 0;
+            $(
+                let ( $($skip,)* fut, .. ) = &mut futures;
+                if woken & (1 << branch) != 0 {
+                    let mut cx = wakers.context(branch);
+                    let poll = $crate::macros::support::Future::poll(fut.as_mut(), &mut cx);
+                    is_pending |= poll.is_pending();
+                } else {
+                    is_pending |= fut.as_mut().output_mut().is_none();
+                }
+                branch += 1;
+            )*
+            let _ = branch;
+
+            if is_pending {
+                return $crate::macros::support::Poll::Pending;
+            }
+            $crate::macros::support::Poll::Ready(( $({
+                let ( $($skip,)* fut, .. ) = &mut futures;
+                fut.as_mut().take_output().expect("join! branch completed")
+            },)* ))
+        })
+        .await
+    }};
+
+    // Collect the next future.
+    (@ { ( $($s:tt)* ) $($t:tt)* } $e:expr, $($rest:tt)*) => {
+        $crate::join!(@ { ( $($s)* _ ) $($t)* ( $($s)* ) $e, } $($rest)*)
+    };
+
+    () => { async {}.await };
+
+    ( $($e:expr),+ $(,)? ) => {
+        $crate::join!(@ { () } $($e,)+)
+    };
+}
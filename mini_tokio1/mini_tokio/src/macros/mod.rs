@@ -0,0 +1,9 @@
+//! Support for the `join!`, `try_join!`, `select!`, `stream!` and
+//! `try_stream!` macros.
+
+mod join;
+mod select;
+mod stream;
+mod try_join;
+
+pub mod support;
@@ -0,0 +1,209 @@
+/// Waits on several futures, running the handler of the first one to complete.
+///
+/// Each branch has the form
+///
+/// ```text
+/// <pattern> = <future>, if <precondition> => <handler>,
+/// ```
+///
+/// where the `if` guard is optional. `select!` runs as follows:
+///
+/// 1. Every precondition is evaluated. A branch whose precondition is false is
+///    disabled for this call.
+/// 2. Every future is created, including those of disabled branches, which
+///    are never polled.
+/// 3. The enabled futures are polled concurrently. When one completes, its
+///    output is matched against the branch pattern. On a match the other
+///    futures are dropped and the handler runs with the pattern's bindings.
+///    Otherwise the branch is disabled and the rest keep running.
+/// 4. If every branch ends up disabled, the `else` handler runs. Without an
+///    `else` branch this panics.
+///
+/// Branches are polled starting from a random one so that a branch that is
+/// always ready cannot starve the others. Starting the branches with
+/// `biased;` polls them in the order they are written instead. Only the
+/// branches that were woken are polled again.
+///
+/// Handlers run in the enclosing async block, so they can `.await`, `break`
+/// out of a surrounding loop or `return`.
+///
+/// ```
+/// use mini_tokio::{delay, select, Executor};
+///
+/// let executor = Executor::new();
+/// let winner = executor.block_on(async {
+///     select! {
+///         _ = delay(1000) => "timeout",
+///         v = async { 42 } => if v == 42 { "work" } else { "wrong" },
+///     }
+/// });
+/// assert_eq!(winner, "work");
+/// ```
+///
+/// At most 64 branches are supported.
+#[macro_export]
+macro_rules! select {
+    // All branches collected. As in `join!`, each branch carries one `_` for
+    // every branch before it to find its own slot in the tuples below.
+    (@ {
+        biased = $biased:tt;
+        ( $($count:tt)* )
+        $( ( $($skip:tt)* ) $bind:pat = $fut:expr, if $c:expr => $handle:expr, )*
+    } else => $else:expr $(,)?) => {{
+        let branches = <[&str]>::len(&[$(::core::stringify!($count)),*]);
+
+        let mut disabled: u64 = 0;
+        let mut branch = 0;
+        $(
+            if !$c {
+                disabled |= 1 << branch;
+            }
+            branch += 1;
+        )*
+        let _ = branch;
+
+        // Scoped so that the losing futures are dropped before the handler
+        // runs.
+        let output = {
+            let mut futures = ( $(
+                $crate::macros::support::pin!(
+                    $crate::macros::support::IntoFuture::into_future($fut),
+                ),
+            )* );
+            let wakers = $crate::macros::support::BranchWakers::new(branches);
+            // Builds the output tuple, with every slot empty.
+            let no_outputs = || ( $( $crate::select!(@none $fut), )* );
+
+            $crate::macros::support::poll_fn(|cx| {
+                let woken = wakers.take_woken(cx);
+                let start = if $biased {
+                    0
+                } else {
+                    $crate::macros::support::thread_rng_n(branches)
+                };
+
+                for i in 0..branches {
+                    let branch = (start + i) % branches;
+                    let bit = 1 << branch;
+                    if disabled & bit != 0 || woken & bit == 0 {
+                        continue;
+                    }
+                    let mut cx = wakers.context(branch);
+
+                    let mut index = 0;
+                    $(
+                        if index == branch {
+                            let ( $($skip,)* fut, .. ) = &mut futures;
+                            let poll = $crate::macros::support::Future::poll(
+                                fut.as_mut(),
+                                &mut cx,
+                            );
+                            if let $crate::macros::support::Poll::Ready(out) = poll {
+                                #[allow(unreachable_patterns, unused_variables, unused_mut)]
+                                match &out {
+                                    $bind => {}
+                                    _ => {
+                                        disabled |= bit;
+                                        continue;
+                                    }
+                                }
+
+                                let mut outputs = no_outputs();
+                                let ( $($skip,)* slot, .. ) = &mut outputs;
+                                *slot = $crate::macros::support::Some(out);
+                                return $crate::macros::support::Poll::Ready(
+                                    $crate::macros::support::Some(outputs),
+                                );
+                            }
+                        }
+                        index += 1;
+                    )*
+                    let _ = index;
+                }
+
+                if disabled == $crate::macros::support::all(branches) {
+                    $crate::macros::support::Poll::Ready($crate::macros::support::None)
+                } else {
+                    $crate::macros::support::Poll::Pending
+                }
+            })
+            .await
+        };
+
+        match output {
+            $crate::macros::support::Some(outputs) => {
+                $(
+                    if let ( $($skip,)* $crate::macros::support::Some($bind), .. ) = outputs {
+                        $handle
+                    } else
+                )*
+                {
+                    ::core::unreachable!()
+                }
+            }
+            $crate::macros::support::None => $else,
+        }
+    }};
+
+    // All branches collected without an `else` branch.
+    (@ {
+        biased = $biased:tt;
+        ( $($count:tt)* )
+        $( ( $($skip:tt)* ) $bind:pat = $fut:expr, if $c:expr => $handle:expr, )*
+    }) => {
+        $crate::select!(@ {
+            biased = $biased;
+            ( $($count)* )
+            $( ( $($skip)* ) $bind = $fut, if $c => $handle, )*
+        } else => ::core::panic!("all select! branches are disabled and there is no else branch"))
+    };
+
+    // A `None` for every branch when building the output tuple.
+    (@none $($t:tt)*) => { $crate::macros::support::None };
+
+    // Collect the next branch. Block handlers may omit the trailing comma.
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr, if $c:expr => $h:block, $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if $c => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr, if $c:expr => $h:block $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if $c => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr, if $c:expr => $h:expr, $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if $c => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr, if $c:expr => $h:expr) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if $c => $h, })
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr => $h:block, $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if true => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr => $h:block $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if true => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr => $h:expr, $($rest:tt)*) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if true => $h, } $($rest)*)
+    };
+    (@ { biased = $biased:tt; ( $($s:tt)* ) $($t:tt)* }
+        $p:pat = $f:expr => $h:expr) => {
+        $crate::select!(@ { biased = $biased; ( $($s)* _ ) $($t)* ( $($s)* ) $p = $f, if true => $h, })
+    };
+
+    () => {
+        ::core::compile_error!("select! requires at least one branch")
+    };
+
+    (biased; $($t:tt)*) => {
+        $crate::select!(@ { biased = true; () } $($t)*)
+    };
+
+    ( $($t:tt)* ) => {
+        $crate::select!(@ { biased = false; () } $($t)*)
+    };
+}
@@ -0,0 +1,89 @@
+//! The stream type that `stream!` and `try_stream!` expand to.
+
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A stream driven by an async block that hands its items to a
+    /// [`Yielder`]
+    pub struct AsyncStream<T, F> {
+        slot: Arc<Mutex<Option<T>>>,
+        done: bool,
+        #[pin]
+        generator: F,
+    }
+}
+
+/// Passes items from the async block of a [`AsyncStream`] to its consumer
+pub struct Yielder<T> {
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+impl<T, F: Future<Output = ()>> AsyncStream<T, F> {
+    /// Creates the stream from the async block built by `generator`
+    pub fn new(generator: impl FnOnce(Yielder<T>) -> F) -> Self {
+        let slot = Arc::new(Mutex::new(None));
+        let generator = generator(Yielder {
+            slot: Arc::clone(&slot),
+        });
+        Self {
+            slot,
+            done: false,
+            generator,
+        }
+    }
+}
+
+impl<T, F: Future<Output = ()>> futures::Stream for AsyncStream<T, F> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+        let poll = this.generator.poll(cx);
+        if let Some(item) = this.slot.lock().unwrap().take() {
+            return Poll::Ready(Some(item));
+        }
+        if poll.is_ready() {
+            *this.done = true;
+            return Poll::Ready(None);
+        }
+        Poll::Pending
+    }
+}
+
+impl<T, F> fmt::Debug for AsyncStream<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncStream")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Yielder<T> {
+    /// Hands `item` to the consumer, then returns once it has been taken.
+    ///
+    /// The first poll stores the item and returns `Pending` without
+    /// registering a waker: [`AsyncStream`] sees the item and returns it,
+    /// and the consumer polling for the next item resumes the block.
+    pub async fn send(&self, item: T) {
+        let mut item = Some(item);
+        poll_fn(|_| match item.take() {
+            Some(item) => {
+                *self.slot.lock().unwrap() = Some(item);
+                Poll::Pending
+            }
+            None => Poll::Ready(()),
+        })
+        .await
+    }
+}
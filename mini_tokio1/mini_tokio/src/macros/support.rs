@@ -0,0 +1,120 @@
+//! Items used by the expansions of `join!`, `try_join!`, `select!`,
+//! `stream!` and `try_stream!`.
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Wake, Waker},
+};
+
+use futures::task::AtomicWaker;
+
+use crate::rand::FastRand;
+
+pub use super::stream::{AsyncStream, Yielder};
+pub use futures::future::{maybe_done, MaybeDone};
+pub use std::{
+    future::{poll_fn, Future, IntoFuture},
+    option::Option::{None, Some},
+    pin::pin,
+    result::Result::{Err, Ok},
+    task::Poll,
+};
+
+/// The most branches a single macro invocation can wait on.
+const MAX_BRANCHES: usize = u64::BITS as usize;
+
+/// One waker per branch, so that only the branches that were woken are
+/// polled again.
+pub struct BranchWakers {
+    state: Arc<WakeState>,
+    wakers: Vec<Waker>,
+}
+
+struct WakeState {
+    /// Bit `i` is set once branch `i` has been woken
+    woken: AtomicU64,
+    /// The waker of the task running the macro
+    parent: AtomicWaker,
+}
+
+struct BranchWaker {
+    state: Arc<WakeState>,
+    bit: u64,
+}
+
+impl BranchWakers {
+    /// Creates wakers for `branches` branches, all of which start out woken
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 64 branches.
+    pub fn new(branches: usize) -> Self {
+        assert!(
+            branches <= MAX_BRANCHES,
+            "at most {MAX_BRANCHES} branches are supported"
+        );
+        let state = Arc::new(WakeState {
+            woken: AtomicU64::new(all(branches)),
+            parent: AtomicWaker::new(),
+        });
+        let wakers = (0..branches)
+            .map(|branch| {
+                Waker::from(Arc::new(BranchWaker {
+                    state: Arc::clone(&state),
+                    bit: 1 << branch,
+                }))
+            })
+            .collect();
+        Self { state, wakers }
+    }
+
+    /// Registers the waker in `cx` and returns the branches woken since the
+    /// previous call, as a bit set
+    pub fn take_woken(&self, cx: &Context<'_>) -> u64 {
+        self.state.parent.register(cx.waker());
+        self.state.woken.swap(0, Ordering::AcqRel)
+    }
+
+    /// Returns a context that wakes `branch`
+    pub fn context(&self, branch: usize) -> Context<'_> {
+        Context::from_waker(&self.wakers[branch])
+    }
+}
+
+impl Wake for BranchWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.state.woken.fetch_or(self.bit, Ordering::AcqRel);
+        self.state.parent.wake();
+    }
+}
+
+/// Returns the bit set holding the first `branches` branches
+pub fn all(branches: usize) -> u64 {
+    if branches >= MAX_BRANCHES {
+        u64::MAX
+    } else {
+        (1 << branches) - 1
+    }
+}
+
+/// Returns a pseudo-random number in `0..n`, or 0 if `n` is 0
+pub fn thread_rng_n(n: usize) -> usize {
+    thread_local! {
+        static RNG: Cell<FastRand> = Cell::new(FastRand::new());
+    }
+
+    RNG.with(|rng| {
+        let mut fast = rng.get();
+        let i = fast.next_n(n);
+        rng.set(fast);
+        i
+    })
+}
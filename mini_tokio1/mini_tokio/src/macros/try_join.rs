@@ -0,0 +1,87 @@
+/// Waits on several fallible futures concurrently, stopping at the first error.
+///
+/// Every future must output a `Result` with the same error type. If all of
+/// them succeed, the values are returned as `Ok` of a tuple in the order the
+/// futures were given. As soon as one fails its error is returned, and the
+/// futures that have not finished yet are dropped.
+///
+/// Like [`join!`](crate::join), only the branches that were woken are polled
+/// again.
+///
+/// ```
+/// use mini_tokio::{try_join, Executor};
+///
+/// let executor = Executor::new();
+/// let res = executor.block_on(async {
+///     try_join!(
+///         async { Ok::<_, &str>(1) },
+///         async { Err::<(), _>("failed") },
+///         futures::future::pending::<Result<(), &str>>(),
+///     )
+/// });
+/// assert_eq!(res, Err("failed"));
+/// ```
+#[macro_export]
+macro_rules! try_join {
+    (@ { ( $($count:tt)* ) $( ( $($skip:tt)* ) $e:expr, )* }) => {{
+        let mut futures = ( $(
+            $crate::macros::support::pin!($crate::macros::support::maybe_done(
+                $crate::macros::support::IntoFuture::into_future($e),
+            )),
+        )* );
+        let wakers = $crate::macros::support::BranchWakers::new(
+            [$(::core::stringify!($count)),*].len(),
+        );
+
+        $crate::macros::support::poll_fn(move |cx| {
+            let woken = wakers.take_woken(cx);
+            let mut is_pending = false;
+            let mut branch = 0;
+            $(
+                let ( $($skip,)* fut, .. ) = &mut futures;
+                if woken & (1 << branch) != 0 {
+                    let mut cx = wakers.context(branch);
+                    let poll = $crate::macros::support::Future::poll(fut.as_mut(), &mut cx);
+                    if poll.is_pending() {
+                        is_pending = true;
+                    } else if fut.as_mut().output_mut().is_some_and(|out| out.is_err()) {
+                        return match fut.as_mut().take_output() {
+                            $crate::macros::support::Some($crate::macros::support::Err(e)) => {
+                                $crate::macros::support::Poll::Ready(
+                                    $crate::macros::support::Err(e),
+                                )
+                            }
+                            _ => ::core::unreachable!(),
+                        };
+                    }
+                } else {
+                    is_pending |= fut.as_mut().output_mut().is_none();
+                }
+                branch += 1;
+            )*
+            let _ = branch;
+
+            if is_pending {
+                return $crate::macros::support::Poll::Pending;
+            }
+            $crate::macros::support::Poll::Ready($crate::macros::support::Ok(( $({
+                let ( $($skip,)* fut, .. ) = &mut futures;
+                match fut.as_mut().take_output() {
+                    $crate::macros::support::Some($crate::macros::support::Ok(value)) => value,
+                    _ => ::core::unreachable!(),
+                }
+            },)* )))
+        })
+        .await
+    }};
+
+    (@ { ( $($s:tt)* ) $($t:tt)* } $e:expr, $($rest:tt)*) => {
+        $crate::try_join!(@ { ( $($s)* _ ) $($t)* ( $($s)* ) $e, } $($rest)*)
+    };
+
+    () => { async { $crate::macros::support::Ok(()) }.await };
+
+    ( $($e:expr),+ $(,)? ) => {
+        $crate::try_join!(@ { () } $($e,)+)
+    };
+}
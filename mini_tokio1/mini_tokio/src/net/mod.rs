@@ -0,0 +1,10 @@
+//! Networking primitives driven by the executor's reactor.
+//!
+//! Provides [`UdpSocket`] plus the Unix domain socket types [`UnixListener`],
+//! [`UnixStream`] and [`UnixDatagram`].
+
+mod udp;
+mod unix;
+
+pub use udp::UdpSocket;
+pub use unix::{UnixDatagram, UnixListener, UnixStream};
@@ -0,0 +1,233 @@
+use std::{
+    io,
+    net::{self, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    task::{Context, Poll},
+};
+
+use crate::io::{driver::Direction, PollEvented};
+
+/// A UDP socket driven by the executor's reactor.
+///
+/// Unlike `std::net::UdpSocket`, the send and receive operations are futures
+/// that yield to the executor instead of blocking the thread.
+pub struct UdpSocket {
+    io: PollEvented<net::UdpSocket>,
+}
+
+impl UdpSocket {
+    /// Creates a socket bound to `addr`
+    pub async fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<UdpSocket> {
+        let socket = net::UdpSocket::bind(addr)?;
+        UdpSocket::from_std(socket)
+    }
+
+    /// Wraps a socket created by the standard library.
+    ///
+    /// The socket is switched to non-blocking mode.
+    pub fn from_std(socket: net::UdpSocket) -> io::Result<UdpSocket> {
+        socket.set_nonblocking(true)?;
+        Ok(UdpSocket {
+            io: PollEvented::new(socket)?,
+        })
+    }
+
+    /// Returns the underlying standard library socket, still in non-blocking mode
+    pub fn into_std(self) -> io::Result<net::UdpSocket> {
+        Ok(self.io.into_inner())
+    }
+
+    /// Connects the socket so that `send` and `recv` use `addr` as the peer
+    pub async fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<()> {
+        self.io.connect(addr)
+    }
+
+    /// Returns the local address of the socket
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Returns the address of the connected peer
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.io.peer_addr()
+    }
+
+    /// Waits until the socket is readable
+    pub async fn readable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Read).await
+    }
+
+    /// Waits until the socket is writable
+    pub async fn writable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Write).await
+    }
+
+    /// Sends `buf` to `target`, returning the number of bytes written
+    pub async fn send_to<A: ToSocketAddrs>(&self, buf: &[u8], target: A) -> io::Result<usize> {
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to send to"))?;
+
+        self.io
+            .registration()
+            .async_io(Direction::Write, || self.io.send_to(buf, target))
+            .await
+    }
+
+    /// Receives a datagram, returning its length and the address it came from
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.io
+            .registration()
+            .async_io(Direction::Read, || self.io.recv_from(buf))
+            .await
+    }
+
+    /// Sends `buf` to the connected peer
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .async_io(Direction::Write, || self.io.send(buf))
+            .await
+    }
+
+    /// Receives a datagram from the connected peer
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .async_io(Direction::Read, || self.io.recv(buf))
+            .await
+    }
+
+    /// Polls for sending `buf` to `target`
+    pub fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        target: SocketAddr,
+    ) -> Poll<io::Result<usize>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Write, || self.io.send_to(buf, target))
+    }
+
+    /// Polls for receiving a datagram into `buf`
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Read, || self.io.recv_from(buf))
+    }
+
+    /// Tries to send `buf` to `target` without waiting
+    pub fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.io
+            .registration()
+            .try_io(Direction::Write, || self.io.send_to(buf, target))
+    }
+
+    /// Tries to receive a datagram without waiting
+    pub fn try_recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.io
+            .registration()
+            .try_io(Direction::Read, || self.io.recv_from(buf))
+    }
+
+    /// Enables or disables sending to broadcast addresses
+    pub fn set_broadcast(&self, on: bool) -> io::Result<()> {
+        self.io.set_broadcast(on)
+    }
+
+    /// Returns whether sending to broadcast addresses is enabled
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.io.broadcast()
+    }
+
+    /// Sets the time-to-live of outgoing unicast packets
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.io.set_ttl(ttl)
+    }
+
+    /// Returns the time-to-live of outgoing unicast packets
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.io.ttl()
+    }
+
+    /// Joins the IPv4 multicast group `multiaddr` on `interface`
+    pub fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.io.join_multicast_v4(&multiaddr, &interface)
+    }
+
+    /// Leaves the IPv4 multicast group `multiaddr` on `interface`
+    pub fn leave_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.io.leave_multicast_v4(&multiaddr, &interface)
+    }
+
+    /// Joins the IPv6 multicast group `multiaddr` on the interface with index `interface`
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.io.join_multicast_v6(multiaddr, interface)
+    }
+
+    /// Leaves the IPv6 multicast group `multiaddr` on the interface with index `interface`
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.io.leave_multicast_v6(multiaddr, interface)
+    }
+
+    /// Sets whether IPv4 multicast packets are looped back to local sockets
+    pub fn set_multicast_loop_v4(&self, on: bool) -> io::Result<()> {
+        self.io.set_multicast_loop_v4(on)
+    }
+
+    /// Returns whether IPv4 multicast packets are looped back to local sockets
+    pub fn multicast_loop_v4(&self) -> io::Result<bool> {
+        self.io.multicast_loop_v4()
+    }
+
+    /// Sets the time-to-live of outgoing IPv4 multicast packets
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> io::Result<()> {
+        self.io.set_multicast_ttl_v4(ttl)
+    }
+
+    /// Returns the time-to-live of outgoing IPv4 multicast packets
+    pub fn multicast_ttl_v4(&self) -> io::Result<u32> {
+        self.io.multicast_ttl_v4()
+    }
+
+    /// Sets whether IPv6 multicast packets are looped back to local sockets
+    pub fn set_multicast_loop_v6(&self, on: bool) -> io::Result<()> {
+        self.io.set_multicast_loop_v6(on)
+    }
+
+    /// Returns whether IPv6 multicast packets are looped back to local sockets
+    pub fn multicast_loop_v6(&self) -> io::Result<bool> {
+        self.io.multicast_loop_v6()
+    }
+
+    /// Returns and clears the pending socket error, if any
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.io.take_error()
+    }
+}
+
+impl AsRawFd for UdpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UdpSocket {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+impl std::fmt::Debug for UdpSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdpSocket")
+            .field("local_addr", &self.local_addr().ok())
+            .finish()
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    io,
+    net::Shutdown,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        unix::net::{self, SocketAddr},
+    },
+    path::Path,
+    task::{Context, Poll},
+};
+
+use crate::io::{driver::Direction, PollEvented};
+
+/// A Unix domain datagram socket.
+pub struct UnixDatagram {
+    io: PollEvented<net::UnixDatagram>,
+}
+
+impl UnixDatagram {
+    /// Creates a socket bound to the socket file at `path`
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixDatagram> {
+        let socket = net::UnixDatagram::bind(path)?;
+        UnixDatagram::from_std(socket)
+    }
+
+    /// Creates a socket that is not bound to any address
+    pub fn unbound() -> io::Result<UnixDatagram> {
+        let socket = net::UnixDatagram::unbound()?;
+        UnixDatagram::from_std(socket)
+    }
+
+    /// Creates an unnamed pair of connected sockets
+    pub fn pair() -> io::Result<(UnixDatagram, UnixDatagram)> {
+        let (a, b) = net::UnixDatagram::pair()?;
+        Ok((UnixDatagram::from_std(a)?, UnixDatagram::from_std(b)?))
+    }
+
+    /// Wraps a socket created by the standard library.
+    ///
+    /// The socket is switched to non-blocking mode.
+    pub fn from_std(socket: net::UnixDatagram) -> io::Result<UnixDatagram> {
+        socket.set_nonblocking(true)?;
+        Ok(UnixDatagram {
+            io: PollEvented::new(socket)?,
+        })
+    }
+
+    /// Returns the underlying standard library socket, still in non-blocking mode
+    pub fn into_std(self) -> io::Result<net::UnixDatagram> {
+        Ok(self.io.into_inner())
+    }
+
+    /// Connects the socket so that `send` and `recv` use the socket at `path` as the peer
+    pub fn connect<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.io.connect(path)
+    }
+
+    /// Returns the local address of the socket
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Returns the address of the connected peer
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.io.peer_addr()
+    }
+
+    /// Waits until the socket is readable
+    pub async fn readable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Read).await
+    }
+
+    /// Waits until the socket is writable
+    pub async fn writable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Write).await
+    }
+
+    /// Sends `buf` to the socket at `target`
+    pub async fn send_to<P: AsRef<Path>>(&self, buf: &[u8], target: P) -> io::Result<usize> {
+        let target = target.as_ref();
+        self.io
+            .registration()
+            .async_io(Direction::Write, || self.io.send_to(buf, target))
+            .await
+    }
+
+    /// Receives a datagram, returning its length and the address it came from
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        self.io
+            .registration()
+            .async_io(Direction::Read, || self.io.recv_from(buf))
+            .await
+    }
+
+    /// Sends `buf` to the connected peer
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .async_io(Direction::Write, || self.io.send(buf))
+            .await
+    }
+
+    /// Receives a datagram from the connected peer
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .async_io(Direction::Read, || self.io.recv(buf))
+            .await
+    }
+
+    /// Polls for receiving a datagram into `buf`
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<(usize, SocketAddr)>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Read, || self.io.recv_from(buf))
+    }
+
+    /// Tries to send `buf` to the connected peer without waiting
+    pub fn try_send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .try_io(Direction::Write, || self.io.send(buf))
+    }
+
+    /// Tries to receive a datagram from the connected peer without waiting
+    pub fn try_recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .try_io(Direction::Read, || self.io.recv(buf))
+    }
+
+    /// Shuts down the read half, the write half, or both
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.io.shutdown(how)
+    }
+
+    /// Returns and clears the pending socket error, if any
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.io.take_error()
+    }
+}
+
+impl AsRawFd for UnixDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UnixDatagram {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+impl std::fmt::Debug for UnixDatagram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixDatagram")
+            .field("local_addr", &self.local_addr().ok())
+            .field("peer_addr", &self.peer_addr().ok())
+            .finish()
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    io,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        unix::net::{self, SocketAddr},
+    },
+    path::Path,
+    task::{Context, Poll},
+};
+
+use super::UnixStream;
+use crate::io::{driver::Direction, PollEvented};
+
+/// A Unix domain socket server, listening for connections.
+pub struct UnixListener {
+    io: PollEvented<net::UnixListener>,
+}
+
+impl UnixListener {
+    /// Creates a listener bound to the socket file at `path`
+    pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<UnixListener> {
+        let listener = net::UnixListener::bind(path)?;
+        UnixListener::from_std(listener)
+    }
+
+    /// Wraps a listener created by the standard library.
+    ///
+    /// The listener is switched to non-blocking mode.
+    pub fn from_std(listener: net::UnixListener) -> io::Result<UnixListener> {
+        listener.set_nonblocking(true)?;
+        Ok(UnixListener {
+            io: PollEvented::new(listener)?,
+        })
+    }
+
+    /// Returns the underlying standard library listener, still in non-blocking mode
+    pub fn into_std(self) -> io::Result<net::UnixListener> {
+        Ok(self.io.into_inner())
+    }
+
+    /// Waits for a new incoming connection
+    pub async fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
+        std::future::poll_fn(|cx| self.poll_accept(cx)).await
+    }
+
+    /// Polls to accept a new incoming connection
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<(UnixStream, SocketAddr)>> {
+        let (stream, addr) = match self
+            .io
+            .registration()
+            .poll_io(cx, Direction::Read, || self.io.accept())
+        {
+            Poll::Ready(Ok(accepted)) => accepted,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        Poll::Ready(UnixStream::from_std(stream).map(|stream| (stream, addr)))
+    }
+
+    /// Returns the local address of the listener
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Returns and clears the pending socket error, if any
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.io.take_error()
+    }
+}
+
+impl AsRawFd for UnixListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UnixListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+impl std::fmt::Debug for UnixListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixListener")
+            .field("local_addr", &self.local_addr().ok())
+            .finish()
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    io::{self, Read, Write},
+    net::Shutdown,
+    os::{
+        fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        unix::net::{self, SocketAddr},
+    },
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::io::{driver::Direction, AsyncRead, AsyncWrite, PollEvented};
+
+/// A connected Unix domain stream socket.
+pub struct UnixStream {
+    io: PollEvented<net::UnixStream>,
+}
+
+impl UnixStream {
+    /// Connects to the socket file at `path`
+    pub async fn connect<P: AsRef<Path>>(path: P) -> io::Result<UnixStream> {
+        let fd = super::socket(libc::SOCK_STREAM)?;
+        let connected = super::connect(&fd, path.as_ref())?;
+        let stream = UnixStream {
+            io: PollEvented::new(net::UnixStream::from(fd))?,
+        };
+
+        if !connected {
+            stream.writable().await?;
+            if let Some(e) = stream.io.take_error()? {
+                return Err(e);
+            }
+        }
+        Ok(stream)
+    }
+
+    /// Creates an unnamed pair of connected sockets
+    pub fn pair() -> io::Result<(UnixStream, UnixStream)> {
+        let (a, b) = net::UnixStream::pair()?;
+        Ok((UnixStream::from_std(a)?, UnixStream::from_std(b)?))
+    }
+
+    /// Wraps a stream created by the standard library.
+    ///
+    /// The stream is switched to non-blocking mode.
+    pub fn from_std(stream: net::UnixStream) -> io::Result<UnixStream> {
+        stream.set_nonblocking(true)?;
+        Ok(UnixStream {
+            io: PollEvented::new(stream)?,
+        })
+    }
+
+    /// Returns the underlying standard library stream, still in non-blocking mode
+    pub fn into_std(self) -> io::Result<net::UnixStream> {
+        Ok(self.io.into_inner())
+    }
+
+    /// Returns the local address of the socket
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.io.local_addr()
+    }
+
+    /// Returns the address of the connected peer
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.io.peer_addr()
+    }
+
+    /// Waits until the stream is readable
+    pub async fn readable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Read).await
+    }
+
+    /// Waits until the stream is writable
+    pub async fn writable(&self) -> io::Result<()> {
+        self.io.registration().ready(Direction::Write).await
+    }
+
+    /// Tries to read into `buf` without waiting.
+    ///
+    /// Returns `WouldBlock` if no data is available yet.
+    pub fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .try_io(Direction::Read, || (&*self.io).read(buf))
+    }
+
+    /// Tries to write `buf` without waiting.
+    ///
+    /// Returns `WouldBlock` if the socket buffer is full.
+    pub fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.io
+            .registration()
+            .try_io(Direction::Write, || (&*self.io).write(buf))
+    }
+
+    /// Shuts down the read half, the write half, or both
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.io.shutdown(how)
+    }
+
+    /// Returns and clears the pending socket error, if any
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.io.take_error()
+    }
+}
+
+impl AsyncRead for UnixStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Read, || (&*self.io).read(buf))
+    }
+}
+
+impl AsyncWrite for UnixStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Write, || (&*self.io).write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.io.shutdown(Shutdown::Write))
+    }
+}
+
+impl AsRawFd for UnixStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UnixStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+impl std::fmt::Debug for UnixStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnixStream")
+            .field("local_addr", &self.local_addr().ok())
+            .field("peer_addr", &self.peer_addr().ok())
+            .finish()
+    }
+}
@@ -0,0 +1,184 @@
+//! Running many tasks with a bound on how many run at once.
+//!
+//! [`WorkerPool`] spawns each submitted future as its own task, but lets at
+//! most `n` of them be in flight. Submitting more waits until one of them
+//! finishes, so a producer cannot queue up unbounded work:
+//!
+//! ```
+//! use mini_tokio::{executor::Builder, pool::WorkerPool, time};
+//! use std::time::Duration;
+//!
+//! let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+//! executor.block_on(async {
+//!     let pool = WorkerPool::new(2);
+//!     let mut handles = Vec::new();
+//!     for i in 0..4 {
+//!         let job = async move {
+//!             time::sleep(Duration::from_secs(1)).await;
+//!             i * 10
+//!         };
+//!         handles.push(pool.submit(job).await.unwrap());
+//!     }
+//!     // The last two jobs waited for the first two to finish.
+//!     assert_eq!(pool.in_flight(), 2);
+//!
+//!     pool.close().await;
+//!     assert_eq!(pool.in_flight(), 0);
+//!     for (i, handle) in handles.into_iter().enumerate() {
+//!         assert_eq!(handle.await.unwrap(), i * 10);
+//!     }
+//! });
+//! ```
+
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    sync::{CancellationToken, Semaphore},
+    JoinHandle,
+};
+
+/// Spawns submitted futures as tasks, at most `n` at a time.
+///
+/// Clones share the same pool.
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<Inner>,
+}
+
+/// Returned by [`WorkerPool::submit`] once the pool is closing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolClosed;
+
+struct Inner {
+    size: usize,
+    /// One permit per task that may be in flight
+    semaphore: Arc<Semaphore>,
+    /// Submissions waiting for a permit
+    queued: AtomicUsize,
+    /// Cancelled by [`WorkerPool::close`], failing waiting submissions
+    closing: CancellationToken,
+}
+
+/// Counts a submission as queued while it exists
+struct Queued<'a>(&'a AtomicUsize);
+
+impl WorkerPool {
+    /// Creates a pool that runs at most `size` tasks at once
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "worker pool size cannot be 0");
+        Self {
+            inner: Arc::new(Inner {
+                size,
+                semaphore: Arc::new(Semaphore::new(size)),
+                queued: AtomicUsize::new(0),
+                closing: CancellationToken::new(),
+            }),
+        }
+    }
+
+    /// Spawns `future` as a task once fewer than `size` submitted tasks are
+    /// in flight, waiting until then.
+    ///
+    /// Submissions are served in order. Fails if the pool is closed, or
+    /// starts closing while this waits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of an executor.
+    pub async fn submit<F>(&self, future: F) -> Result<JoinHandle<F::Output>, PoolClosed>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        if self.is_closed() {
+            return Err(PoolClosed);
+        }
+        let permit = {
+            let _queued = Queued::new(&self.inner.queued);
+            let acquire = Arc::clone(&self.inner.semaphore).acquire_owned();
+            self.inner.closing.run_until_cancelled(acquire).await
+        };
+        let Some(Ok(permit)) = permit else {
+            return Err(PoolClosed);
+        };
+        Ok(crate::spawn(async move {
+            let _permit = permit;
+            future.await
+        }))
+    }
+
+    /// Stops taking submissions and waits for the tasks in flight to finish.
+    ///
+    /// Submissions still waiting for room fail with [`PoolClosed`].
+    pub async fn close(&self) {
+        self.inner.closing.cancel();
+        // Every permit is back once the last task has finished. This fails
+        // right away if another call has already closed the pool.
+        let permits = self.inner.semaphore.acquire_many(self.inner.size).await;
+        self.inner.semaphore.close();
+        drop(permits);
+    }
+
+    /// Returns whether [`close`](Self::close) has been called
+    pub fn is_closed(&self) -> bool {
+        self.inner.closing.is_cancelled()
+    }
+
+    /// Returns the most tasks the pool runs at once
+    pub fn size(&self) -> usize {
+        self.inner.size
+    }
+
+    /// Returns how many submitted tasks have not finished yet
+    pub fn in_flight(&self) -> usize {
+        self.inner.size - self.inner.semaphore.available_permits()
+    }
+
+    /// Returns how many submissions are waiting for room
+    pub fn queued(&self) -> usize {
+        self.inner.queued.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for WorkerPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerPool")
+            .field("size", &self.size())
+            .field("in_flight", &self.in_flight())
+            .field("queued", &self.queued())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<'a> Queued<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::AcqRel);
+        Self(queued)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl fmt::Display for PoolClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("worker pool closed")
+    }
+}
+
+impl std::error::Error for PoolClosed {}
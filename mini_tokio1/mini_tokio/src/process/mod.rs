@@ -0,0 +1,438 @@
+//! Asynchronous child processes.
+//!
+//! [`Command`] mirrors [`std::process::Command`], except that waiting for the
+//! child and talking to it through pipes do not block the executor thread.
+//! Many children can run concurrently on a single executor:
+//!
+//! ```
+//! use mini_tokio::{process::Command, Executor};
+//!
+//! let executor = Executor::new();
+//! let outputs = executor.block_on(async {
+//!     let mut first = Command::new("echo");
+//!     let mut second = Command::new("echo");
+//!     mini_tokio::try_join!(first.arg("one").output(), second.arg("two").output())
+//! });
+//! let (first, second) = outputs.unwrap();
+//! assert_eq!(first.stdout, b"one\n");
+//! assert_eq!(second.stdout, b"two\n");
+//! ```
+//!
+//! The executor learns that a child exited from a pidfd registered with its
+//! I/O driver, or from `SIGCHLD` on kernels without pidfds.
+
+use std::{
+    ffi::OsStr,
+    fmt,
+    fs::File,
+    future::poll_fn,
+    io::{self, Read, Write},
+    os::fd::{AsRawFd, OwnedFd, RawFd},
+    path::Path,
+    pin::Pin,
+    process::{ExitStatus, Output, Stdio},
+    task::{Context, Poll},
+};
+
+use crate::{
+    context,
+    io::{
+        driver::{cvt, Direction},
+        AsyncRead, AsyncReadExt, AsyncWrite, PollEvented,
+    },
+};
+
+mod reap;
+
+use reap::ExitWatch;
+pub(crate) use reap::{reap_abandoned, Orphans};
+
+/// Builds and spawns child processes, like [`std::process::Command`].
+pub struct Command {
+    std: std::process::Command,
+    kill_on_drop: bool,
+    /// Whether stdin, stdout and stderr were set, so that
+    /// [`output`](Self::output) only picks the ones that were not
+    stdio_set: [bool; 3],
+}
+
+/// A running child process.
+///
+/// A child that is dropped before it exits keeps running unless
+/// [`Command::kill_on_drop`] was set. Once it exits, it is reaped by the
+/// executor it was spawned on so that it does not linger as a zombie, or by
+/// a process-wide `SIGCHLD` handler if that executor has shut down.
+pub struct Child {
+    std: std::process::Child,
+    /// Only taken when the child is dropped
+    exit: Option<ExitWatch>,
+    orphans: Orphans,
+    kill_on_drop: bool,
+    /// The child's stdin, if it was set to [`Stdio::piped`]
+    pub stdin: Option<ChildStdin>,
+    /// The child's stdout, if it was set to [`Stdio::piped`]
+    pub stdout: Option<ChildStdout>,
+    /// The child's stderr, if it was set to [`Stdio::piped`]
+    pub stderr: Option<ChildStderr>,
+}
+
+/// The write end of a child's stdin pipe. Dropping it closes the pipe.
+pub struct ChildStdin {
+    io: PollEvented<File>,
+}
+
+/// The read end of a child's stdout pipe.
+pub struct ChildStdout {
+    io: PollEvented<File>,
+}
+
+/// The read end of a child's stderr pipe.
+pub struct ChildStderr {
+    io: PollEvented<File>,
+}
+
+impl Command {
+    /// Starts building a command that runs `program`
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        std::process::Command::new(program).into()
+    }
+
+    /// Adds an argument
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.std.arg(arg);
+        self
+    }
+
+    /// Adds several arguments
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.std.args(args);
+        self
+    }
+
+    /// Sets an environment variable
+    pub fn env(&mut self, key: impl AsRef<OsStr>, val: impl AsRef<OsStr>) -> &mut Self {
+        self.std.env(key, val);
+        self
+    }
+
+    /// Sets several environment variables
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.std.envs(vars);
+        self
+    }
+
+    /// Removes an environment variable
+    pub fn env_remove(&mut self, key: impl AsRef<OsStr>) -> &mut Self {
+        self.std.env_remove(key);
+        self
+    }
+
+    /// Clears every environment variable, including inherited ones
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.std.env_clear();
+        self
+    }
+
+    /// Sets the working directory of the child
+    pub fn current_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.std.current_dir(dir);
+        self
+    }
+
+    /// Sets the child's stdin, inherited by default
+    pub fn stdin(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+        self.stdio_set[0] = true;
+        self.std.stdin(cfg);
+        self
+    }
+
+    /// Sets the child's stdout, inherited by default
+    pub fn stdout(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+        self.stdio_set[1] = true;
+        self.std.stdout(cfg);
+        self
+    }
+
+    /// Sets the child's stderr, inherited by default
+    pub fn stderr(&mut self, cfg: impl Into<Stdio>) -> &mut Self {
+        self.stdio_set[2] = true;
+        self.std.stderr(cfg);
+        self
+    }
+
+    /// Kills the child when its [`Child`] is dropped before it has exited
+    pub fn kill_on_drop(&mut self, kill_on_drop: bool) -> &mut Self {
+        self.kill_on_drop = kill_on_drop;
+        self
+    }
+
+    /// Returns the wrapped [`std::process::Command`]
+    pub fn as_std(&self) -> &std::process::Command {
+        &self.std
+    }
+
+    /// Returns the wrapped [`std::process::Command`] mutably.
+    ///
+    /// Stdio set through it is overridden by the defaults of
+    /// [`output`](Self::output).
+    pub fn as_std_mut(&mut self) -> &mut std::process::Command {
+        &mut self.std
+    }
+
+    /// Starts the child.
+    ///
+    /// Fails outside of an executor, since the child's pipes and exit are
+    /// watched by the executor's I/O driver.
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        let orphans = context::orphans()?;
+        let mut std = self.std.spawn()?;
+        let stdin = std.stdin.take().map(OwnedFd::from);
+        let stdout = std.stdout.take().map(OwnedFd::from);
+        let stderr = std.stderr.take().map(OwnedFd::from);
+        let watched = ExitWatch::new(std.id()).and_then(|exit| {
+            Ok((
+                exit,
+                stdin.map(pipe).transpose()?,
+                stdout.map(pipe).transpose()?,
+                stderr.map(pipe).transpose()?,
+            ))
+        });
+        let (exit, stdin, stdout, stderr) = match watched {
+            Ok(watched) => watched,
+            Err(e) => {
+                let _ = std.kill();
+                let _ = std.wait();
+                return Err(e);
+            }
+        };
+
+        Ok(Child {
+            stdin: stdin.map(|io| ChildStdin { io }),
+            stdout: stdout.map(|io| ChildStdout { io }),
+            stderr: stderr.map(|io| ChildStderr { io }),
+            std,
+            exit: Some(exit),
+            orphans,
+            kill_on_drop: self.kill_on_drop,
+        })
+    }
+
+    /// Runs the child to completion and returns its exit status
+    pub async fn status(&mut self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait().await
+    }
+
+    /// Runs the child to completion and collects everything it writes.
+    ///
+    /// Unless set otherwise, stdin is null and stdout and stderr are piped,
+    /// as with [`std::process::Command::output`].
+    pub async fn output(&mut self) -> io::Result<Output> {
+        let defaults = [Stdio::null, Stdio::piped, Stdio::piped];
+        self.spawn_with_defaults(defaults)?.wait_with_output().await
+    }
+
+    /// Spawns the child with `defaults` for the stdio the caller did not
+    /// set, leaving the command as it was
+    fn spawn_with_defaults(&mut self, defaults: [fn() -> Stdio; 3]) -> io::Result<Child> {
+        self.set_unset_stdio(defaults);
+        let child = self.spawn();
+        self.set_unset_stdio([Stdio::inherit; 3]);
+        child
+    }
+
+    fn set_unset_stdio(&mut self, cfg: [fn() -> Stdio; 3]) {
+        if !self.stdio_set[0] {
+            self.std.stdin(cfg[0]());
+        }
+        if !self.stdio_set[1] {
+            self.std.stdout(cfg[1]());
+        }
+        if !self.stdio_set[2] {
+            self.std.stderr(cfg[2]());
+        }
+    }
+}
+
+impl From<std::process::Command> for Command {
+    fn from(std: std::process::Command) -> Self {
+        Self {
+            std,
+            kill_on_drop: false,
+            stdio_set: [false; 3],
+        }
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.std.fmt(f)
+    }
+}
+
+/// Makes one end of a pipe non-blocking and registers it with the reactor
+fn pipe(fd: OwnedFd) -> io::Result<PollEvented<File>> {
+    let flags = cvt(unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) })?;
+    cvt(unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) })?;
+    PollEvented::new(File::from(fd))
+}
+
+impl Child {
+    /// Returns the child's process ID
+    pub fn id(&self) -> u32 {
+        self.std.id()
+    }
+
+    /// Waits for the child to exit.
+    ///
+    /// Closes the child's stdin first, so that a child reading it until end
+    /// of file does not wait forever.
+    pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+        drop(self.stdin.take());
+        poll_fn(|cx| self.poll_wait(cx)).await
+    }
+
+    /// Returns the exit status if the child has exited, without waiting
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.std.try_wait()
+    }
+
+    /// Sends `SIGKILL` to the child without waiting for it to exit
+    pub fn start_kill(&mut self) -> io::Result<()> {
+        self.std.kill()
+    }
+
+    /// Kills the child and waits for it to exit
+    pub async fn kill(&mut self) -> io::Result<()> {
+        self.start_kill()?;
+        self.wait().await.map(drop)
+    }
+
+    /// Waits for the child to exit while collecting everything it writes to
+    /// its piped stdout and stderr
+    pub async fn wait_with_output(mut self) -> io::Result<Output> {
+        async fn read_all(reader: Option<impl AsyncRead + Unpin>) -> io::Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            if let Some(mut reader) = reader {
+                reader.read_to_end(&mut buf).await?;
+            }
+            Ok(buf)
+        }
+
+        let stdout = read_all(self.stdout.take());
+        let stderr = read_all(self.stderr.take());
+        let (status, stdout, stderr) = crate::try_join!(self.wait(), stdout, stderr)?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<ExitStatus>> {
+        loop {
+            if let Some(status) = self.std.try_wait()? {
+                return Poll::Ready(Ok(status));
+            }
+            let exit = self.exit.as_mut().expect("child already dropped");
+            if let Err(e) = std::task::ready!(exit.poll_exit(cx)) {
+                return Poll::Ready(Err(e));
+            }
+        }
+    }
+}
+
+impl Drop for Child {
+    fn drop(&mut self) {
+        if let Ok(Some(_)) = self.std.try_wait() {
+            return;
+        }
+        if self.kill_on_drop {
+            let _ = self.std.kill();
+        }
+        if let Some(exit) = self.exit.take() {
+            self.orphans.adopt(self.std.id(), exit);
+        }
+    }
+}
+
+impl fmt::Debug for Child {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Child")
+            .field("id", &self.std.id())
+            .field("stdin", &self.stdin)
+            .field("stdout", &self.stdout)
+            .field("stderr", &self.stderr)
+            .finish()
+    }
+}
+
+impl AsyncWrite for ChildStdin {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.io
+            .registration()
+            .poll_io(cx, Direction::Write, || (&*self.io).write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Does nothing, since a pipe can only be closed by dropping it
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+macro_rules! child_output {
+    ($name:ident) => {
+        impl AsyncRead for $name {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                self.io
+                    .registration()
+                    .poll_io(cx, Direction::Read, || (&*self.io).read(buf))
+            }
+        }
+    };
+}
+
+child_output!(ChildStdout);
+child_output!(ChildStderr);
+
+macro_rules! child_stdio_common {
+    ($name:ident) => {
+        impl AsRawFd for $name {
+            fn as_raw_fd(&self) -> RawFd {
+                self.io.as_raw_fd()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("fd", &self.io.as_raw_fd())
+                    .finish()
+            }
+        }
+    };
+}
+
+child_stdio_common!(ChildStdin);
+child_stdio_common!(ChildStdout);
+child_stdio_common!(ChildStderr);
@@ -0,0 +1,236 @@
+//! Finding out when a child has exited.
+//!
+//! A pidfd becomes readable once its process exits, so it can be watched by
+//! the I/O driver like any other descriptor. On kernels without pidfds
+//! (before 5.3) every `SIGCHLD` is taken as a hint to check again instead.
+//!
+//! Children dropped while still running are handed to [`Orphans`], whose
+//! single task per executor reaps them as they exit. Once the executor shuts
+//! down, its orphans are left in a process-wide table instead, which the
+//! `SIGCHLD` handler sweeps. The table holds up to 1024 children at a time;
+//! any more linger as zombies until the process exits.
+
+use std::{
+    future::poll_fn,
+    io, mem,
+    os::fd::{FromRawFd, OwnedFd},
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    executor::Spawner,
+    io::{driver::Direction, PollEvented},
+    signal::{
+        self,
+        unix::{signal, Signal, SignalKind},
+    },
+    task,
+};
+
+/// Most children whose executor is gone that can wait to be reaped at once
+const MAX_ABANDONED: usize = 1024;
+
+/// Children whose executor shut down before they exited, swept by the
+/// `SIGCHLD` handler; zero marks a free slot
+static ABANDONED: [AtomicI32; MAX_ABANDONED] = [const { AtomicI32::new(0) }; MAX_ABANDONED];
+
+pub(super) enum ExitWatch {
+    Pidfd(PollEvented<OwnedFd>),
+    Signal(Signal),
+}
+
+impl ExitWatch {
+    pub(super) fn new(pid: u32) -> io::Result<Self> {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd < 0 {
+            let err = io::Error::last_os_error();
+            return match err.raw_os_error() {
+                Some(libc::ENOSYS) => Ok(ExitWatch::Signal(signal(SignalKind::child())?)),
+                _ => Err(err),
+            };
+        }
+        // SAFETY: `pidfd_open` returned a fresh descriptor that nothing else
+        // owns, opened close-on-exec.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) };
+        Ok(ExitWatch::Pidfd(PollEvented::new(fd)?))
+    }
+
+    /// Completes when the child may have exited
+    pub(super) fn poll_exit(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self {
+            ExitWatch::Pidfd(fd) => fd
+                .registration()
+                .poll_ready(cx, Direction::Read)
+                .map_ok(drop),
+            // Any child's exit raises `SIGCHLD`, so this one may still be running.
+            ExitWatch::Signal(signal) => signal.poll_recv(cx).map(|_| Ok(())),
+        }
+    }
+}
+
+/// Children of an executor that were dropped before they exited
+#[derive(Clone)]
+pub(crate) struct Orphans {
+    state: Arc<Mutex<OrphanState>>,
+    /// Spawns the reaper task
+    spawner: Spawner,
+}
+
+struct OrphanState {
+    children: Vec<Orphan>,
+    /// Set while the reaper task is running
+    reaping: bool,
+    /// Set once the executor has shut down
+    closed: bool,
+    reaper: Option<Waker>,
+}
+
+struct Orphan {
+    pid: libc::pid_t,
+    exit: ExitWatch,
+}
+
+impl Orphans {
+    pub(crate) fn new(spawner: Spawner) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(OrphanState {
+                children: Vec::new(),
+                reaping: false,
+                closed: false,
+                reaper: None,
+            })),
+            spawner,
+        }
+    }
+
+    /// Reaps `child` once it exits, starting the reaper task if it is not
+    /// running
+    pub(super) fn adopt(&self, pid: u32, exit: ExitWatch) {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            abandon(pid as libc::pid_t);
+            return;
+        }
+        state.children.push(Orphan {
+            pid: pid as libc::pid_t,
+            exit,
+        });
+        if state.reaping {
+            if let Some(reaper) = state.reaper.take() {
+                reaper.wake();
+            }
+            return;
+        }
+        state.reaping = true;
+        drop(state);
+        let mut builder = task::Builder::new();
+        builder.name("mini-tokio-reaper");
+        self.spawner.spawn(reap(Arc::clone(&self.state)), &builder);
+    }
+
+    /// Hands the orphans to the `SIGCHLD` handler, along with any adopted
+    /// from now on, since the executor is shutting down
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        for orphan in state.children.drain(..) {
+            abandon(orphan.pid);
+        }
+        // The reaper finds nothing left to do, if it still gets to run.
+        if let Some(reaper) = state.reaper.take() {
+            reaper.wake();
+        }
+    }
+}
+
+/// Leaves `pid` to be reaped by the `SIGCHLD` handler
+fn abandon(pid: libc::pid_t) {
+    if signal::watch_children().is_err() {
+        return;
+    }
+    let added = ABANDONED.iter().any(|slot| {
+        slot.compare_exchange(0, pid, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+    });
+    // The child may have exited before it was added.
+    if added {
+        reap_abandoned();
+    }
+}
+
+/// Reaps the children in the table that have exited.
+///
+/// Called by the `SIGCHLD` handler, so it only makes async-signal-safe calls.
+pub(crate) fn reap_abandoned() {
+    for slot in &ABANDONED {
+        let pid = slot.load(Ordering::Acquire);
+        if pid == 0 {
+            continue;
+        }
+        // SAFETY: `siginfo_t` is plain data for which all zeroes is a valid
+        // value. `WNOWAIT` leaves the child to be reaped below, by whoever
+        // frees its slot, so no two callers reap the same pid.
+        let (res, exited) = unsafe {
+            let mut info: libc::siginfo_t = mem::zeroed();
+            let res = libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+            );
+            (res, info.si_pid() != 0)
+        };
+        if res != 0 {
+            // Not our child anymore, so there is nothing to reap.
+            let _ = slot.compare_exchange(pid, 0, Ordering::AcqRel, Ordering::Relaxed);
+        } else if exited
+            && slot
+                .compare_exchange(pid, 0, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+        {
+            // SAFETY: a null status pointer is allowed, and the child has
+            // exited, so this does not block.
+            unsafe { libc::waitpid(pid, std::ptr::null_mut(), 0) };
+        }
+    }
+}
+
+/// Runs until every orphan has been reaped
+async fn reap(state: Arc<Mutex<OrphanState>>) {
+    poll_fn(|cx| {
+        let mut state = state.lock().unwrap();
+        state.children.retain_mut(|orphan| !orphan.poll_reaped(cx));
+        if state.children.is_empty() {
+            state.reaping = false;
+            state.reaper = None;
+            return Poll::Ready(());
+        }
+        state.reaper = Some(cx.waker().clone());
+        Poll::Pending
+    })
+    .await
+}
+
+impl Orphan {
+    /// Returns whether the child is gone, either reaped or impossible to
+    /// wait for
+    fn poll_reaped(&mut self, cx: &mut Context<'_>) -> bool {
+        loop {
+            // SAFETY: a null status pointer is allowed, and `WNOHANG` makes
+            // this return right away. The pid cannot have been reused, since
+            // the child stays a zombie until it is reaped here.
+            if unsafe { libc::waitpid(self.pid, std::ptr::null_mut(), libc::WNOHANG) } != 0 {
+                return true;
+            }
+            match self.exit.poll_exit(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(_)) => return true,
+                Poll::Pending => return false,
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! A fast pseudo-random number generator, good enough to pick among
+//! branches or tasks but nothing else.
+
+use std::{
+    hash::{BuildHasher, RandomState},
+    thread,
+};
+
+/// xorshift64
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FastRand {
+    /// Never zero
+    state: u64,
+}
+
+impl FastRand {
+    /// Creates a generator with a random seed
+    pub(crate) fn new() -> Self {
+        Self::with_seed(RandomState::new().hash_one(thread::current().id()))
+    }
+
+    /// Creates a generator whose numbers are determined by `seed`
+    pub(crate) fn with_seed(seed: u64) -> Self {
+        // xorshift never leaves zero, so make sure not to start there.
+        Self { state: seed | 1 }
+    }
+
+    /// Returns a pseudo-random number in `0..n`, or 0 if `n` is 0
+    pub(crate) fn next_n(&mut self, n: usize) -> usize {
+        if n == 0 {
+            return 0;
+        }
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        (x % n as u64) as usize
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use crate::time::Instant;
+
+/// Stops calling a service that keeps failing.
+///
+/// The breaker starts [closed](CircuitState::Closed), letting calls
+/// through. After `failure_threshold` failures in a row it opens, failing
+/// calls right away with [`CircuitError::Open`]. Once `reset_timeout` has
+/// passed it is half-open: the next call goes through as a probe, closing
+/// the breaker if it succeeds and opening it again if it fails. Other calls
+/// are rejected while the probe runs.
+///
+/// ```
+/// use mini_tokio::{executor::Builder, resilience::{CircuitBreaker, CircuitError}};
+/// use std::time::Duration;
+///
+/// let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+/// executor.block_on(async {
+///     let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+///     for _ in 0..2 {
+///         let _ = breaker.call(|| async { Err::<(), _>("timeout") }).await;
+///     }
+///     let rejected = breaker.call(|| async { Ok::<_, &str>(()) }).await;
+///     assert!(matches!(rejected, Err(CircuitError::Open)));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: usize,
+    reset_timeout: Duration,
+    state: Mutex<State>,
+    next_probe: AtomicU64,
+}
+
+/// Whether a [`CircuitBreaker`] lets calls through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls fail right away
+    Open,
+    /// The next call goes through to probe whether the service is back
+    HalfOpen,
+}
+
+/// Returned by [`CircuitBreaker::call`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitError<E> {
+    /// The breaker is open, so the operation was not run
+    Open,
+    /// The operation ran and failed
+    Failed(E),
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        failures: usize,
+    },
+    Open {
+        until: Instant,
+    },
+    /// Holds the number of the probe running, if any
+    HalfOpen {
+        probe: Option<u64>,
+    },
+}
+
+/// Lets the breaker know if a call was dropped before it finished
+struct Call<'a> {
+    breaker: &'a CircuitBreaker,
+    /// The number of the probe, if this call is one
+    probe: Option<u64>,
+    finished: bool,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker that opens after `failure_threshold`
+    /// failures in a row and stays open for `reset_timeout`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `failure_threshold` is 0.
+    pub fn new(failure_threshold: usize, reset_timeout: Duration) -> Self {
+        assert!(
+            failure_threshold > 0,
+            "circuit breaker failure threshold cannot be 0"
+        );
+        Self {
+            failure_threshold,
+            reset_timeout,
+            state: Mutex::new(State::Closed { failures: 0 }),
+            next_probe: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns whether the breaker currently lets calls through
+    pub fn state(&self) -> CircuitState {
+        match *self.state.lock().unwrap() {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { until } if Instant::now() < until => CircuitState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Runs the operation built by `f` if the breaker lets it through,
+    /// counting its outcome
+    pub async fn call<F, Fut, T, E>(&self, f: F) -> Result<T, CircuitError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut call = self.start().ok_or(CircuitError::Open)?;
+        let result = f().await;
+        call.finish(result.is_ok());
+        result.map_err(CircuitError::Failed)
+    }
+
+    /// Admits a call, or returns `None` if the breaker rejects it
+    fn start(&self) -> Option<Call<'_>> {
+        let mut state = self.state.lock().unwrap();
+        let probe = match *state {
+            State::Closed { .. } => None,
+            State::Open { until } if Instant::now() < until => return None,
+            State::Open { .. } | State::HalfOpen { probe: None } => {
+                let probe = self.next_probe.fetch_add(1, Ordering::Relaxed);
+                *state = State::HalfOpen { probe: Some(probe) };
+                Some(probe)
+            }
+            State::HalfOpen { probe: Some(_) } => return None,
+        };
+        Some(Call {
+            breaker: self,
+            probe,
+            finished: false,
+        })
+    }
+}
+
+impl Call<'_> {
+    fn finish(&mut self, ok: bool) {
+        self.finished = true;
+        let breaker = self.breaker;
+        let mut state = breaker.state.lock().unwrap();
+        let failures = match (&*state, ok) {
+            (_, true) => 0,
+            (State::Closed { failures }, false) if self.probe.is_none() => failures + 1,
+            // A failed probe opens the breaker again.
+            _ => breaker.failure_threshold,
+        };
+        *state = if failures >= breaker.failure_threshold {
+            State::Open {
+                until: Instant::now() + breaker.reset_timeout,
+            }
+        } else {
+            State::Closed { failures }
+        };
+    }
+}
+
+impl Drop for Call<'_> {
+    fn drop(&mut self) {
+        // A probe that was dropped proved nothing, so let the next call
+        // probe instead, unless another call has settled the state since.
+        if self.finished || self.probe.is_none() {
+            return;
+        }
+        let mut state = self.breaker.state.lock().unwrap();
+        if let State::HalfOpen { probe } = &mut *state {
+            if *probe == self.probe {
+                *probe = None;
+            }
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for CircuitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::Open => f.write_str("circuit breaker is open"),
+            CircuitError::Failed(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for CircuitError<E> {}
@@ -0,0 +1,62 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+
+use crate::time::sleep;
+
+/// Runs the operation built by `f`, starting a second attempt if the first
+/// has not finished after `delay`.
+///
+/// Returns the output of whichever attempt finishes first, dropping the
+/// other. Hedging trades extra load for a shorter tail latency, so `delay`
+/// is usually set around the operation's high percentile latency.
+///
+/// ```
+/// use mini_tokio::{executor::Builder, resilience, time};
+/// use std::time::Duration;
+///
+/// let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+/// executor.block_on(async {
+///     let start = time::Instant::now();
+///     let mut attempt = 0;
+///     let winner = resilience::hedge(Duration::from_millis(100), || {
+///         attempt += 1;
+///         // The first attempt is stuck, the second is quick.
+///         let latency = if attempt == 1 { 10_000 } else { 50 };
+///         let id = attempt;
+///         async move {
+///             time::sleep(Duration::from_millis(latency)).await;
+///             id
+///         }
+///     })
+///     .await;
+///     assert_eq!(winner, 2);
+///     assert_eq!(start.elapsed(), Duration::from_millis(150));
+/// });
+/// ```
+pub async fn hedge<F, Fut>(delay: Duration, mut f: F) -> Fut::Output
+where
+    F: FnMut() -> Fut,
+    Fut: Future,
+{
+    let mut first = pin!(f());
+    let mut timer = pin!(sleep(delay));
+    let raced = poll_fn(|cx| match first.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(Some(output)),
+        Poll::Pending => timer.as_mut().poll(cx).map(|()| None),
+    })
+    .await;
+    if let Some(output) = raced {
+        return output;
+    }
+
+    let mut second = pin!(f());
+    poll_fn(|cx| match first.as_mut().poll(cx) {
+        Poll::Ready(output) => Poll::Ready(output),
+        Poll::Pending => second.as_mut().poll(cx),
+    })
+    .await
+}
@@ -0,0 +1,43 @@
+//! Combinators for calling services that sometimes fail or are slow.
+//!
+//! - [`retry`] runs an operation again after a failure, waiting between
+//!   attempts as a [`RetryPolicy`] says
+//! - [`CircuitBreaker`] stops calling a service that keeps failing, and
+//!   tries it again after a while
+//! - [`hedge`] starts a second attempt when the first one is slow, and
+//!   takes whichever finishes first
+//!
+//! All waiting goes through the executor's timer, so with a paused clock
+//! these run without waiting in real time:
+//!
+//! ```
+//! use mini_tokio::{executor::Builder, resilience::{self, Backoff, RetryPolicy}, time};
+//! use std::time::Duration;
+//!
+//! let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+//! executor.block_on(async {
+//!     let start = time::Instant::now();
+//!     let mut attempts = 0;
+//!     let policy = RetryPolicy::new(Backoff::exponential(
+//!         Duration::from_secs(1),
+//!         Duration::from_secs(60),
+//!     ));
+//!     let result = resilience::retry(&policy, || {
+//!         attempts += 1;
+//!         let attempt = attempts;
+//!         async move { if attempt < 3 { Err("unavailable") } else { Ok(attempt) } }
+//!     })
+//!     .await;
+//!     assert_eq!(result, Ok(3));
+//!     // Waited 1 second, then 2.
+//!     assert_eq!(start.elapsed(), Duration::from_secs(3));
+//! });
+//! ```
+
+mod circuit_breaker;
+mod hedge;
+mod retry;
+
+pub use circuit_breaker::{CircuitBreaker, CircuitError, CircuitState};
+pub use hedge::hedge;
+pub use retry::{retry, Backoff, RetryPolicy};
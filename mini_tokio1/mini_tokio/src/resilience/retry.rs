@@ -0,0 +1,107 @@
+use std::{future::Future, time::Duration};
+
+use crate::{macros::support::thread_rng_n, time::sleep};
+
+/// How long to wait before each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same delay before every retry
+    Fixed(Duration),
+    /// Starts at `initial` and doubles for each retry, up to `max`
+    Exponential {
+        /// The delay before the first retry
+        initial: Duration,
+        /// The longest delay
+        max: Duration,
+    },
+    /// Like [`Backoff::Exponential`], but waits a random time between zero
+    /// and that delay, so that many clients failing together do not retry
+    /// in lockstep
+    Jittered {
+        /// The longest delay before the first retry
+        initial: Duration,
+        /// The longest delay
+        max: Duration,
+    },
+}
+
+/// When and how often [`retry`] tries again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    backoff: Backoff,
+    max_retries: usize,
+}
+
+impl Backoff {
+    /// Exponential backoff from `initial` up to `max`
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self::Exponential { initial, max }
+    }
+
+    /// Jittered exponential backoff from `initial` up to `max`
+    pub fn jittered(initial: Duration, max: Duration) -> Self {
+        Self::Jittered { initial, max }
+    }
+
+    /// Returns the delay before the `retry`th retry, counting from 1
+    pub fn delay(&self, retry: usize) -> Duration {
+        match *self {
+            Self::Fixed(delay) => delay,
+            Self::Exponential { initial, max } => exponential(initial, max, retry),
+            Self::Jittered { initial, max } => {
+                let ceiling = exponential(initial, max, retry).as_nanos();
+                let ceiling = usize::try_from(ceiling).unwrap_or(usize::MAX);
+                let nanos = thread_rng_n(ceiling.saturating_add(1));
+                Duration::from_nanos(nanos as u64)
+            }
+        }
+    }
+}
+
+fn exponential(initial: Duration, max: Duration, retry: usize) -> Duration {
+    let factor = 2u32.saturating_pow(retry.saturating_sub(1) as u32);
+    initial.saturating_mul(factor).min(max)
+}
+
+impl RetryPolicy {
+    /// Retries up to 3 times, waiting as `backoff` says
+    pub fn new(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            max_retries: 3,
+        }
+    }
+
+    /// Sets how many times to retry after the first attempt
+    pub fn max_retries(&mut self, max_retries: usize) -> &mut Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Returns the backoff between attempts
+    pub fn backoff(&self) -> Backoff {
+        self.backoff
+    }
+}
+
+/// Runs the operation built by `f` until it succeeds or has been retried as
+/// often as `policy` allows.
+///
+/// Returns the first success, or the error of the last attempt.
+pub async fn retry<F, Fut, T, E>(policy: &RetryPolicy, mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retries = 0;
+    loop {
+        match f().await {
+            Ok(output) => return Ok(output),
+            Err(e) if retries == policy.max_retries => return Err(e),
+            Err(_) => {
+                retries += 1;
+                sleep(policy.backoff.delay(retries)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Asynchronous signal handling.
+//!
+//! Signals are caught by a process-wide handler that writes to a self-pipe,
+//! which listeners watch with their executor's I/O driver. See
+//! [`unix::signal`] for listening to any signal.
+
+use std::io;
+
+mod registry;
+pub mod unix;
+
+pub(crate) use registry::watch_children;
+
+/// Completes when the process receives `SIGINT`, usually from Ctrl-C in a
+/// terminal.
+///
+/// As with [`unix::signal`], the first call installs a handler that stays for
+/// the life of the process, so Ctrl-C no longer terminates it on its own.
+///
+/// ```no_run
+/// # mini_tokio::Executor::new().block_on(async {
+/// mini_tokio::signal::ctrl_c().await?;
+/// println!("interrupted");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+pub async fn ctrl_c() -> io::Result<()> {
+    let mut signal = unix::signal(unix::SignalKind::interrupt())?;
+    signal.recv().await;
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Process-wide signal state, shared by the listeners of every executor.
+//!
+//! The first listener for a signal installs a handler that marks the signal
+//! pending and writes a byte to a self-pipe. Every listener watches its own
+//! duplicate of the pipe's read end with its executor's I/O driver; whichever
+//! one reads the bytes first dispatches all pending signals to every
+//! listener.
+
+use std::{
+    fs::File,
+    io, mem,
+    os::fd::{AsRawFd, FromRawFd},
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    task::Waker,
+};
+
+use crate::io::driver::cvt;
+
+/// One more than the highest signal number on Linux
+const NSIG: usize = 65;
+
+/// Signals that cannot be caught or whose handlers must not return
+const FORBIDDEN: [libc::c_int; 5] = [
+    libc::SIGILL,
+    libc::SIGFPE,
+    libc::SIGKILL,
+    libc::SIGSEGV,
+    libc::SIGSTOP,
+];
+
+pub(super) struct Slot {
+    /// Set by the handler, cleared when the signal is dispatched
+    pending: AtomicBool,
+    /// Bumped whenever the signal is dispatched
+    generation: AtomicU64,
+    /// Listeners waiting for the next dispatch
+    waiters: Mutex<Vec<Waker>>,
+    /// The outcome of installing the handler, as an errno on failure
+    installed: OnceLock<Result<(), i32>>,
+}
+
+struct Pipe {
+    receiver: File,
+    /// Kept open for the life of the process, written through [`SENDER`]
+    _sender: File,
+}
+
+static SLOTS: [Slot; NSIG] = [const { Slot::new() }; NSIG];
+static PIPE: OnceLock<Result<Pipe, i32>> = OnceLock::new();
+/// The pipe's write end, for the handler
+static SENDER: AtomicI32 = AtomicI32::new(-1);
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            pending: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            waiters: Mutex::new(Vec::new()),
+            installed: OnceLock::new(),
+        }
+    }
+
+    pub(super) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Registers `waker` to be woken by the next dispatch of this signal
+    pub(super) fn add_waiter(&self, waker: &Waker) {
+        let mut waiters = self.waiters.lock().unwrap();
+        if !waiters.iter().any(|w| w.will_wake(waker)) {
+            waiters.push(waker.clone());
+        }
+    }
+}
+
+/// Installs the handler for `signum` if needed, returning its slot and a
+/// fresh duplicate of the pipe's read end
+pub(super) fn register(signum: libc::c_int) -> io::Result<(&'static Slot, File)> {
+    let slot = usize::try_from(signum)
+        .ok()
+        .filter(|&n| n > 0)
+        .and_then(|n| SLOTS.get(n))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid signal number {signum}"),
+            )
+        })?;
+    if FORBIDDEN.contains(&signum) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("signal {signum} cannot be listened for"),
+        ));
+    }
+
+    let pipe = PIPE
+        .get_or_init(open_pipe)
+        .as_ref()
+        .map_err(|&errno| io::Error::from_raw_os_error(errno))?;
+    slot.installed
+        .get_or_init(|| install(signum))
+        .map_err(io::Error::from_raw_os_error)?;
+    Ok((slot, pipe.receiver.try_clone()?))
+}
+
+/// Installs the handler for `SIGCHLD` if needed, which also reaps the
+/// children left by executors that shut down
+pub(crate) fn watch_children() -> io::Result<()> {
+    register(libc::SIGCHLD).map(drop)
+}
+
+/// Wakes the listeners of every signal that arrived since the last dispatch
+pub(super) fn dispatch() {
+    for slot in &SLOTS {
+        if slot.pending.swap(false, Ordering::AcqRel) {
+            slot.generation.fetch_add(1, Ordering::AcqRel);
+            let waiters = mem::take(&mut *slot.waiters.lock().unwrap());
+            for waker in waiters {
+                waker.wake();
+            }
+        }
+    }
+}
+
+fn open_pipe() -> Result<Pipe, i32> {
+    let mut fds = [0; 2];
+    cvt(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) })
+        .map_err(|e| e.raw_os_error().unwrap_or(libc::EIO))?;
+    // SAFETY: `pipe2` returned two fresh descriptors that nothing else owns.
+    let (receiver, sender) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    SENDER.store(sender.as_raw_fd(), Ordering::Release);
+    Ok(Pipe {
+        receiver,
+        _sender: sender,
+    })
+}
+
+fn install(signum: libc::c_int) -> Result<(), i32> {
+    // SAFETY: `sigaction` is plain data for which all zeroes is a valid
+    // value, and `handle` has the signature `sa_sigaction` expects without
+    // `SA_SIGINFO`.
+    let res = unsafe {
+        let mut action: libc::sigaction = mem::zeroed();
+        action.sa_sigaction = handle as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        action.sa_flags = libc::SA_RESTART;
+        libc::sigemptyset(&mut action.sa_mask);
+        libc::sigaction(signum, &action, std::ptr::null_mut())
+    };
+    cvt(res)
+        .map(drop)
+        .map_err(|e| e.raw_os_error().unwrap_or(libc::EIO))
+}
+
+extern "C" fn handle(signum: libc::c_int) {
+    // SAFETY: reading and restoring errno is always sound, and besides
+    // atomics the handler only calls `write` and the wait functions, which
+    // are async-signal-safe. A full pipe is fine, since a byte is already
+    // waiting to be read.
+    unsafe {
+        let errno = *libc::__errno_location();
+        if signum == libc::SIGCHLD {
+            crate::process::reap_abandoned();
+        }
+        if let Some(slot) = SLOTS.get(signum as usize) {
+            slot.pending.store(true, Ordering::Release);
+        }
+        let byte = 1u8;
+        libc::write(
+            SENDER.load(Ordering::Acquire),
+            &byte as *const u8 as *const libc::c_void,
+            1,
+        );
+        *libc::__errno_location() = errno;
+    }
+}
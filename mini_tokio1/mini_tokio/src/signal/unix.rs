@@ -0,0 +1,172 @@
+//! Listening for any Unix signal.
+
+use std::{
+    fs::File,
+    future::poll_fn,
+    io::{self, Read},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use super::registry::{self, Slot};
+use crate::io::{driver::Direction, PollEvented};
+
+/// A Unix signal, such as `SIGTERM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignalKind(libc::c_int);
+
+impl SignalKind {
+    /// Wraps a raw signal number
+    pub const fn from_raw(signum: i32) -> Self {
+        Self(signum)
+    }
+
+    /// Returns the raw signal number
+    pub const fn as_raw_value(&self) -> i32 {
+        self.0
+    }
+
+    /// `SIGALRM`, sent when a real-time timer expires
+    pub const fn alarm() -> Self {
+        Self(libc::SIGALRM)
+    }
+
+    /// `SIGCHLD`, sent when a child process exits or stops
+    pub const fn child() -> Self {
+        Self(libc::SIGCHLD)
+    }
+
+    /// `SIGHUP`, sent when the controlling terminal is closed
+    pub const fn hangup() -> Self {
+        Self(libc::SIGHUP)
+    }
+
+    /// `SIGINT`, sent by Ctrl-C in a terminal
+    pub const fn interrupt() -> Self {
+        Self(libc::SIGINT)
+    }
+
+    /// `SIGIO`, sent when I/O is possible on a descriptor set up for it
+    pub const fn io() -> Self {
+        Self(libc::SIGIO)
+    }
+
+    /// `SIGPIPE`, sent when writing to a pipe with no reader
+    pub const fn pipe() -> Self {
+        Self(libc::SIGPIPE)
+    }
+
+    /// `SIGQUIT`, sent by Ctrl-\ in a terminal
+    pub const fn quit() -> Self {
+        Self(libc::SIGQUIT)
+    }
+
+    /// `SIGTERM`, the polite request to terminate
+    pub const fn terminate() -> Self {
+        Self(libc::SIGTERM)
+    }
+
+    /// `SIGUSR1`, for the application's own use
+    pub const fn user_defined1() -> Self {
+        Self(libc::SIGUSR1)
+    }
+
+    /// `SIGUSR2`, for the application's own use
+    pub const fn user_defined2() -> Self {
+        Self(libc::SIGUSR2)
+    }
+
+    /// `SIGWINCH`, sent when the terminal is resized
+    pub const fn window_change() -> Self {
+        Self(libc::SIGWINCH)
+    }
+}
+
+/// Receives a notification each time a signal arrives.
+///
+/// Signals that arrive in quick succession, before the listener is polled,
+/// may be coalesced into one notification. Also a [`Stream`] of `()`.
+pub struct Signal {
+    slot: &'static Slot,
+    /// The generation of the slot last reported
+    seen: u64,
+    pipe: PollEvented<File>,
+}
+
+/// Listens for signals of `kind` sent after the call.
+///
+/// The first listener for a signal installs a handler for it that stays for
+/// the life of the process, so from then on the signal's default action, such
+/// as terminating the process, no longer happens.
+///
+/// Fails outside of an executor and for signals that cannot be caught, such
+/// as `SIGKILL`.
+///
+/// ```no_run
+/// use mini_tokio::signal::unix::{signal, SignalKind};
+///
+/// # mini_tokio::Executor::new().block_on(async {
+/// let mut terminate = signal(SignalKind::terminate())?;
+/// terminate.recv().await;
+/// println!("shutting down");
+/// # std::io::Result::Ok(())
+/// # }).unwrap();
+/// ```
+pub fn signal(kind: SignalKind) -> io::Result<Signal> {
+    let (slot, pipe) = registry::register(kind.0)?;
+    Ok(Signal {
+        slot,
+        seen: slot.generation(),
+        pipe: PollEvented::new(pipe)?,
+    })
+}
+
+impl Signal {
+    /// Waits for the next signal, returning `None` if no more can arrive
+    pub async fn recv(&mut self) -> Option<()> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next signal, returning `None` if no more can arrive
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        loop {
+            let generation = self.slot.generation();
+            if generation != self.seen {
+                self.seen = generation;
+                return Poll::Ready(Some(()));
+            }
+            self.slot.add_waiter(cx.waker());
+
+            // The pipe carries a byte for every signal, not only this one,
+            // so whatever is read is dispatched to all listeners.
+            let mut buf = [0; 64];
+            let read = self
+                .pipe
+                .registration()
+                .poll_io(cx, Direction::Read, || (&*self.pipe).read(&mut buf));
+            match read {
+                Poll::Ready(Ok(_)) => registry::dispatch(),
+                Poll::Ready(Err(_)) => return Poll::Ready(None),
+                // Another listener may have dispatched since the check above.
+                Poll::Pending if self.slot.generation() != self.seen => {}
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl Stream for Signal {
+    type Item = ();
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        self.get_mut().poll_recv(cx)
+    }
+}
+
+impl std::fmt::Debug for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signal").field("seen", &self.seen).finish()
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
+
+use futures::{stream::FuturesUnordered, Stream, StreamExt};
+
+use crate::{Cancelled, JoinHandle};
+
+/// Runs `f` on every item of `stream`, each as its own spawned task.
+///
+/// At most `limit` tasks run at once, and `None` means no limit. Items are
+/// only taken from the stream while there is room for another task.
+///
+/// Returns once the stream has ended and every task has finished, with
+/// `Err(Cancelled)` if any of them was cancelled before finishing, such as
+/// by the executor shutting down.
+///
+/// ```
+/// use mini_tokio::{executor::Builder, stream, time};
+/// use std::time::Duration;
+///
+/// let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+/// executor.block_on(async {
+///     let start = time::Instant::now();
+///     let ids = futures::stream::iter(0..6);
+///     stream::for_each_concurrent(ids, 2, |_| time::sleep(Duration::from_secs(1)))
+///         .await
+///         .unwrap();
+///     // Three rounds of two tasks each.
+///     assert_eq!(start.elapsed(), Duration::from_secs(3));
+/// });
+/// ```
+///
+/// # Panics
+///
+/// Panics if `limit` is `Some(0)`, or when called outside of an executor.
+pub async fn for_each_concurrent<S, F, Fut>(
+    stream: S,
+    limit: impl Into<Option<usize>>,
+    mut f: F,
+) -> Result<(), Cancelled>
+where
+    S: Stream,
+    F: FnMut(S::Item) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let limit = limit.into();
+    assert!(limit != Some(0), "for_each_concurrent limit cannot be 0");
+
+    let mut stream = pin!(stream);
+    let mut ended = false;
+    let mut running = FuturesUnordered::<JoinHandle<()>>::new();
+    let mut result = Ok(());
+    poll_fn(|cx| loop {
+        while let Poll::Ready(Some(done)) = running.poll_next_unpin(cx) {
+            result = result.clone().and(done);
+        }
+        if ended || limit.is_some_and(|limit| running.len() >= limit) {
+            return if running.is_empty() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            };
+        }
+        match stream.as_mut().poll_next(cx) {
+            Poll::Ready(Some(item)) => running.push(crate::spawn(f(item))),
+            Poll::Ready(None) => ended = true,
+            Poll::Pending => return Poll::Pending,
+        }
+    })
+    .await;
+    result
+}
@@ -0,0 +1,33 @@
+//! Streams: asynchronous sequences of values.
+//!
+//! This module re-exports [`Stream`] and [`StreamExt`] from `futures`, and
+//! adds what connects them to the runtime:
+//!
+//! - [`IntervalStream`], [`ReceiverStream`] and [`UnboundedReceiverStream`]
+//!   turn the runtime's timers and channels into streams
+//! - [`for_each_concurrent`] runs a future per item as a spawned task, with
+//!   a limit on how many run at once
+//! - [`stream!`](crate::stream!) and [`try_stream!`](crate::try_stream!)
+//!   write a stream as an async block that `yield`s its items
+//!
+//! ```
+//! use mini_tokio::{stream::StreamExt, Executor};
+//!
+//! let executor = Executor::new();
+//! let squares: Vec<u32> = executor.block_on(async {
+//!     let numbers = mini_tokio::stream! {
+//!         for i in 1..=3 {
+//!             yield mini_tokio::spawn(async move { i }).await.unwrap();
+//!         }
+//!     };
+//!     numbers.map(|i| i * i).collect().await
+//! });
+//! assert_eq!(squares, [1, 4, 9]);
+//! ```
+
+mod for_each;
+mod wrappers;
+
+pub use for_each::for_each_concurrent;
+pub use futures::stream::{Stream, StreamExt};
+pub use wrappers::{IntervalStream, ReceiverStream, UnboundedReceiverStream};
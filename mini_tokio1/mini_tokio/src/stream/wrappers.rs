@@ -0,0 +1,96 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use crate::{
+    sync::mpsc::{Receiver, UnboundedReceiver},
+    time::{Instant, Interval},
+};
+
+/// A stream of the ticks of an [`Interval`]. It never ends.
+#[derive(Debug)]
+pub struct IntervalStream {
+    interval: Interval,
+}
+
+impl IntervalStream {
+    /// Wraps `interval`
+    pub fn new(interval: Interval) -> Self {
+        Self { interval }
+    }
+
+    /// Returns the wrapped interval
+    pub fn into_inner(self) -> Interval {
+        self.interval
+    }
+}
+
+impl Stream for IntervalStream {
+    type Item = Instant;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Instant>> {
+        self.interval.poll_tick(cx).map(Some)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+macro_rules! receiver_stream {
+    ($(#[$doc:meta])* $name:ident, $receiver:ident) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<T> {
+            receiver: $receiver<T>,
+        }
+
+        impl<T> $name<T> {
+            /// Wraps `receiver`
+            pub fn new(receiver: $receiver<T>) -> Self {
+                Self { receiver }
+            }
+
+            /// Returns the wrapped receiver
+            pub fn into_inner(self) -> $receiver<T> {
+                self.receiver
+            }
+
+            /// Closes the channel, so that the stream ends once the values
+            /// already sent are taken
+            pub fn close(&mut self) {
+                self.receiver.close();
+            }
+        }
+
+        impl<T> Stream for $name<T> {
+            type Item = T;
+
+            fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+                self.receiver.poll_recv(cx)
+            }
+        }
+
+        impl<T> From<$receiver<T>> for $name<T> {
+            fn from(receiver: $receiver<T>) -> Self {
+                Self::new(receiver)
+            }
+        }
+    };
+}
+
+receiver_stream!(
+    /// A stream of the values received by a bounded [`Receiver`]. It ends
+    /// once the channel is closed and empty.
+    ReceiverStream,
+    Receiver
+);
+receiver_stream!(
+    /// A stream of the values received by an [`UnboundedReceiver`]. It ends
+    /// once the channel is closed and empty.
+    UnboundedReceiverStream,
+    UnboundedReceiver
+);
@@ -0,0 +1,319 @@
+//! Erlang-style supervision of tasks that should keep running.
+//!
+//! A [`Supervisor`] starts a group of children, each from a factory that
+//! builds its future, and runs each child as its own task. A child that
+//! returns `Ok(())` is done for good. One that panics or returns an error
+//! is restarted after a backoff, along with the children that its
+//! [`Strategy`] says depend on it.
+//!
+//! If children fail more often than the restart intensity allows, the
+//! supervisor stops the rest and returns a [`SupervisorError`]. Since
+//! [`Supervisor::run`] is a future that fails like a child, a supervisor
+//! can itself be the child of another one, forming a supervision tree.
+//!
+//! ```
+//! use mini_tokio::{executor::Builder, supervisor::{Strategy, Supervisor}};
+//! use std::sync::{
+//!     atomic::{AtomicUsize, Ordering},
+//!     Arc,
+//! };
+//!
+//! let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+//! let attempts = Arc::new(AtomicUsize::new(0));
+//! let result = executor.block_on({
+//!     let attempts = Arc::clone(&attempts);
+//!     let mut supervisor = Supervisor::new(Strategy::OneForOne);
+//!     supervisor.child("flaky", move || {
+//!         let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+//!         async move {
+//!             if attempt < 2 {
+//!                 return Err("transient failure");
+//!             }
+//!             Ok(())
+//!         }
+//!     });
+//!     supervisor.run()
+//! });
+//! assert!(result.is_ok());
+//! assert_eq!(attempts.load(Ordering::SeqCst), 3);
+//! ```
+
+use std::{
+    any::Any,
+    collections::VecDeque,
+    fmt,
+    future::{poll_fn, Future},
+    panic::AssertUnwindSafe,
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+
+use crate::{
+    sync::CancellationToken,
+    time::{sleep, Instant},
+    JoinHandle,
+};
+
+/// Which children are restarted when one of them fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Only the failed child
+    #[default]
+    OneForOne,
+    /// Every child that is still running, along with the failed one
+    OneForAll,
+    /// The failed child and the running children added after it
+    RestForOne,
+}
+
+/// Starts children and restarts them when they fail.
+///
+/// Cloning a supervisor copies its configuration, not its running
+/// children.
+#[derive(Clone)]
+pub struct Supervisor {
+    strategy: Strategy,
+    max_restarts: usize,
+    within: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    children: Vec<Arc<ChildSpec>>,
+}
+
+/// Returned by [`Supervisor::run`] when children failed more often than
+/// the restart intensity allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisorError {
+    child: String,
+    reason: String,
+}
+
+type Factory = dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync;
+
+struct ChildSpec {
+    name: String,
+    factory: Box<Factory>,
+}
+
+/// One run of a child
+struct Running {
+    handle: JoinHandle<Result<(), String>>,
+    /// Stops this run of the child
+    token: CancellationToken,
+}
+
+/// Stops every child when the supervisor is done or dropped
+struct StopOnDrop(CancellationToken);
+
+impl Supervisor {
+    /// Creates a supervisor without children.
+    ///
+    /// By default it allows 3 restarts within 5 seconds, and waits from
+    /// 100 milliseconds up to 10 seconds before a restart.
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            max_restarts: 3,
+            within: Duration::from_secs(5),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            children: Vec::new(),
+        }
+    }
+
+    /// Allows at most `max_restarts` restarts within any period of
+    /// `within`. The failure after that stops the supervisor.
+    pub fn restart_intensity(&mut self, max_restarts: usize, within: Duration) -> &mut Self {
+        self.max_restarts = max_restarts;
+        self.within = within;
+        self
+    }
+
+    /// Waits `initial` before the first restart, doubling the wait for each
+    /// further restart within the intensity period, up to `max`
+    pub fn backoff(&mut self, initial: Duration, max: Duration) -> &mut Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Adds a child, started by calling `factory`.
+    ///
+    /// Children are started in the order they are added, which is also the
+    /// order [`Strategy::RestForOne`] goes by.
+    pub fn child<F, Fut, E>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: fmt::Display,
+    {
+        self.children.push(Arc::new(ChildSpec {
+            name: name.into(),
+            factory: Box::new(move || {
+                factory()
+                    .map(|result| result.map_err(|e| e.to_string()))
+                    .boxed()
+            }),
+        }));
+        self
+    }
+
+    /// Runs the children until all of them have returned `Ok(())`, or
+    /// until they fail too often.
+    ///
+    /// Dropping the returned future stops the children.
+    ///
+    /// # Panics
+    ///
+    /// The future panics if polled outside of an executor.
+    pub fn run(&self) -> impl Future<Output = Result<(), SupervisorError>> + Send + 'static {
+        let supervisor = self.clone();
+        async move { supervisor.supervise().await }
+    }
+
+    async fn supervise(self) -> Result<(), SupervisorError> {
+        let stop = StopOnDrop(CancellationToken::new());
+        let mut running: Vec<_> = self
+            .children
+            .iter()
+            .map(|child| Some(start(child, &stop.0)))
+            .collect();
+        let mut restarts = VecDeque::new();
+
+        while running.iter().any(Option::is_some) {
+            let (index, exit) = poll_fn(|cx| {
+                for (index, child) in running.iter().enumerate() {
+                    if let Some(Poll::Ready(exit)) = child.as_ref().map(|c| c.handle.poll(cx)) {
+                        return Poll::Ready((index, exit));
+                    }
+                }
+                Poll::Pending
+            })
+            .await;
+            running[index] = None;
+            let reason = match exit {
+                Ok(Ok(())) => continue,
+                Ok(Err(reason)) => reason,
+                Err(cancelled) => cancelled.to_string(),
+            };
+
+            let now = Instant::now();
+            while restarts
+                .front()
+                .is_some_and(|&restart| now.duration_since(restart) >= self.within)
+            {
+                restarts.pop_front();
+            }
+            if restarts.len() >= self.max_restarts {
+                return Err(SupervisorError {
+                    child: self.children[index].name.clone(),
+                    reason,
+                });
+            }
+            restarts.push_back(now);
+
+            let restart: Vec<_> = match self.strategy {
+                Strategy::OneForOne => vec![index],
+                Strategy::OneForAll => (0..running.len())
+                    .filter(|&i| i == index || running[i].is_some())
+                    .collect(),
+                Strategy::RestForOne => (index..running.len())
+                    .filter(|&i| i == index || running[i].is_some())
+                    .collect(),
+            };
+            for &i in &restart {
+                if let Some(child) = running[i].take() {
+                    child.token.cancel();
+                    let _ = child.handle.await;
+                }
+            }
+            sleep(self.restart_delay(restarts.len())).await;
+            for &i in &restart {
+                running[i] = Some(start(&self.children[i], &stop.0));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns how long to wait before the `nth` recent restart, counting
+    /// from 1
+    fn restart_delay(&self, nth: usize) -> Duration {
+        let factor = 2u32.saturating_pow(nth.saturating_sub(1) as u32);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Spawns a run of `child`, turning a panic into a failure
+fn start(child: &Arc<ChildSpec>, parent: &CancellationToken) -> Running {
+    let token = parent.child_token();
+    let future = AssertUnwindSafe((child.factory)()).catch_unwind();
+    let handle = crate::spawn({
+        let token = token.clone();
+        async move {
+            match token.run_until_cancelled(future).await {
+                Some(Ok(result)) => result,
+                Some(Err(panic)) => Err(panic_message(panic)),
+                // Stopped by the supervisor, which no longer waits for it.
+                None => Ok(()),
+            }
+        }
+    });
+    Running { handle, token }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let message = match panic.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => panic.downcast_ref::<String>().map_or("Box<dyn Any>", |m| m),
+    };
+    format!("panicked: {message}")
+}
+
+impl fmt::Debug for Supervisor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let children: Vec<_> = self.children.iter().map(|child| &child.name).collect();
+        f.debug_struct("Supervisor")
+            .field("strategy", &self.strategy)
+            .field("max_restarts", &self.max_restarts)
+            .field("within", &self.within)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("children", &children)
+            .finish()
+    }
+}
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+impl SupervisorError {
+    /// Returns the name of the child whose failure stopped the supervisor
+    pub fn child(&self) -> &str {
+        &self.child
+    }
+
+    /// Returns why that child failed
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl fmt::Display for SupervisorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "restart intensity exceeded, child `{}` failed: {}",
+            self.child, self.reason
+        )
+    }
+}
+
+impl std::error::Error for SupervisorError {}
@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    pin::{pin, Pin},
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll, Waker},
+};
+
+/// Cooperative cancellation shared by any number of tasks.
+///
+/// Clones share the same state, so cancelling one cancels them all. Tokens
+/// made with [`child_token`](Self::child_token) are cancelled along with
+/// their parent, but cancelling a child leaves the parent alone, which lets
+/// a request cancel the work it started without affecting its siblings.
+///
+/// ```
+/// use mini_tokio::{sync::CancellationToken, time, Executor};
+/// use std::time::Duration;
+///
+/// let executor = Executor::new();
+/// executor.block_on(async {
+///     let request = CancellationToken::new();
+///     let worker = mini_tokio::spawn({
+///         let token = request.child_token();
+///         async move {
+///             token
+///                 .run_until_cancelled(time::sleep(Duration::from_secs(60)))
+///                 .await
+///         }
+///     });
+///
+///     request.cancel();
+///     assert_eq!(worker.await.unwrap(), None);
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    node: Arc<Node>,
+}
+
+/// Waits for a token to be cancelled, returned by
+/// [`CancellationToken::cancelled`].
+pub struct WaitForCancellation<'a> {
+    node: &'a Node,
+    /// Key of this future's waker in the node, once it has been polled
+    key: Option<usize>,
+}
+
+#[derive(Default)]
+struct Node {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    cancelled: bool,
+    /// Child tokens, which do not keep each other alive
+    children: Vec<Weak<Node>>,
+    waiters: HashMap<usize, Waker>,
+    next_key: usize,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels this token, its clones and all of its children, waking every
+    /// task waiting on them. Does nothing if it is already cancelled.
+    pub fn cancel(&self) {
+        self.node.cancel();
+    }
+
+    /// Returns whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.node.state.lock().unwrap().cancelled
+    }
+
+    /// Waits until the token is cancelled
+    pub fn cancelled(&self) -> WaitForCancellation<'_> {
+        WaitForCancellation {
+            node: &self.node,
+            key: None,
+        }
+    }
+
+    /// Creates a token that is cancelled when this one is.
+    ///
+    /// The child is created cancelled if this token already is.
+    pub fn child_token(&self) -> CancellationToken {
+        let child = Arc::new(Node::default());
+        let mut state = self.node.state.lock().unwrap();
+        if state.cancelled {
+            child.state.lock().unwrap().cancelled = true;
+        } else {
+            state.children.retain(|child| child.strong_count() > 0);
+            state.children.push(Arc::downgrade(&child));
+        }
+        CancellationToken { node: child }
+    }
+
+    /// Runs `future` until it completes or the token is cancelled, whichever
+    /// comes first.
+    ///
+    /// Returns `None` if the token was cancelled first, dropping `future`.
+    /// Cancellation wins if both are ready.
+    pub async fn run_until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut future = pin!(future);
+        let mut cancelled = pin!(self.cancelled());
+        std::future::poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+            future.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl Node {
+    fn cancel(&self) {
+        let (waiters, children) = {
+            let mut state = self.state.lock().unwrap();
+            if state.cancelled {
+                return;
+            }
+            state.cancelled = true;
+            (
+                std::mem::take(&mut state.waiters),
+                std::mem::take(&mut state.children),
+            )
+        };
+        waiters.into_values().for_each(Waker::wake);
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
+
+impl Future for WaitForCancellation<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.node.state.lock().unwrap();
+        if state.cancelled {
+            return Poll::Ready(());
+        }
+        match self.key {
+            Some(key) => {
+                state.waiters.insert(key, cx.waker().clone());
+            }
+            None => {
+                let key = state.next_key;
+                state.next_key += 1;
+                state.waiters.insert(key, cx.waker().clone());
+                drop(state);
+                self.key = Some(key);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForCancellation<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.node.state.lock().unwrap().waiters.remove(&key);
+        }
+    }
+}
+
+impl fmt::Debug for WaitForCancellation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitForCancellation")
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,14 @@
+//! Synchronization primitives for tasks.
+//!
+//! Unlike their `std::sync` counterparts these never block the thread:
+//! waiting suspends the task instead, so that the executor can run others.
+
+mod cancellation;
+pub mod mpsc;
+pub mod oneshot;
+mod semaphore;
+
+pub use cancellation::{CancellationToken, WaitForCancellation};
+pub use semaphore::{
+    AcquireError, OwnedSemaphorePermit, Semaphore, SemaphorePermit, TryAcquireError,
+};
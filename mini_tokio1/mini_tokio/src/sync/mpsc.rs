@@ -0,0 +1,379 @@
+//! Multi-producer, single-consumer channels.
+//!
+//! [`channel`] creates a bounded channel, whose senders wait while it is
+//! full, which applies backpressure to producers that outpace the consumer.
+//! [`unbounded_channel`] creates one whose senders never wait.
+//!
+//! Either kind closes once every sender is dropped or the receiver is
+//! dropped or [closed](Receiver::close). The receiver still gets the values
+//! sent before that.
+//!
+//! ```
+//! use mini_tokio::{sync::mpsc, Executor};
+//!
+//! let executor = Executor::new();
+//! let sum = executor.block_on(async {
+//!     let (tx, mut rx) = mpsc::channel(4);
+//!     for i in 1..=3 {
+//!         let tx = tx.clone();
+//!         mini_tokio::spawn(async move { tx.send(i).await.unwrap() });
+//!     }
+//!     drop(tx);
+//!
+//!     let mut sum = 0;
+//!     while let Some(i) = rx.recv().await {
+//!         sum += i;
+//!     }
+//!     sum
+//! });
+//! assert_eq!(sum, 6);
+//! ```
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::poll_fn,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Creates a channel that holds up to `capacity` values
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "mpsc channel capacity cannot be 0");
+    let chan = Chan::new(Some(capacity));
+    (
+        Sender {
+            chan: Arc::clone(&chan),
+        },
+        Receiver { chan },
+    )
+}
+
+/// Creates a channel without a limit on the values it holds
+pub fn unbounded_channel<T>() -> (UnboundedSender<T>, UnboundedReceiver<T>) {
+    let chan = Chan::new(None);
+    (
+        UnboundedSender {
+            chan: Arc::clone(&chan),
+        },
+        UnboundedReceiver { chan },
+    )
+}
+
+/// Sends values into a bounded channel. Cloning it adds a sender.
+pub struct Sender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Receives the values sent into a bounded channel.
+pub struct Receiver<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Sends values into an unbounded channel. Cloning it adds a sender.
+pub struct UnboundedSender<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Receives the values sent into an unbounded channel.
+pub struct UnboundedReceiver<T> {
+    chan: Arc<Chan<T>>,
+}
+
+/// Returned by a send when the channel is closed, with the value that could
+/// not be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// Returned by [`Sender::try_send`] with the value that could not be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// The channel is full
+    Full(T),
+    /// The channel is closed
+    Closed(T),
+}
+
+/// Returned by `try_recv` when there is no value to take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The channel is empty but still open
+    Empty,
+    /// The channel is empty and closed
+    Disconnected,
+}
+
+struct Chan<T> {
+    state: Mutex<State<T>>,
+    /// `None` for an unbounded channel
+    capacity: Option<usize>,
+}
+
+struct State<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    /// Set once the receiver is dropped or closed
+    closed: bool,
+    rx_waker: Option<Waker>,
+    /// Senders waiting for room in a full channel
+    tx_wakers: Vec<Waker>,
+}
+
+impl<T> Chan<T> {
+    fn new(capacity: Option<usize>) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                senders: 1,
+                closed: false,
+                rx_waker: None,
+                tx_wakers: Vec::new(),
+            }),
+            capacity,
+        })
+    }
+
+    /// Queues `value` if there is room, otherwise registering `waker`, if
+    /// any, to be woken once there may be
+    fn push(&self, value: T, waker: Option<&Waker>) -> Result<(), TrySendError<T>> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(TrySendError::Closed(value));
+        }
+        if self.capacity.is_some_and(|cap| state.queue.len() >= cap) {
+            if let Some(waker) = waker {
+                state.tx_wakers.push(waker.clone());
+            }
+            return Err(TrySendError::Full(value));
+        }
+        state.queue.push_back(value);
+        let rx_waker = state.rx_waker.take();
+        drop(state);
+        if let Some(rx_waker) = rx_waker {
+            rx_waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Takes the next value, otherwise registering `waker`, if any, to be
+    /// woken once there may be one
+    fn pop(&self, waker: Option<&Waker>) -> Result<T, TryRecvError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.queue.pop_front() {
+            // Every waiting sender tries again, since one that was woken may
+            // have been dropped instead of sending.
+            let tx_wakers = std::mem::take(&mut state.tx_wakers);
+            drop(state);
+            tx_wakers.into_iter().for_each(Waker::wake);
+            return Ok(value);
+        }
+        if state.senders == 0 || state.closed {
+            return Err(TryRecvError::Disconnected);
+        }
+        if let Some(waker) = waker {
+            state.rx_waker = Some(waker.clone());
+        }
+        Err(TryRecvError::Empty)
+    }
+
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.pop(Some(cx.waker())) {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    fn close(&self) {
+        let wakers = {
+            let mut state = self.state.lock().unwrap();
+            state.closed = true;
+            std::mem::take(&mut state.tx_wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Closes the channel and drops the values nobody will receive now
+    fn drop_receiver(&self) {
+        self.close();
+        let queue = std::mem::take(&mut self.state.lock().unwrap().queue);
+        drop(queue);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    fn add_sender(self: &Arc<Self>) -> Arc<Self> {
+        self.state.lock().unwrap().senders += 1;
+        Arc::clone(self)
+    }
+
+    fn drop_sender(&self) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.senders -= 1;
+            if state.senders > 0 {
+                return;
+            }
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Sends `value`, waiting while the channel is full.
+    ///
+    /// Fails if the channel is closed.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut value = Some(value);
+        poll_fn(|cx| {
+            let pending = value.take().expect("polled after completion");
+            match self.chan.push(pending, Some(cx.waker())) {
+                Ok(()) => Poll::Ready(Ok(())),
+                Err(TrySendError::Closed(pending)) => Poll::Ready(Err(SendError(pending))),
+                Err(TrySendError::Full(pending)) => {
+                    value = Some(pending);
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+
+    /// Sends `value` if there is room right away
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.chan.push(value, None)
+    }
+
+    /// Returns whether the receiver is gone or closed
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+}
+
+impl<T> UnboundedSender<T> {
+    /// Sends `value`, failing if the channel is closed
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        self.chan.push(value, None).map_err(|err| match err {
+            TrySendError::Closed(value) | TrySendError::Full(value) => SendError(value),
+        })
+    }
+
+    /// Returns whether the receiver is gone or closed
+    pub fn is_closed(&self) -> bool {
+        self.chan.is_closed()
+    }
+}
+
+macro_rules! receiver_methods {
+    () => {
+        /// Receives the next value, or `None` once the channel is closed and
+        /// empty
+        pub async fn recv(&mut self) -> Option<T> {
+            poll_fn(|cx| self.chan.poll_recv(cx)).await
+        }
+
+        /// Polls for the next value, or `None` once the channel is closed
+        /// and empty
+        pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+            self.chan.poll_recv(cx)
+        }
+
+        /// Takes the next value if there is one
+        pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+            self.chan.pop(None)
+        }
+
+        /// Closes the channel, so that further sends fail, while keeping the
+        /// values already sent for `recv`
+        pub fn close(&mut self) {
+            self.chan.close();
+        }
+    };
+}
+
+impl<T> Receiver<T> {
+    receiver_methods!();
+}
+
+impl<T> UnboundedReceiver<T> {
+    receiver_methods!();
+}
+
+macro_rules! channel_end_impls {
+    ($sender:ident, $receiver:ident) => {
+        impl<T> Clone for $sender<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    chan: self.chan.add_sender(),
+                }
+            }
+        }
+
+        impl<T> Drop for $sender<T> {
+            fn drop(&mut self) {
+                self.chan.drop_sender();
+            }
+        }
+
+        impl<T> Drop for $receiver<T> {
+            fn drop(&mut self) {
+                self.chan.drop_receiver();
+            }
+        }
+
+        impl<T> fmt::Debug for $sender<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($sender)).finish_non_exhaustive()
+            }
+        }
+
+        impl<T> fmt::Debug for $receiver<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($receiver))
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+channel_end_impls!(Sender, Receiver);
+channel_end_impls!(UnboundedSender, UnboundedReceiver);
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("channel closed")
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for SendError<T> {}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Full(_) => f.write_str("channel full"),
+            TrySendError::Closed(_) => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for TrySendError<T> {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("channel empty"),
+            TryRecvError::Disconnected => f.write_str("channel closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
@@ -0,0 +1,175 @@
+//! Channels that carry a single value.
+//!
+//! Good for handing a result back to the task that asked for it:
+//!
+//! ```
+//! use mini_tokio::{sync::oneshot, Executor};
+//!
+//! let executor = Executor::new();
+//! let answer = executor.block_on(async {
+//!     let (tx, rx) = oneshot::channel();
+//!     mini_tokio::spawn(async move { tx.send(42).unwrap() });
+//!     rx.await.unwrap()
+//! });
+//! assert_eq!(answer, 42);
+//! ```
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Creates a channel for a single value
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Mutex::new(State {
+        value: None,
+        sender_gone: false,
+        receiver_gone: false,
+        rx_waker: None,
+    }));
+    (
+        Sender {
+            inner: Arc::clone(&inner),
+        },
+        Receiver { inner },
+    )
+}
+
+/// Sends the value. Dropping it without sending fails the receiver.
+pub struct Sender<T> {
+    inner: Arc<Mutex<State<T>>>,
+}
+
+/// Waits for the value. Awaiting it resolves to `Err(RecvError)` if the
+/// sender was dropped without sending.
+pub struct Receiver<T> {
+    inner: Arc<Mutex<State<T>>>,
+}
+
+/// Returned by a [`Receiver`] whose sender was dropped without sending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+/// Returned by [`Receiver::try_recv`] when there is no value to take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The value has not been sent yet
+    Empty,
+    /// The sender was dropped without sending
+    Closed,
+}
+
+struct State<T> {
+    value: Option<T>,
+    /// Set once the sender has sent or been dropped
+    sender_gone: bool,
+    /// Set once the receiver is dropped or closed
+    receiver_gone: bool,
+    rx_waker: Option<Waker>,
+}
+
+impl<T> Sender<T> {
+    /// Sends `value`, giving it back if the receiver is gone
+    pub fn send(self, value: T) -> Result<(), T> {
+        let mut state = self.inner.lock().unwrap();
+        if state.receiver_gone {
+            return Err(value);
+        }
+        state.value = Some(value);
+        Ok(())
+    }
+
+    /// Returns whether the receiver has been dropped or closed
+    pub fn is_closed(&self) -> bool {
+        self.inner.lock().unwrap().receiver_gone
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.inner.lock().unwrap();
+            state.sender_gone = true;
+            state.rx_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Takes the value if it has been sent, without waiting
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut state = self.inner.lock().unwrap();
+        match state.value.take() {
+            Some(value) => Ok(value),
+            None if state.sender_gone => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Makes a later send fail. A value sent before this can still be
+    /// received.
+    pub fn close(&mut self) {
+        self.inner.lock().unwrap().receiver_gone = true;
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.inner.lock().unwrap();
+        if let Some(value) = state.value.take() {
+            return Poll::Ready(Ok(value));
+        }
+        if state.sender_gone {
+            return Poll::Ready(Err(RecvError));
+        }
+        state.rx_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("is_closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sender dropped without sending")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("no value sent yet"),
+            TryRecvError::Closed => f.write_str("sender dropped without sending"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
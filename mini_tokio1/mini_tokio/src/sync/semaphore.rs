@@ -0,0 +1,339 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::{poll_fn, Future},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+/// Limits how many tasks can do something at once.
+///
+/// A task takes permits with [`acquire`](Self::acquire), waiting while
+/// there are not enough, and gives them back by dropping the returned
+/// permit. Waiting tasks are served in the order they started waiting, so a
+/// task asking for many permits is not starved by tasks asking for few.
+///
+/// ```
+/// use mini_tokio::{sync::Semaphore, Executor};
+///
+/// let executor = Executor::new();
+/// executor.block_on(async {
+///     let semaphore = Semaphore::new(2);
+///     let first = semaphore.acquire().await.unwrap();
+///     let _second = semaphore.acquire().await.unwrap();
+///     assert!(semaphore.try_acquire().is_err());
+///
+///     drop(first);
+///     assert_eq!(semaphore.available_permits(), 1);
+/// });
+/// ```
+pub struct Semaphore {
+    state: Mutex<State>,
+}
+
+/// Permits taken from a [`Semaphore`], given back when dropped.
+#[must_use]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+/// Permits taken from a [`Semaphore`] held in an `Arc`, given back when
+/// dropped. Unlike [`SemaphorePermit`] they can be moved into a task.
+#[must_use]
+pub struct OwnedSemaphorePermit {
+    semaphore: Arc<Semaphore>,
+    permits: usize,
+}
+
+/// Returned by an acquire once the semaphore is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquireError;
+
+/// Returned by [`Semaphore::try_acquire`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore is closed
+    Closed,
+    /// There are not enough permits, or other tasks are waiting for them
+    NoPermits,
+}
+
+struct State {
+    permits: usize,
+    closed: bool,
+    /// Tasks waiting for permits, served from the front
+    waiters: VecDeque<Waiter>,
+    next_key: usize,
+}
+
+struct Waiter {
+    key: usize,
+    permits: usize,
+    waker: Waker,
+}
+
+/// Waits for permits, keeping its place in the queue between polls
+struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+    /// Key of this future's entry in the queue, while it is in it
+    key: Option<usize>,
+}
+
+impl Semaphore {
+    /// Creates a semaphore with `permits` permits
+    pub fn new(permits: usize) -> Self {
+        Self {
+            state: Mutex::new(State {
+                permits,
+                closed: false,
+                waiters: VecDeque::new(),
+                next_key: 0,
+            }),
+        }
+    }
+
+    /// Returns how many permits are free
+    pub fn available_permits(&self) -> usize {
+        self.state.lock().unwrap().permits
+    }
+
+    /// Adds `n` permits, waking tasks waiting for them
+    pub fn add_permits(&self, n: usize) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.permits += n;
+            state.next_waker()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Waits for a permit
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Waits for `n` permits, taking them all at once
+    pub async fn acquire_many(&self, n: usize) -> Result<SemaphorePermit<'_>, AcquireError> {
+        let mut acquire = Acquire {
+            semaphore: self,
+            permits: n,
+            key: None,
+        };
+        poll_fn(|cx| Pin::new(&mut acquire).poll(cx)).await?;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Waits for a permit that can outlive the borrow of the semaphore
+    pub async fn acquire_owned(self: Arc<Self>) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire().await?.forget();
+        Ok(OwnedSemaphorePermit {
+            semaphore: self,
+            permits: 1,
+        })
+    }
+
+    /// Takes a permit if one is free and no task is waiting for one
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(TryAcquireError::Closed);
+        }
+        if state.permits == 0 || !state.waiters.is_empty() {
+            return Err(TryAcquireError::NoPermits);
+        }
+        state.permits -= 1;
+        Ok(SemaphorePermit {
+            semaphore: self,
+            permits: 1,
+        })
+    }
+
+    /// Closes the semaphore, failing every pending and future acquire.
+    ///
+    /// Permits already taken stay valid.
+    pub fn close(&self) {
+        let waiters = {
+            let mut state = self.state.lock().unwrap();
+            state.closed = true;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters {
+            waiter.waker.wake();
+        }
+    }
+
+    /// Returns whether the semaphore has been closed
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().unwrap();
+        f.debug_struct("Semaphore")
+            .field("permits", &state.permits)
+            .field("waiters", &state.waiters.len())
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+impl State {
+    /// Returns the waker of the first waiter if there are enough permits
+    /// for it
+    fn next_waker(&self) -> Option<Waker> {
+        self.waiters
+            .front()
+            .filter(|waiter| waiter.permits <= self.permits)
+            .map(|waiter| waiter.waker.clone())
+    }
+}
+
+impl Future for Acquire<'_> {
+    type Output = Result<(), AcquireError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.semaphore.state.lock().unwrap();
+        if state.closed {
+            drop(state);
+            self.key = None;
+            return Poll::Ready(Err(AcquireError));
+        }
+
+        let Some(key) = self.key else {
+            if state.waiters.is_empty() && state.permits >= self.permits {
+                state.permits -= self.permits;
+                return Poll::Ready(Ok(()));
+            }
+            let key = state.next_key;
+            state.next_key += 1;
+            state.waiters.push_back(Waiter {
+                key,
+                permits: self.permits,
+                waker: cx.waker().clone(),
+            });
+            drop(state);
+            self.key = Some(key);
+            return Poll::Pending;
+        };
+
+        let first = state
+            .waiters
+            .front()
+            .is_some_and(|waiter| waiter.key == key);
+        if first && state.permits >= self.permits {
+            state.waiters.pop_front();
+            state.permits -= self.permits;
+            let next = state.next_waker();
+            drop(state);
+            self.key = None;
+            if let Some(next) = next {
+                next.wake();
+            }
+            return Poll::Ready(Ok(()));
+        }
+        if let Some(waiter) = state.waiters.iter_mut().find(|waiter| waiter.key == key) {
+            waiter.waker.clone_from(cx.waker());
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(key) = self.key else { return };
+        let waker = {
+            let mut state = self.semaphore.state.lock().unwrap();
+            state.waiters.retain(|waiter| waiter.key != key);
+            // The next waiter may have been held up behind this one.
+            state.next_waker()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl SemaphorePermit<'_> {
+    /// Returns how many permits this holds
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Keeps the permits taken instead of giving them back
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.add_permits(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OwnedSemaphorePermit {
+    /// Returns how many permits this holds
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Keeps the permits taken instead of giving them back
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        if self.permits > 0 {
+            self.semaphore.add_permits(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for OwnedSemaphorePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSemaphorePermit")
+            .field("permits", &self.permits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("semaphore closed")
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+impl fmt::Display for TryAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("semaphore closed"),
+            Self::NoPermits => f.write_str("no permits available"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
@@ -1,3 +1,5 @@
+//! Spawning tasks and waiting for their output.
+
 use std::{
     future::Future,
     pin::Pin,
@@ -5,6 +7,204 @@ use std::{
     task::{Context, Poll, Waker},
 };
 
+use crate::time::Instant;
+
+/// Spawns a task onto the executor running on this thread
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::block_on`](crate::Executor::block_on)
+/// or a task.
+#[track_caller]
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    crate::context::spawn(future, &Builder::new())
+}
+
+/// Runs the blocking closure `f` on a thread of the current executor's
+/// blocking pool, so that it does not hold up other tasks.
+///
+/// Meant for work that blocks, such as file system calls or CPU-heavy
+/// computations. The returned handle resolves to `Err(Cancelled)` if `f`
+/// panics.
+///
+/// ```
+/// use mini_tokio::{task, Executor};
+///
+/// let executor = Executor::new();
+/// let sum = executor.block_on(async {
+///     task::spawn_blocking(|| (1..=100u64).sum::<u64>()).await.unwrap()
+/// });
+/// assert_eq!(sum, 5050);
+/// ```
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::block_on`](crate::Executor::block_on)
+/// or a task.
+pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    crate::context::blocking_pool().spawn(f)
+}
+
+/// Spawns a task with the given priority onto the executor running on this
+/// thread.
+///
+/// See [`Priority`] for how tasks of different classes are scheduled.
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::block_on`](crate::Executor::block_on)
+/// or a task.
+#[track_caller]
+pub fn spawn_with_priority<F>(priority: Priority, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    crate::context::spawn(future, Builder::new().priority(priority))
+}
+
+/// Spawns a task that should complete by `deadline` onto the executor
+/// running on this thread.
+///
+/// See [`Builder::deadline`].
+///
+/// # Panics
+///
+/// Panics if called outside of [`Executor::block_on`](crate::Executor::block_on)
+/// or a task.
+#[track_caller]
+pub fn spawn_with_deadline<F>(deadline: Instant, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    crate::context::spawn(future, Builder::new().deadline(deadline))
+}
+
+/// How urgently a task is polled relative to the others on its executor.
+///
+/// The executor keeps one run queue per class and always polls a woken task
+/// from the most urgent non-empty queue. So that background work still makes
+/// progress under a steady load of urgent tasks, a class that has been passed
+/// over several times in a row while it had woken tasks gets the next turn.
+///
+/// ```
+/// use mini_tokio::{spawn_with_priority, task::Priority, Executor};
+///
+/// let executor = Executor::new();
+/// let output = executor.block_on(async {
+///     let compaction = spawn_with_priority(Priority::Background, async { "compacted" });
+///     let request = spawn_with_priority(Priority::High, async { "handled" });
+///     (request.await.unwrap(), compaction.await.unwrap())
+/// });
+/// assert_eq!(output, ("handled", "compacted"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Latency-sensitive work, such as request handlers
+    High,
+    /// The class of tasks spawned without a priority
+    #[default]
+    Normal,
+    /// Work that can wait, such as compaction or cleanup
+    Background,
+}
+
+impl Priority {
+    pub(crate) const COUNT: usize = 3;
+
+    /// Position of the class from most to least urgent
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Priority::High => "high",
+            Priority::Normal => "normal",
+            Priority::Background => "background",
+        })
+    }
+}
+
+/// Configures a task before spawning it.
+///
+/// ```
+/// use mini_tokio::{task, Executor};
+///
+/// let executor = Executor::new();
+/// let output = executor.block_on(async {
+///     task::Builder::new().name("worker").spawn(async { 1 + 1 }).await
+/// });
+/// assert_eq!(output.unwrap(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    pub(crate) name: Option<String>,
+    pub(crate) priority: Priority,
+    pub(crate) deadline: Option<Instant>,
+}
+
+impl Builder {
+    /// Returns a builder for an unnamed task
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names the task, so that it can be told apart in
+    /// [`Executor::dump`](crate::Executor::dump) and the console
+    pub fn name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Sets the task's priority, [`Priority::Normal`] by default
+    pub fn priority(&mut self, priority: Priority) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the time by which the task should complete.
+    ///
+    /// An executor built with the [`Edf`](crate::executor::scheduler::Edf)
+    /// scheduler polls the woken task with the earliest deadline first. With
+    /// any scheduler, a task still running when its deadline passes is
+    /// counted in [`Metrics::deadline_misses`](crate::executor::Metrics::deadline_misses)
+    /// and reported to the
+    /// [`on_deadline_miss`](crate::executor::Builder::on_deadline_miss) hook.
+    pub fn deadline(&mut self, deadline: Instant) -> &mut Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Spawns the task onto the executor running on this thread.
+    ///
+    /// The caller's location is recorded as the task's spawn site.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of [`Executor::block_on`](crate::Executor::block_on)
+    /// or a task.
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        crate::context::spawn(future, self)
+    }
+}
+
 /// A handle to a spawned task that can be awaited
 pub struct JoinHandle<T> {
     inner: Arc<Mutex<TaskInner<T>>>,
@@ -66,7 +266,7 @@ impl<T> Future for JoinHandle<T> {
     type Output = Result<T, super::Cancelled>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        self.poll(cx)
+        JoinHandle::poll(&self, cx)
     }
 }
 
@@ -76,4 +276,4 @@ impl<T> Clone for JoinHandle<T> {
             inner: Arc::clone(&self.inner),
         }
     }
-}
\ No newline at end of file
+}
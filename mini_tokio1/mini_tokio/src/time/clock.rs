@@ -0,0 +1,89 @@
+use std::{sync::Mutex, time::Duration};
+
+use super::Instant;
+use crate::context;
+
+/// A clock that can be paused and moved forward by hand.
+pub(crate) struct Clock {
+    inner: Mutex<ClockInner>,
+}
+
+struct ClockInner {
+    /// The time the clock showed when it was last paused or resumed
+    base: std::time::Instant,
+    /// When the clock was last resumed, or `None` while it is paused
+    unfrozen: Option<std::time::Instant>,
+}
+
+impl Clock {
+    pub(crate) fn new(start_paused: bool) -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            inner: Mutex::new(ClockInner {
+                base: now,
+                unfrozen: (!start_paused).then_some(now),
+            }),
+        }
+    }
+
+    pub(crate) fn now(&self) -> Instant {
+        let inner = self.inner.lock().unwrap();
+        let elapsed = inner.unfrozen.map(|t| t.elapsed()).unwrap_or_default();
+        Instant::from_std(inner.base + elapsed)
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.inner.lock().unwrap().unfrozen.is_none()
+    }
+
+    fn pause(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(unfrozen) = inner.unfrozen.take() {
+            inner.base += unfrozen.elapsed();
+        }
+    }
+
+    fn resume(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.unfrozen.get_or_insert_with(std::time::Instant::now);
+    }
+
+    /// Moves a paused clock forward to `deadline` if it is in the future
+    pub(crate) fn advance_to(&self, deadline: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        assert!(inner.unfrozen.is_none(), "time is not paused");
+        inner.base = inner.base.max(deadline.into_std());
+    }
+}
+
+/// Pauses the clock of the current executor.
+///
+/// # Panics
+///
+/// Panics if called outside of an executor.
+pub fn pause() {
+    context::time_handle().clock().pause();
+}
+
+/// Resumes a paused clock of the current executor.
+///
+/// # Panics
+///
+/// Panics if called outside of an executor.
+pub fn resume() {
+    context::time_handle().clock().resume();
+}
+
+/// Moves the paused clock of the current executor forward by `duration`.
+///
+/// Timers whose deadline has been reached fire the next time the executor
+/// turns.
+///
+/// # Panics
+///
+/// Panics if called outside of an executor or if the clock is not paused.
+pub fn advance(duration: Duration) {
+    let handle = context::time_handle();
+    let clock = handle.clock();
+    clock.advance_to(clock.now() + duration);
+}
@@ -0,0 +1,160 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    task::Waker,
+};
+
+use super::{clock::Clock, Instant};
+use crate::executor::park::Parker;
+
+/// The timer driver.
+///
+/// Keeps the pending timers ordered by deadline. The executor calls
+/// [`Handle::process`] on every turn to wake the timers that have expired,
+/// and is unparked when a timer is registered that expires before the others.
+#[derive(Clone)]
+pub(crate) struct Handle {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    clock: Clock,
+    timers: Mutex<Timers>,
+    parker: Parker,
+}
+
+#[derive(Default)]
+struct Timers {
+    entries: BTreeMap<TimerKey, Waker>,
+    next_id: u64,
+}
+
+/// Identifies a registered timer. Ordered by deadline first, so that the
+/// earliest timer comes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TimerKey {
+    deadline: Instant,
+    id: u64,
+}
+
+impl Handle {
+    pub(crate) fn new(start_paused: bool, parker: Parker) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                clock: Clock::new(start_paused),
+                timers: Mutex::new(Timers::default()),
+                parker,
+            }),
+        }
+    }
+
+    pub(crate) fn clock(&self) -> &Clock {
+        &self.inner.clock
+    }
+
+    /// Registers `waker` to be woken at `deadline`, replacing the timer
+    /// registered under `prev`
+    pub(crate) fn register(
+        &self,
+        prev: Option<TimerKey>,
+        deadline: Instant,
+        waker: &Waker,
+    ) -> TimerKey {
+        let mut timers = self.inner.timers.lock().unwrap();
+        if let Some(prev) = prev {
+            if prev.deadline == deadline {
+                if let Some(registered) = timers.entries.get_mut(&prev) {
+                    if !registered.will_wake(waker) {
+                        *registered = waker.clone();
+                    }
+                    return prev;
+                }
+            }
+            timers.entries.remove(&prev);
+        }
+
+        let key = TimerKey {
+            deadline,
+            id: timers.next_id,
+        };
+        timers.next_id += 1;
+        timers.entries.insert(key, waker.clone());
+        let earliest = timers.entries.keys().next() == Some(&key);
+        drop(timers);
+        // The executor may be asleep until a later deadline.
+        if earliest {
+            self.inner.parker.unpark();
+        }
+        key
+    }
+
+    /// Removes a timer that has not fired yet
+    pub(crate) fn cancel(&self, key: TimerKey) {
+        self.inner.timers.lock().unwrap().entries.remove(&key);
+    }
+
+    /// Wakes every timer whose deadline has been reached, returning their IDs
+    pub(crate) fn process(&self) -> Vec<u64> {
+        let now = self.clock().now();
+        let expired = {
+            let mut timers = self.inner.timers.lock().unwrap();
+            let pending = timers.entries.split_off(&TimerKey {
+                deadline: now,
+                id: u64::MAX,
+            });
+            std::mem::replace(&mut timers.entries, pending)
+        };
+        wake_all(expired)
+    }
+
+    /// Wakes the timers with the given IDs whatever their deadline, returning
+    /// the IDs of those that were not registered
+    pub(crate) fn fire(&self, ids: &[u64]) -> Vec<u64> {
+        let (fired, missing) = {
+            let mut timers = self.inner.timers.lock().unwrap();
+            let (fired, pending) = std::mem::take(&mut timers.entries)
+                .into_iter()
+                .partition(|(key, _)| ids.contains(&key.id));
+            timers.entries = pending;
+            let fired: BTreeMap<_, _> = fired;
+            let missing = ids
+                .iter()
+                .copied()
+                .filter(|id| !fired.keys().any(|key| key.id == *id))
+                .collect();
+            (fired, missing)
+        };
+        wake_all(fired);
+        missing
+    }
+
+    /// Returns the deadline of the earliest pending timer
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let timers = self.inner.timers.lock().unwrap();
+        timers.entries.keys().next().map(|key| key.deadline)
+    }
+
+    /// Moves a paused clock to the earliest pending timer, returning whether
+    /// there was one to move to
+    pub(crate) fn auto_advance(&self) -> bool {
+        if !self.clock().is_paused() {
+            return false;
+        }
+        match self.next_deadline() {
+            Some(deadline) => {
+                self.clock().advance_to(deadline);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn wake_all(expired: BTreeMap<TimerKey, Waker>) -> Vec<u64> {
+    let ids = expired.keys().map(|key| key.id).collect();
+    // Woken outside the lock, since a woken task may register a new timer.
+    for waker in expired.into_values() {
+        waker.wake();
+    }
+    ids
+}
@@ -0,0 +1,115 @@
+use std::{
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use super::{sleep_until, Instant, Sleep};
+
+/// Ticks every `period`, starting right away.
+///
+/// ```
+/// use mini_tokio::{executor::Builder, time};
+/// use std::time::Duration;
+///
+/// let executor = Builder::new_current_thread().start_paused(true).build().unwrap();
+/// executor.block_on(async {
+///     let mut interval = time::interval(Duration::from_secs(1));
+///     let first = interval.tick().await;
+///     let second = interval.tick().await;
+///     assert_eq!(second - first, Duration::from_secs(1));
+/// });
+/// ```
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval(period: Duration) -> Interval {
+    interval_at(Instant::now(), period)
+}
+
+/// Ticks every `period`, starting at `start`.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn interval_at(start: Instant, period: Duration) -> Interval {
+    assert!(!period.is_zero(), "interval period cannot be zero");
+    Interval {
+        sleep: sleep_until(start),
+        period,
+        missed_tick_behavior: MissedTickBehavior::default(),
+    }
+}
+
+/// What an [`Interval`] does about ticks that were due while nobody was
+/// waiting for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Returns the missed ticks right away, one per call, until it has
+    /// caught up with the original schedule
+    #[default]
+    Burst,
+    /// Returns one missed tick, then ticks every period from then on
+    Delay,
+    /// Returns one missed tick, then goes on with the original schedule,
+    /// skipping the other missed ticks
+    Skip,
+}
+
+/// Ticks at a fixed period, returned by [`interval`] and [`interval_at`].
+#[derive(Debug)]
+pub struct Interval {
+    /// Sleeps until the next tick
+    sleep: Sleep,
+    period: Duration,
+    missed_tick_behavior: MissedTickBehavior,
+}
+
+impl Interval {
+    /// Waits for the next tick, returning when it was due
+    pub async fn tick(&mut self) -> Instant {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next tick, returning when it was due
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        ready!(Pin::new(&mut self.sleep).poll(cx));
+        let due = self.sleep.deadline();
+        let now = Instant::now();
+        let mut next = due + self.period;
+        if next <= now {
+            next = match self.missed_tick_behavior {
+                MissedTickBehavior::Burst => next,
+                MissedTickBehavior::Delay => now + self.period,
+                MissedTickBehavior::Skip => {
+                    let missed = (now - due).as_nanos() / self.period.as_nanos();
+                    due + self.period * u32::try_from(missed + 1).unwrap_or(u32::MAX)
+                }
+            };
+        }
+        self.sleep.reset(next);
+        Poll::Ready(due)
+    }
+
+    /// Makes the next tick due one period from now
+    pub fn reset(&mut self) {
+        self.sleep.reset(Instant::now() + self.period);
+    }
+
+    /// Returns the time between ticks
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Returns what the interval does about missed ticks
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Sets what the interval does about missed ticks
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+}
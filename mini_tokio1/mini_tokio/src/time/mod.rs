@@ -0,0 +1,20 @@
+//! Utilities for tracking time.
+//!
+//! Every executor has its own clock and timer driver. Timers are registered
+//! with the executor running on the current thread and fire as the executor
+//! turns. The clock can be paused, which is useful in tests: while it is
+//! paused, time only moves when [`advance`] is called or when the executor has
+//! nothing to do but wait for the next timer, in which case it jumps straight
+//! to that timer's deadline.
+
+mod clock;
+pub(crate) mod driver;
+mod instant;
+mod interval;
+mod sleep;
+pub(crate) mod timerfd;
+
+pub use clock::{advance, pause, resume};
+pub use instant::Instant;
+pub use interval::{interval, interval_at, Interval, MissedTickBehavior};
+pub use sleep::{delay, sleep, sleep_until, Sleep};
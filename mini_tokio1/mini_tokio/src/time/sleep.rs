@@ -0,0 +1,112 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use super::{
+    driver::{self, TimerKey},
+    Instant,
+};
+use crate::{context, executor::sequence::Participant};
+
+/// A future that completes once a deadline has been reached.
+///
+/// Created by [`sleep`], [`sleep_until`] and [`delay`]. The timer is
+/// registered with the executor that first polls it, so polling a `Sleep`
+/// outside of an executor panics.
+pub struct Sleep {
+    deadline: Instant,
+    entry: Option<(driver::Handle, TimerKey)>,
+}
+
+impl Sleep {
+    fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            entry: None,
+        }
+    }
+
+    /// Returns the instant at which the sleep completes
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns true once the deadline has been reached
+    pub fn is_elapsed(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Changes the deadline, so that the sleep can be reused.
+    ///
+    /// The new deadline takes effect the next time the sleep is polled.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+        if let Some((handle, key)) = self.entry.take() {
+            handle.cancel(key);
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = &mut *self;
+        let (handle, prev) = match this.entry.take() {
+            Some((handle, key)) => (handle, Some(key)),
+            None => (context::time_handle(), None),
+        };
+
+        if handle.clock().now() >= this.deadline {
+            if let Some(key) = prev {
+                handle.cancel(key);
+            }
+            return Poll::Ready(());
+        }
+
+        let key = handle.register(prev, this.deadline, cx.waker());
+        this.entry = Some((handle, key));
+        context::waker_registered(Participant::Timer);
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some((handle, key)) = self.entry.take() {
+            handle.cancel(key);
+        }
+    }
+}
+
+impl std::fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sleep")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+/// Waits until `duration` has elapsed
+pub fn sleep(duration: Duration) -> Sleep {
+    let now = Instant::now();
+    // Saturate rather than overflow for very long sleeps.
+    Sleep::new(now.checked_add(duration).unwrap_or_else(|| far_future(now)))
+}
+
+/// Waits until `deadline` is reached
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep::new(deadline)
+}
+
+/// Creates a future that completes after the specified duration
+pub fn delay(ms: u64) -> Sleep {
+    sleep(Duration::from_millis(ms))
+}
+
+fn far_future(now: Instant) -> Instant {
+    now + Duration::from_secs(86400 * 365 * 30)
+}
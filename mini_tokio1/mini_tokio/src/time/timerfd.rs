@@ -0,0 +1,78 @@
+use std::{
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    ptr,
+    time::Duration,
+};
+
+use crate::io::driver::{self, cvt, Registration};
+
+/// A `timerfd` registered with the I/O driver, so that a turn of the driver
+/// that blocks in `epoll_wait` ends when the timer expires.
+///
+/// `epoll_wait` only takes its timeout in milliseconds, while the timerfd
+/// expires with nanosecond resolution.
+pub(crate) struct TimerFd {
+    // Declared before `fd` so that the descriptor is deregistered before it
+    // is closed.
+    _registration: Registration,
+    fd: OwnedFd,
+}
+
+impl TimerFd {
+    pub(crate) fn new(io: &driver::Handle) -> io::Result<Self> {
+        // SAFETY: `timerfd_create` takes no pointers.
+        let fd = cvt(unsafe {
+            libc::timerfd_create(
+                libc::CLOCK_MONOTONIC,
+                libc::TFD_NONBLOCK | libc::TFD_CLOEXEC,
+            )
+        })?;
+        // SAFETY: `fd` was just opened and nothing else owns it.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        Ok(Self {
+            _registration: io.register(fd.as_raw_fd())?,
+            fd,
+        })
+    }
+
+    /// Arms the timer to expire `after` from now, or disarms it if `None`
+    pub(crate) fn arm(&self, after: Option<Duration>) -> io::Result<()> {
+        self.clear();
+        let value = match after {
+            // A zero value would disarm the timer instead.
+            Some(after) => timespec(after.max(Duration::from_nanos(1))),
+            None => timespec(Duration::ZERO),
+        };
+        let spec = libc::itimerspec {
+            it_interval: timespec(Duration::ZERO),
+            it_value: value,
+        };
+        // SAFETY: `spec` is a valid `itimerspec`, and the old value is not
+        // asked for.
+        cvt(unsafe { libc::timerfd_settime(self.fd.as_raw_fd(), 0, &spec, ptr::null_mut()) })?;
+        Ok(())
+    }
+
+    /// Consumes a past expiration, so that the next one is reported as a
+    /// new event
+    fn clear(&self) {
+        let mut expirations = [0u8; 8];
+        // SAFETY: the buffer is valid for writes of its length. Nothing to
+        // read fails with `EAGAIN`, which is fine.
+        unsafe {
+            libc::read(
+                self.fd.as_raw_fd(),
+                expirations.as_mut_ptr().cast(),
+                expirations.len(),
+            )
+        };
+    }
+}
+
+fn timespec(duration: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: duration.as_secs().try_into().unwrap_or(libc::time_t::MAX),
+        tv_nsec: duration.subsec_nanos().into(),
+    }
+}
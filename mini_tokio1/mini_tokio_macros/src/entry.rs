@@ -0,0 +1,187 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::Parser, punctuated::Punctuated, spanned::Spanned, Expr, ExprLit, ItemFn, Lit,
+    MetaNameValue, Token,
+};
+
+/// Which attribute is being expanded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Main,
+    Test,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    CurrentThread,
+    MultiThread,
+}
+
+/// The executor options given to the attribute.
+struct Config {
+    flavor: Flavor,
+    worker_threads: Option<(usize, proc_macro2::Span)>,
+    start_paused: Option<(bool, proc_macro2::Span)>,
+}
+
+pub(crate) fn expand(args: TokenStream, item: TokenStream, kind: Kind) -> syn::Result<TokenStream> {
+    let mut func: ItemFn = syn::parse2(item)?;
+    let config = parse_config(args)?;
+
+    if func.sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            func.sig.fn_token,
+            "the `async` keyword is missing from the function declaration",
+        ));
+    }
+    if kind == Kind::Test {
+        if !func.sig.inputs.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &func.sig.inputs,
+                "the test function cannot accept arguments",
+            ));
+        }
+        if let Some(attr) = func.attrs.iter().find(|attr| attr.path().is_ident("test")) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "second test attribute is supplied, remove the #[test] attribute",
+            ));
+        }
+    }
+    func.sig.asyncness = None;
+
+    let mut builder = match config.flavor {
+        Flavor::CurrentThread => quote! {
+            ::mini_tokio::executor::Builder::new_current_thread()
+        },
+        Flavor::MultiThread => quote! {
+            ::mini_tokio::executor::Builder::new_multi_thread()
+        },
+    };
+    if let Some((n, _)) = config.worker_threads {
+        builder.extend(quote! { .worker_threads(#n) });
+    }
+    if let Some((paused, _)) = config.start_paused {
+        builder.extend(quote! { .start_paused(#paused) });
+    }
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = func;
+    let test_attr = match kind {
+        Kind::Main => quote! {},
+        Kind::Test => quote! { #[::core::prelude::v1::test] },
+    };
+    // Spanned to the body so that type errors in the body's output point at it.
+    let body = quote_spanned! {block.span()=>
+        let body = async #block;
+        #builder
+            .build()
+            .expect("failed to build the mini_tokio executor")
+            .block_on(body)
+    };
+
+    Ok(quote! {
+        #test_attr
+        #(#attrs)*
+        #vis #sig {
+            #body
+        }
+    })
+}
+
+fn parse_config(args: TokenStream) -> syn::Result<Config> {
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(args)?;
+
+    let mut flavor = None;
+    let mut worker_threads = None;
+    let mut start_paused = None;
+    for arg in args {
+        let span = arg.span();
+        let name = arg
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        match name.as_str() {
+            "flavor" => {
+                flavor = Some(match string(&arg.value)?.as_str() {
+                    "current_thread" => Flavor::CurrentThread,
+                    "multi_thread" => Flavor::MultiThread,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &arg.value,
+                            "unknown flavor, expected \"current_thread\" or \"multi_thread\"",
+                        ))
+                    }
+                });
+            }
+            "worker_threads" => {
+                let n = int(&arg.value)?;
+                if n == 0 {
+                    return Err(syn::Error::new_spanned(
+                        &arg.value,
+                        "`worker_threads` cannot be 0",
+                    ));
+                }
+                worker_threads = Some((n, span));
+            }
+            "start_paused" => start_paused = Some((boolean(&arg.value)?, span)),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &arg.path,
+                    "unknown option, expected `flavor`, `worker_threads` or `start_paused`",
+                ))
+            }
+        }
+    }
+
+    let flavor = flavor.unwrap_or(Flavor::CurrentThread);
+    match (flavor, worker_threads, start_paused) {
+        (Flavor::CurrentThread, Some((_, span)), _) => Err(syn::Error::new(
+            span,
+            "`worker_threads` requires `flavor = \"multi_thread\"`",
+        )),
+        (Flavor::MultiThread, _, Some((true, span))) => Err(syn::Error::new(
+            span,
+            "`start_paused` requires `flavor = \"current_thread\"`",
+        )),
+        _ => Ok(Config {
+            flavor,
+            worker_threads,
+            start_paused,
+        }),
+    }
+}
+
+fn lit(expr: &Expr) -> syn::Result<&Lit> {
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => Ok(lit),
+        _ => Err(syn::Error::new_spanned(expr, "expected a literal")),
+    }
+}
+
+fn string(expr: &Expr) -> syn::Result<String> {
+    match lit(expr)? {
+        Lit::Str(s) => Ok(s.value()),
+        lit => Err(syn::Error::new_spanned(lit, "expected a string")),
+    }
+}
+
+fn int(expr: &Expr) -> syn::Result<usize> {
+    match lit(expr)? {
+        Lit::Int(n) => n.base10_parse(),
+        lit => Err(syn::Error::new_spanned(lit, "expected an integer")),
+    }
+}
+
+fn boolean(expr: &Expr) -> syn::Result<bool> {
+    match lit(expr)? {
+        Lit::Bool(b) => Ok(b.value),
+        lit => Err(syn::Error::new_spanned(lit, "expected `true` or `false`")),
+    }
+}
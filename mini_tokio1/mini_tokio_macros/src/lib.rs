@@ -0,0 +1,94 @@
+//! Procedural macros for mini_tokio.
+//!
+//! These are re-exported by `mini_tokio` and should be used through it, as
+//! `#[mini_tokio::main]`, `#[mini_tokio::test]`, `mini_tokio::stream!` and
+//! `mini_tokio::try_stream!`.
+
+use proc_macro::TokenStream;
+
+mod entry;
+mod stream;
+
+/// Runs an `async fn main` on a mini_tokio executor.
+///
+/// The function body becomes the future passed to `Executor::block_on`.
+/// Options select how the executor is built:
+///
+/// - `flavor = "current_thread"` (the default) or `flavor = "multi_thread"`
+/// - `worker_threads = N`, for the `multi_thread` flavor
+/// - `start_paused = true`, to start with the clock paused; requires the
+///   `current_thread` flavor
+///
+/// ```ignore
+/// #[mini_tokio::main(flavor = "multi_thread", worker_threads = 4)]
+/// async fn main() {
+///     let handle = mini_tokio::spawn(async { 1 + 1 });
+///     assert_eq!(handle.await.unwrap(), 2);
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn main(args: TokenStream, item: TokenStream) -> TokenStream {
+    entry::expand(args.into(), item.into(), entry::Kind::Main)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Runs an `async fn` test on a mini_tokio executor.
+///
+/// Takes the same options as [`main`], and also builds a `current_thread`
+/// executor by default. Each test gets its own executor.
+///
+/// ```ignore
+/// #[mini_tokio::test(start_paused = true)]
+/// async fn sleeps_without_waiting() {
+///     mini_tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    entry::expand(args.into(), item.into(), entry::Kind::Test)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Writes a stream as a block of async code that `yield`s its items.
+///
+/// The block runs as the stream is polled, and pauses at each `yield` until
+/// the item has been taken by the consumer.
+///
+/// ```ignore
+/// use mini_tokio::stream::StreamExt;
+///
+/// let evens = mini_tokio::stream! {
+///     for i in 0..3 {
+///         yield i * 2;
+///     }
+/// };
+/// assert_eq!(evens.collect::<Vec<_>>().await, [0, 2, 4]);
+/// ```
+#[proc_macro]
+pub fn stream(input: TokenStream) -> TokenStream {
+    stream::expand(input.into(), stream::Kind::Stream)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Like [`stream!`], but the block may use `?`.
+///
+/// Yielded values become `Ok` items, and an error ends the stream after
+/// being yielded as its last item.
+///
+/// ```ignore
+/// let lines = mini_tokio::try_stream! {
+///     let text = mini_tokio::fs::read_to_string("notes.txt").await?;
+///     for line in text.lines() {
+///         yield line.to_owned();
+///     }
+/// };
+/// ```
+#[proc_macro]
+pub fn try_stream(input: TokenStream) -> TokenStream {
+    stream::expand(input.into(), stream::Kind::TryStream)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,70 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::Parser,
+    parse_quote,
+    visit_mut::{self, VisitMut},
+    Block, Expr, Item,
+};
+
+/// Whether `?` may be used in the body, making the items `Result`s.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Stream,
+    TryStream,
+}
+
+pub(crate) fn expand(input: TokenStream, kind: Kind) -> syn::Result<TokenStream> {
+    let mut stmts = Block::parse_within.parse2(input)?;
+    let mut yields = Yields { kind };
+    for stmt in &mut stmts {
+        yields.visit_stmt_mut(stmt);
+    }
+
+    let body = match kind {
+        Kind::Stream => quote! { #(#stmts)* },
+        Kind::TryStream => quote! {
+            let __result = async {
+                #(#stmts)*
+                ::mini_tokio::macros::support::Ok(())
+            }
+            .await;
+            if let ::mini_tokio::macros::support::Err(err) = __result {
+                __yielder.send(::mini_tokio::macros::support::Err(err)).await;
+            }
+        },
+    };
+    Ok(quote! {
+        ::mini_tokio::macros::support::AsyncStream::new(|__yielder| async move { #body })
+    })
+}
+
+/// Rewrites each `yield value` into sending `value` through the yielder.
+struct Yields {
+    kind: Kind,
+}
+
+impl VisitMut for Yields {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Yield(yield_expr) => {
+                let mut value = yield_expr
+                    .expr
+                    .take()
+                    .map_or_else(|| parse_quote!(()), |value| *value);
+                self.visit_expr_mut(&mut value);
+                let value: Expr = match self.kind {
+                    Kind::Stream => value,
+                    Kind::TryStream => parse_quote!(::mini_tokio::macros::support::Ok(#value)),
+                };
+                *expr = parse_quote!(__yielder.send(#value).await);
+            }
+            // A `yield` in a closure or a nested async block cannot suspend
+            // the stream, so it is left for the compiler to reject.
+            Expr::Closure(_) | Expr::Async(_) => {}
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {}
+}
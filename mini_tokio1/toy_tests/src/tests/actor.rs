@@ -0,0 +1,120 @@
+use mini_tokio::{
+    actor::{self, Actor, AskError, Context},
+    sync::{mpsc::TrySendError, oneshot},
+    time::sleep,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Records its lifecycle and the messages it handles
+struct Recorder {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Debug)]
+enum Command {
+    Record(&'static str),
+    Len,
+    Stop,
+    /// Waits until told to go on, keeping the mailbox from draining
+    Block(oneshot::Receiver<()>),
+}
+
+impl Actor for Recorder {
+    type Message = Command;
+    type Reply = usize;
+
+    async fn started(&mut self, _ctx: &mut Context) {
+        self.log.lock().unwrap().push("started".into());
+    }
+
+    async fn handle(&mut self, msg: Command, ctx: &mut Context) -> usize {
+        match msg {
+            Command::Record(entry) => self.log.lock().unwrap().push(entry.into()),
+            Command::Len => {}
+            Command::Stop => ctx.stop(),
+            Command::Block(go_on) => {
+                let _ = go_on.await;
+            }
+        }
+        self.log.lock().unwrap().len()
+    }
+
+    async fn stopped(&mut self) {
+        sleep(Duration::from_millis(1)).await;
+        self.log.lock().unwrap().push("stopped".into());
+    }
+}
+
+fn recorder() -> (actor::Addr<Recorder>, Arc<Mutex<Vec<String>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let addr = actor::spawn_with_capacity(
+        Recorder {
+            log: Arc::clone(&log),
+        },
+        2,
+    );
+    (addr, log)
+}
+
+#[mini_tokio::test]
+async fn ask_returns_the_reply_after_earlier_messages() {
+    let (addr, log) = recorder();
+    addr.send(Command::Record("a")).await.unwrap();
+    addr.send(Command::Record("b")).await.unwrap();
+    assert_eq!(addr.ask(Command::Len).await, Ok(3));
+    assert_eq!(*log.lock().unwrap(), ["started", "a", "b"]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn dropping_the_last_address_stops_after_draining_the_mailbox() {
+    let (addr, log) = recorder();
+    let other = addr.clone();
+    addr.send(Command::Record("a")).await.unwrap();
+    drop(addr);
+    other.send(Command::Record("b")).await.unwrap();
+    drop(other);
+
+    sleep(Duration::from_millis(10)).await;
+    assert_eq!(*log.lock().unwrap(), ["started", "a", "b", "stopped"]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn stopping_from_a_handler_fails_queued_asks() {
+    let (addr, log) = recorder();
+    let (go_on, blocked) = oneshot::channel();
+    addr.send(Command::Block(blocked)).await.unwrap();
+    addr.send(Command::Stop).await.unwrap();
+    let queued = mini_tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.ask(Command::Record("never")).await }
+    });
+    sleep(Duration::from_millis(1)).await;
+    go_on.send(()).unwrap();
+
+    assert_eq!(queued.await.unwrap(), Err(AskError));
+    assert_eq!(addr.ask(Command::Len).await, Err(AskError));
+    assert!(addr.is_closed());
+    sleep(Duration::from_millis(10)).await;
+    assert_eq!(*log.lock().unwrap(), ["started", "stopped"]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn mailboxes_are_bounded() {
+    let (addr, _log) = recorder();
+    let (go_on, blocked) = oneshot::channel();
+    addr.send(Command::Block(blocked)).await.unwrap();
+    sleep(Duration::from_millis(1)).await;
+
+    addr.try_send(Command::Len).unwrap();
+    addr.try_send(Command::Len).unwrap();
+    assert!(matches!(
+        addr.try_send(Command::Len),
+        Err(TrySendError::Full(Command::Len))
+    ));
+
+    go_on.send(()).unwrap();
+    assert_eq!(addr.ask(Command::Len).await, Ok(1));
+}
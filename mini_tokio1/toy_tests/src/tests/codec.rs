@@ -0,0 +1,159 @@
+use bytes::{Bytes, BytesMut};
+use futures::{SinkExt, StreamExt};
+use mini_tokio::{
+    codec::{
+        Decoder, Framed, FramedRead, FramedWrite, LengthDelimitedCodec, LinesCodec, LinesCodecError,
+    },
+    io::{duplex, AsyncWriteExt},
+    net::UnixStream,
+    Executor,
+};
+use std::io;
+
+#[test]
+fn lines_round_trip() {
+    let executor = Executor::new();
+
+    let lines = executor.block_on(async {
+        let (a, b) = duplex(5);
+
+        let writer = executor.spawn(async move {
+            let mut sink = FramedWrite::new(a, LinesCodec::new());
+            sink.send("first").await.unwrap();
+            sink.send(String::from("second")).await.unwrap();
+            // `LinesCodec` encodes any `AsRef<str>`, so name the item type.
+            SinkExt::<&str>::close(&mut sink).await.unwrap();
+        });
+
+        let lines: Vec<String> = FramedRead::new(b, LinesCodec::new())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        writer.await.unwrap();
+        lines
+    });
+
+    assert_eq!(lines, ["first", "second"]);
+}
+
+#[test]
+fn lines_final_line_without_newline() {
+    let executor = Executor::new();
+
+    let lines = executor.block_on(async {
+        let input: &[u8] = b"one\r\ntwo";
+        FramedRead::new(input, LinesCodec::new())
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    assert_eq!(lines, ["one", "two"]);
+}
+
+#[test]
+fn lines_max_length_skips_long_line() {
+    let mut codec = LinesCodec::new_with_max_length(8);
+    let mut buf = BytesMut::from(&b"ok\nthis line is too long\nfine\n"[..]);
+
+    assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "ok");
+    assert!(matches!(
+        codec.decode(&mut buf),
+        Err(LinesCodecError::MaxLineLengthExceeded)
+    ));
+    assert_eq!(codec.decode(&mut buf).unwrap().unwrap(), "fine");
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+}
+
+#[test]
+fn framed_read_stops_after_decode_error() {
+    let executor = Executor::new();
+
+    let frames = executor.block_on(async {
+        let input: &[u8] = b"ok\nthis line is too long\nfine\n";
+        FramedRead::new(input, LinesCodec::new_with_max_length(8))
+            .collect::<Vec<_>>()
+            .await
+    });
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].as_ref().unwrap(), "ok");
+    assert!(frames[1].is_err());
+}
+
+#[test]
+fn length_delimited_over_unix_stream() {
+    let executor = Executor::new();
+
+    let echoed = executor.block_on(async {
+        let (a, b) = UnixStream::pair().unwrap();
+
+        let server = executor.spawn(async move {
+            let mut framed = Framed::new(b, LengthDelimitedCodec::new());
+            while let Some(frame) = framed.next().await {
+                framed.send(frame.unwrap().freeze()).await.unwrap();
+            }
+        });
+
+        let mut client = Framed::new(a, LengthDelimitedCodec::new());
+        let mut echoed = Vec::new();
+        for payload in [&b"alpha"[..], b"", &[0xAB; 20_000]] {
+            client.send(Bytes::copy_from_slice(payload)).await.unwrap();
+            echoed.push(client.next().await.unwrap().unwrap().to_vec());
+        }
+        client.close().await.unwrap();
+        server.await.unwrap();
+        echoed
+    });
+
+    assert_eq!(echoed[0], b"alpha");
+    assert!(echoed[1].is_empty());
+    assert_eq!(echoed[2], vec![0xAB; 20_000]);
+}
+
+#[test]
+fn length_delimited_custom_header() {
+    let executor = Executor::new();
+
+    let builder = *LengthDelimitedCodec::builder()
+        .length_field_length(2)
+        .little_endian()
+        .length_adjustment(-2);
+
+    let (wire, frames) = executor.block_on(async {
+        let mut sink = builder.new_write(Vec::new());
+        sink.send(Bytes::from_static(b"abc")).await.unwrap();
+        sink.send(Bytes::from_static(b"de")).await.unwrap();
+        sink.flush().await.unwrap();
+        let wire = sink.into_inner();
+
+        // Feed the frames through a tiny pipe so that headers and payloads
+        // arrive split across reads.
+        let (mut a, b) = duplex(1);
+        let bytes = wire.clone();
+        let writer = executor.spawn(async move {
+            a.write_all(&bytes).await.unwrap();
+        });
+        let frames: Vec<_> = builder
+            .new_read(b)
+            .map(|frame| frame.unwrap().to_vec())
+            .collect()
+            .await;
+        writer.await.unwrap();
+        (wire, frames)
+    });
+
+    assert_eq!(wire, b"\x05\x00abc\x04\x00de");
+    assert_eq!(frames, [b"abc".to_vec(), b"de".to_vec()]);
+}
+
+#[test]
+fn length_delimited_rejects_oversized_frames() {
+    let mut codec = LengthDelimitedCodec::builder()
+        .max_frame_length(4)
+        .new_codec();
+
+    let mut buf = BytesMut::from(&b"\x00\x00\x00\x05hello"[..]);
+    let err = codec.decode(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,133 @@
+use futures::channel::oneshot;
+use mini_tokio::{
+    console::Snapshot,
+    executor::{Builder, Executor},
+    spawn,
+};
+use std::{
+    io::ErrorKind,
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mini-tokio-{name}-{}.sock", std::process::id()))
+}
+
+fn console_executor(path: &Path) -> Executor {
+    Builder::new_current_thread()
+        .console_socket(path)
+        .build()
+        .unwrap()
+}
+
+/// Fetches a snapshot from a plain thread, since the executor thread is busy
+/// running `block_on`.
+fn fetch(path: &Path) -> Snapshot {
+    let path = path.to_path_buf();
+    thread::spawn(move || Snapshot::fetch(path).unwrap())
+        .join()
+        .unwrap()
+}
+
+#[test]
+fn console_lists_idle_tasks() {
+    let path = socket_path("idle");
+    let executor = console_executor(&path);
+
+    let snapshot = executor.block_on(async {
+        let (tx, rx) = oneshot::channel::<()>();
+        spawn(async move {
+            let _ = rx.await;
+        });
+        // Let the task run until it waits on the channel.
+        mini_tokio::delay(10).await;
+
+        let snapshot = fetch(&path);
+        drop(tx);
+        snapshot
+    });
+
+    assert_eq!(snapshot.tasks.len(), 1);
+    let task = &snapshot.tasks[0];
+    assert_eq!(task.state, "idle");
+    assert_eq!(task.polls, 1);
+    assert!(task.location.contains("console.rs"), "{}", task.location);
+    assert_eq!(task.last_woken_by, None);
+}
+
+#[test]
+fn console_reports_who_woke_a_task() {
+    let path = socket_path("woken");
+    let executor = console_executor(&path);
+
+    let snapshot = executor.block_on(async {
+        let (tx, rx) = oneshot::channel::<()>();
+        let (_done_tx, done_rx) = oneshot::channel::<()>();
+        spawn(async move {
+            let _ = rx.await;
+            let _ = done_rx.await;
+        });
+        let waker = spawn(async move {
+            mini_tokio::delay(5).await;
+            tx.send(()).unwrap();
+        });
+        waker.await.unwrap();
+        mini_tokio::delay(10).await;
+
+        fetch(&path)
+    });
+
+    assert_eq!(snapshot.tasks.len(), 1);
+    let task = &snapshot.tasks[0];
+    assert_eq!(task.polls, 2);
+    assert_eq!(task.last_woken_by.as_deref(), Some("task 1"));
+    assert!(task.since_last_wake_us.is_some());
+}
+
+#[test]
+fn console_socket_is_removed_with_executor() {
+    let path = socket_path("removed");
+    let executor = console_executor(&path);
+    assert!(path.exists());
+
+    drop(executor);
+    assert!(!path.exists());
+}
+
+#[test]
+fn console_socket_of_a_live_executor_is_not_taken_over() {
+    let path = socket_path("taken");
+    let executor = console_executor(&path);
+    let Err(err) = Builder::new_current_thread().console_socket(&path).build() else {
+        panic!("a second executor took over the console socket");
+    };
+    assert_eq!(err.kind(), ErrorKind::AddrInUse);
+
+    assert!(fetch(&path).tasks.is_empty());
+    drop(executor);
+}
+
+#[test]
+fn client_that_never_reads_does_not_block_shutdown() {
+    let path = socket_path("unread");
+    let executor = console_executor(&path);
+    // Enough tasks for the snapshot to overflow the socket's buffer.
+    for _ in 0..10_000 {
+        executor.spawn(std::future::pending::<()>());
+    }
+    let _client = UnixStream::connect(&path).unwrap();
+    // Lets the server start writing to the client.
+    thread::sleep(Duration::from_millis(200));
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        drop(executor);
+        tx.send(()).unwrap();
+    });
+    rx.recv_timeout(Duration::from_secs(10))
+        .expect("dropping the executor hung");
+}
@@ -0,0 +1,233 @@
+use futures::FutureExt;
+use mini_tokio::{
+    executor::Builder,
+    fs::{self, File, WatchEventKind},
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    task, Executor,
+};
+use std::{
+    io::SeekFrom,
+    path::PathBuf,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mini-tokio-fs-{name}-{}", std::process::id()))
+}
+
+#[mini_tokio::test]
+async fn write_then_read_back() {
+    let path = temp_path("round-trip");
+    fs::write(&path, b"some bytes").await.unwrap();
+    assert_eq!(fs::read(&path).await.unwrap(), b"some bytes");
+    assert_eq!(fs::metadata(&path).await.unwrap().len(), 10);
+
+    fs::remove_file(&path).await.unwrap();
+    let err = fs::read(&path).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[mini_tokio::test]
+async fn read_dir_lists_every_entry() {
+    let dir = temp_path("read-dir");
+    fs::create_dir_all(dir.join("sub")).await.unwrap();
+    // More than one batch of entries.
+    for i in 0..40 {
+        fs::write(dir.join(format!("{i}.txt")), "").await.unwrap();
+    }
+
+    let mut names = Vec::new();
+    let mut entries = fs::read_dir(&dir).await.unwrap();
+    while let Some(entry) = entries.next_entry().await.unwrap() {
+        if entry.file_type().await.unwrap().is_dir() {
+            assert_eq!(entry.path(), dir.join("sub"));
+        }
+        names.push(entry.file_name().into_string().unwrap());
+    }
+    names.sort();
+    let mut expected: Vec<_> = (0..40).map(|i| format!("{i}.txt")).collect();
+    expected.push("sub".into());
+    expected.sort();
+    assert_eq!(names, expected);
+
+    fs::remove_dir_all(&dir).await.unwrap();
+}
+
+#[mini_tokio::test]
+async fn file_writes_seeks_and_reads() {
+    let path = temp_path("file");
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .await
+        .unwrap();
+    file.write_all(b"hello world").await.unwrap();
+    assert_eq!(file.seek(SeekFrom::Start(6)).await.unwrap(), 6);
+
+    let mut word = [0; 3];
+    file.read_exact(&mut word).await.unwrap();
+    assert_eq!(&word, b"wor");
+    // Buffered but unread bytes do not count toward the position.
+    assert_eq!(file.stream_position().await.unwrap(), 9);
+
+    // Overwrites "wor" even though "ld" was already read into the buffer.
+    file.seek(SeekFrom::Current(-3)).await.unwrap();
+    file.write_all(b"WOR").await.unwrap();
+    file.flush().await.unwrap();
+    assert_eq!(fs::read(&path).await.unwrap(), b"hello WORld");
+
+    file.set_len(5).await.unwrap();
+    file.rewind().await.unwrap();
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).await.unwrap();
+    assert_eq!(contents, "hello");
+
+    fs::remove_file(&path).await.unwrap();
+}
+
+#[mini_tokio::test]
+async fn write_errors_surface_on_flush() {
+    // Writes to /dev/full fail with ENOSPC once they reach the file.
+    let mut file = File::options().write(true).open("/dev/full").await.unwrap();
+    file.write_all(b"lost").await.unwrap();
+    let err = file.flush().await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOSPC));
+}
+
+#[mini_tokio::test]
+async fn write_errors_surface_on_the_next_operation() {
+    let mut file = File::options().write(true).open("/dev/full").await.unwrap();
+    file.write_all(b"lost").await.unwrap();
+    let err = file.write_all(b"also lost").await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOSPC));
+
+    file.write_all(b"lost again").await.unwrap();
+    let err = file.seek(SeekFrom::Start(0)).await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOSPC));
+}
+
+#[mini_tokio::test]
+async fn dropped_seek_does_not_answer_the_next_one() {
+    let path = temp_path("dropped-seek");
+    let mut file = File::create(&path).await.unwrap();
+    // Leaves the seek running on the blocking pool.
+    let _ = file.seek(SeekFrom::Start(100)).now_or_never();
+    assert_eq!(file.seek(SeekFrom::Start(5)).await.unwrap(), 5);
+    assert_eq!(file.stream_position().await.unwrap(), 5);
+    fs::remove_file(&path).await.unwrap();
+}
+
+#[mini_tokio::test]
+async fn into_std_while_a_dropped_operation_holds_the_file() {
+    let path = temp_path("into-std");
+    let file = File::create(&path).await.unwrap();
+    let _ = file.metadata().now_or_never();
+    let std = file.into_std().await;
+    assert!(std.metadata().unwrap().is_file());
+    fs::remove_file(&path).await.unwrap();
+}
+
+#[test]
+fn spawn_blocking_leaves_other_tasks_running() {
+    let executor = Executor::new();
+    let (tx, rx) = mpsc::channel::<()>();
+    executor.block_on(async {
+        // Blocks until the task below has run.
+        let blocked = task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)));
+        mini_tokio::spawn(async move { tx.send(()).unwrap() });
+        assert!(blocked.await.unwrap().is_ok());
+    });
+}
+
+#[test]
+fn blocking_pool_queues_past_its_limit() {
+    let executor = Builder::new_current_thread()
+        .max_blocking_threads(1)
+        .build()
+        .unwrap();
+    let start = Instant::now();
+    executor.block_on(async {
+        let sleeps: Vec<_> = (0..3)
+            .map(|_| task::spawn_blocking(|| std::thread::sleep(Duration::from_millis(50))))
+            .collect();
+        for sleep in sleeps {
+            sleep.await.unwrap();
+        }
+    });
+    assert!(start.elapsed() >= Duration::from_millis(150));
+}
+
+#[test]
+fn panicking_blocking_closure_cancels_its_handle() {
+    let executor = Executor::new();
+    let res = executor.block_on(executor.spawn_blocking(|| -> u32 { panic!("boom") }));
+    assert!(res.is_err());
+    // The pool keeps working.
+    assert_eq!(executor.block_on(executor.spawn_blocking(|| 7)).unwrap(), 7);
+}
+
+#[mini_tokio::test]
+async fn watch_reports_changes_in_a_directory() {
+    let dir = temp_path("watch");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut watcher = fs::watch(&dir).unwrap();
+
+    let (a, b) = (dir.join("a"), dir.join("b"));
+    std::fs::write(&a, "x").unwrap();
+    std::fs::rename(&a, &b).unwrap();
+    std::fs::remove_file(&b).unwrap();
+    std::fs::remove_dir(&dir).unwrap();
+
+    let mut events = Vec::new();
+    while let Some(event) = watcher.recv().await {
+        let event = event.unwrap();
+        events.push((event.kind().clone(), event.path().to_owned()));
+    }
+    assert_eq!(
+        events,
+        [
+            (WatchEventKind::Create, a.clone()),
+            (WatchEventKind::Modify, a.clone()),
+            (WatchEventKind::Rename { from: a }, b.clone()),
+            (WatchEventKind::Delete, b),
+            (WatchEventKind::Delete, dir),
+        ]
+    );
+}
+
+#[mini_tokio::test]
+async fn watch_waits_for_writes_from_another_task() {
+    let path = temp_path("watch-file");
+    std::fs::write(&path, "").unwrap();
+    let mut watcher = fs::watch(&path).unwrap();
+
+    let writer = mini_tokio::spawn({
+        let path = path.clone();
+        async move {
+            mini_tokio::delay(20).await;
+            fs::write(&path, "updated").await.unwrap();
+        }
+    });
+    let event = watcher.recv().await.unwrap().unwrap();
+    assert_eq!(event.kind(), &WatchEventKind::Modify);
+    assert_eq!(event.path(), path);
+    writer.await.unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[mini_tokio::test]
+async fn watch_fails_for_missing_paths() {
+    let err = fs::watch(temp_path("missing")).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn watch_fails_outside_of_an_executor() {
+    let err = fs::watch(std::env::temp_dir()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+}
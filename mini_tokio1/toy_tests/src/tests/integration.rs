@@ -1,5 +1,8 @@
 use mini_tokio::{delay, Executor};
-use std::time::{Duration, Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 #[test]
 fn completes_simple_future() {
@@ -39,11 +42,12 @@ fn delay_future_sleeps() {
 
 #[test]
 fn nested_spawn() {
-    let executor = Executor::new();
+    let executor = Arc::new(Executor::new());
+    let spawner = Arc::clone(&executor);
 
     let result = executor.block_on(async {
-        let outer = executor.spawn(async {
-            let inner = executor.spawn(async { 21 });
+        let outer = executor.spawn(async move {
+            let inner = spawner.spawn(async { 21 });
             inner.await.unwrap() * 2
         });
 
@@ -70,4 +74,4 @@ fn cancel_handle() {
     // The executor should still be running
     let result = executor.block_on(async { 42 });
     assert_eq!(result, 42);
-}
\ No newline at end of file
+}
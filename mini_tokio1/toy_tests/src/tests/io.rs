@@ -0,0 +1,210 @@
+use futures::StreamExt;
+use mini_tokio::{
+    io::{
+        copy, duplex, split, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter,
+        Compat,
+    },
+    net::UnixStream,
+    Executor,
+};
+use std::io;
+
+#[test]
+fn duplex_round_trip_between_tasks() {
+    let executor = Executor::new();
+
+    let reply = executor.block_on(async {
+        let (mut client, mut server) = duplex(4);
+
+        let echo = executor.spawn(async move {
+            let mut buf = [0; 11];
+            server.read_exact(&mut buf).await.unwrap();
+            server.write_all(&buf).await.unwrap();
+        });
+
+        // Larger than the pipe capacity, so the writer has to wait for the reader.
+        client.write_all(b"hello world").await.unwrap();
+        let mut buf = [0; 11];
+        client.read_exact(&mut buf).await.unwrap();
+        echo.await.unwrap();
+        buf
+    });
+
+    assert_eq!(&reply, b"hello world");
+}
+
+#[test]
+fn read_exact_reports_early_eof() {
+    let executor = Executor::new();
+
+    let err = executor.block_on(async {
+        let mut reader: &[u8] = b"abc";
+        let mut buf = [0; 4];
+        reader.read_exact(&mut buf).await.unwrap_err()
+    });
+
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn read_to_end_after_shutdown() {
+    let executor = Executor::new();
+
+    let (bytes, text) = executor.block_on(async {
+        let (mut a, mut b) = duplex(64);
+        a.write_all(b"first").await.unwrap();
+        a.shutdown().await.unwrap();
+
+        let mut bytes = Vec::new();
+        b.read_to_end(&mut bytes).await.unwrap();
+
+        let mut text = String::new();
+        let mut reader: &[u8] = "héllo".as_bytes();
+        reader.read_to_string(&mut text).await.unwrap();
+        (bytes, text)
+    });
+
+    assert_eq!(bytes, b"first");
+    assert_eq!(text, "héllo");
+}
+
+#[test]
+fn buf_reader_lines() {
+    let executor = Executor::new();
+
+    let lines = executor.block_on(async {
+        let (mut writer, reader) = duplex(8);
+
+        let producer = executor.spawn(async move {
+            writer.write_all(b"alpha\nbeta\r\ngamma").await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        let lines: Vec<String> = BufReader::new(reader)
+            .lines()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        producer.await.unwrap();
+        lines
+    });
+
+    assert_eq!(lines, ["alpha", "beta", "gamma"]);
+}
+
+#[test]
+fn buf_reader_read_line_and_until() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let mut reader = BufReader::with_capacity(4, &b"key=value\nrest"[..]);
+
+        let mut key = Vec::new();
+        reader.read_until(b'=', &mut key).await.unwrap();
+        assert_eq!(key, b"key=");
+
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "value\n");
+
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).await.unwrap(), 4);
+        assert_eq!(line, "rest");
+        assert_eq!(reader.read_line(&mut line).await.unwrap(), 0);
+    });
+}
+
+#[test]
+fn buf_writer_holds_data_until_flush() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let mut writer = BufWriter::with_capacity(16, Vec::new());
+        writer.write_all(b"small").await.unwrap();
+        assert!(writer.get_ref().is_empty());
+        assert_eq!(writer.buffer(), b"small");
+
+        writer.flush().await.unwrap();
+        assert_eq!(writer.get_ref(), b"small");
+
+        // Writes larger than the buffer go straight through.
+        writer.write_all(&[7; 32]).await.unwrap();
+        assert_eq!(writer.get_ref().len(), 37);
+    });
+}
+
+#[test]
+fn copy_between_streams() {
+    let executor = Executor::new();
+
+    let copied = executor.block_on(async {
+        let (mut a, mut b) = duplex(16);
+        let payload = vec![42u8; 10_000];
+
+        let writer = executor.spawn(async move {
+            let mut src = &payload[..];
+            let n = copy(&mut src, &mut a).await.unwrap();
+            a.shutdown().await.unwrap();
+            n
+        });
+
+        let mut sink = Vec::new();
+        let n = copy(&mut b, &mut sink).await.unwrap();
+        assert_eq!(writer.await.unwrap(), n);
+        sink
+    });
+
+    assert_eq!(copied, vec![42u8; 10_000]);
+}
+
+#[test]
+fn split_unix_stream_halves() {
+    let executor = Executor::new();
+
+    let reply = executor.block_on(async {
+        let (a, b) = UnixStream::pair().unwrap();
+        let (mut read, mut write) = split(a);
+
+        let echo = executor.spawn(async move {
+            let (mut read, mut write) = split(b);
+            let mut buf = [0; 4];
+            read.read_exact(&mut buf).await.unwrap();
+            write.write_all(&buf).await.unwrap();
+            read.unsplit(write)
+        });
+
+        write.write_all(b"ping").await.unwrap();
+        let mut buf = [0; 4];
+        read.read_exact(&mut buf).await.unwrap();
+
+        assert!(read.is_pair_of(&write));
+        echo.await.unwrap();
+        buf
+    });
+
+    assert_eq!(&reply, b"ping");
+}
+
+#[test]
+fn compat_with_futures_io() {
+    use futures::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        // Our stream driven through the `futures::io` traits.
+        let (a, b) = duplex(64);
+        let (mut a, mut b) = (Compat::new(a), Compat::new(b));
+        a.write_all(b"interop").await.unwrap();
+        a.close().await.unwrap();
+        let mut out = Vec::new();
+        b.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, b"interop");
+
+        // A `futures::io` type driven through our traits.
+        let mut cursor = Compat::new(futures::io::Cursor::new(b"cursor".to_vec()));
+        let mut out = String::new();
+        cursor.read_to_string(&mut out).await.unwrap();
+        assert_eq!(out, "cursor");
+    });
+}
@@ -0,0 +1,193 @@
+use futures::{channel::oneshot, future};
+use mini_tokio::{delay, join, select, try_join};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+/// Counts how many times the wrapped future is polled.
+struct CountPolls<F> {
+    inner: Pin<Box<F>>,
+    polls: Arc<AtomicUsize>,
+}
+
+impl<F: Future> Future for CountPolls<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+/// Sets a flag when dropped.
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[mini_tokio::test]
+async fn join_returns_outputs_in_order() {
+    let out = join!(
+        async {
+            delay(30).await;
+            1
+        },
+        async {
+            delay(10).await;
+            "two"
+        },
+        async { 3.0 },
+    );
+
+    assert_eq!(out, (1, "two", 3.0));
+}
+
+#[mini_tokio::test]
+async fn join_repolls_only_woken_branches() {
+    let polls = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = oneshot::channel();
+    let waiting = CountPolls {
+        inner: Box::pin(rx),
+        polls: polls.clone(),
+    };
+
+    // The second branch is polled again when its timer fires, and the first
+    // only once the sender wakes it.
+    let (value, ()) = join!(waiting, async {
+        delay(30).await;
+        tx.send(7).unwrap();
+    });
+
+    assert_eq!(value.unwrap(), 7);
+    // Once while setting up and once after the send woke it.
+    assert_eq!(polls.load(Ordering::SeqCst), 2);
+}
+
+#[mini_tokio::test]
+async fn try_join_collects_successes() {
+    let res: Result<_, &str> = try_join!(
+        async {
+            delay(10).await;
+            Ok(1)
+        },
+        async { Ok("two") },
+    );
+
+    assert_eq!(res, Ok((1, "two")));
+}
+
+#[mini_tokio::test]
+async fn try_join_stops_at_first_error() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    let res = try_join!(
+        async move {
+            let _flag = flag;
+            future::pending::<Result<(), &str>>().await
+        },
+        async {
+            delay(10).await;
+            Err::<(), _>("failed")
+        },
+    );
+
+    assert_eq!(res, Err("failed"));
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[mini_tokio::test]
+async fn select_drops_losing_branches_before_handler() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    let seen = select! {
+        _ = async move {
+            let _flag = flag;
+            delay(1000).await;
+        } => None,
+        v = async {
+            delay(10).await;
+            5
+        } => Some((v, dropped.load(Ordering::SeqCst))),
+    };
+
+    assert_eq!(seen, Some((5, true)));
+}
+
+#[mini_tokio::test]
+async fn select_biased_polls_in_order() {
+    for _ in 0..20 {
+        let v = select! {
+            biased;
+            v = async { 1 } => v,
+            v = async { 2 } => v,
+        };
+        assert_eq!(v, 1);
+    }
+}
+
+#[mini_tokio::test]
+async fn select_unbiased_picks_every_ready_branch() {
+    let mut picks = Vec::new();
+    for _ in 0..100 {
+        picks.push(select! {
+            v = async { 1 } => v,
+            v = async { 2 } => v,
+        });
+    }
+
+    assert!(picks.contains(&1));
+    assert!(picks.contains(&2));
+}
+
+#[mini_tokio::test]
+async fn select_guards_patterns_and_else() {
+    let guarded = select! {
+        v = async { 1 }, if false => v,
+        else => 0,
+    };
+    assert_eq!(guarded, 0);
+
+    // The first branch completes first but does not match its pattern, so it
+    // is disabled and the second branch wins.
+    let unmatched = select! {
+        biased;
+        Some(v) = async { None::<i32> } => v,
+        v = async {
+            delay(10).await;
+            2
+        } => v,
+    };
+    assert_eq!(unmatched, 2);
+}
+
+#[mini_tokio::test]
+async fn select_in_loop_until_shutdown() {
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+    let mut shutdown_tx = Some(shutdown_tx);
+    let mut handled = 0;
+
+    loop {
+        select! {
+            _ = &mut shutdown_rx => break,
+            _ = delay(5) => {
+                handled += 1;
+                if handled == 3 {
+                    shutdown_tx.take().unwrap().send(()).unwrap();
+                }
+            }
+        }
+    }
+
+    assert_eq!(handled, 3);
+}
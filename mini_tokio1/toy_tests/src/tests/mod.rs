@@ -0,0 +1,21 @@
+mod actor;
+mod codec;
+mod console;
+mod fs;
+mod integration;
+mod io;
+mod macros;
+mod net;
+mod pool;
+mod process;
+mod replay;
+mod resilience;
+mod runtime;
+mod scheduler;
+mod sequence;
+mod signal;
+mod stream;
+mod supervisor;
+mod sync;
+mod task;
+mod watchdog;
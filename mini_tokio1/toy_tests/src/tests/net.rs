@@ -0,0 +1,195 @@
+use mini_tokio::{
+    net::{UdpSocket, UnixDatagram, UnixListener, UnixStream},
+    Executor,
+};
+use std::{
+    io,
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A fresh directory under the system temp dir for socket files, removed on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "mini_tokio-net-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+async fn read_exact(stream: &UnixStream, buf: &mut [u8]) -> io::Result<()> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        stream.readable().await?;
+        match stream.try_read(&mut buf[filled..]) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn udp_send_to_recv_from() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let a = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let b = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let n = a.send_to(b"ping", b.local_addr().unwrap()).await.unwrap();
+        assert_eq!(n, 4);
+
+        let mut buf = [0; 16];
+        let (n, from) = b.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        assert_eq!(from, a.local_addr().unwrap());
+    });
+}
+
+#[test]
+fn udp_recv_waits_for_datagram() {
+    let executor = Executor::new();
+
+    let received = executor.block_on(async {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+
+        let receiver = executor.spawn(async move {
+            let mut buf = [0; 16];
+            let n = server.recv(&mut buf).await.unwrap();
+            buf[..n].to_vec()
+        });
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.connect(addr).await.unwrap();
+        assert_eq!(client.peer_addr().unwrap(), addr);
+        client.send(b"metrics").await.unwrap();
+
+        receiver.await.unwrap()
+    });
+
+    assert_eq!(received, b"metrics");
+}
+
+#[test]
+fn udp_socket_options() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let socket = UdpSocket::bind("0.0.0.0:0").await.unwrap();
+
+        socket.set_broadcast(true).unwrap();
+        assert!(socket.broadcast().unwrap());
+        socket.set_ttl(42).unwrap();
+        assert_eq!(socket.ttl().unwrap(), 42);
+
+        socket.set_multicast_loop_v4(false).unwrap();
+        assert!(!socket.multicast_loop_v4().unwrap());
+        socket.set_multicast_ttl_v4(4).unwrap();
+        assert_eq!(socket.multicast_ttl_v4().unwrap(), 4);
+
+        let group = Ipv4Addr::new(239, 255, 0, 1);
+        socket
+            .join_multicast_v4(group, Ipv4Addr::LOCALHOST)
+            .unwrap();
+        socket
+            .leave_multicast_v4(group, Ipv4Addr::LOCALHOST)
+            .unwrap();
+    });
+}
+
+#[test]
+fn unix_stream_accept_and_connect() {
+    let executor = Executor::new();
+    let dir = TempDir::new();
+    let path = dir.join("stream.sock");
+
+    let echoed = executor.block_on(async {
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = executor.spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 5];
+            read_exact(&stream, &mut buf).await.unwrap();
+            stream.writable().await.unwrap();
+            stream.try_write(&buf).unwrap();
+        });
+
+        let client = UnixStream::connect(&path).await.unwrap();
+        client.writable().await.unwrap();
+        client.try_write(b"hello").unwrap();
+
+        let mut buf = [0; 5];
+        read_exact(&client, &mut buf).await.unwrap();
+        server.await.unwrap();
+        buf
+    });
+
+    assert_eq!(&echoed, b"hello");
+}
+
+#[test]
+fn unix_stream_connect_to_missing_path_fails() {
+    let executor = Executor::new();
+    let dir = TempDir::new();
+    let path = dir.join("missing.sock");
+
+    let err = executor.block_on(UnixStream::connect(&path)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn unix_datagram_send_to_recv_from() {
+    let executor = Executor::new();
+    let dir = TempDir::new();
+
+    executor.block_on(async {
+        let a = UnixDatagram::bind(dir.join("a.sock")).unwrap();
+        let b = UnixDatagram::bind(dir.join("b.sock")).unwrap();
+
+        a.send_to(b"datagram", dir.join("b.sock")).await.unwrap();
+
+        let mut buf = [0; 16];
+        let (n, from) = b.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"datagram");
+        let expected = dir.join("a.sock");
+        assert_eq!(from.as_pathname(), Some(Path::new(&expected)));
+    });
+}
+
+#[test]
+fn unix_datagram_pair() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let (a, b) = UnixDatagram::pair().unwrap();
+        a.send(b"one").await.unwrap();
+
+        let mut buf = [0; 8];
+        let n = b.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"one");
+    });
+}
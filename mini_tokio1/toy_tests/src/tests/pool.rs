@@ -0,0 +1,89 @@
+use mini_tokio::{
+    pool::{PoolClosed, WorkerPool},
+    spawn,
+    time::{sleep, Instant},
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[mini_tokio::test(start_paused = true)]
+async fn submit_waits_while_the_pool_is_full() {
+    let pool = WorkerPool::new(3);
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+
+    let mut handles = Vec::new();
+    for _ in 0..9 {
+        let running = Arc::clone(&running);
+        let peak = Arc::clone(&peak);
+        let job = async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            sleep(Duration::from_secs(1)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+        };
+        handles.push(pool.submit(job).await.unwrap());
+    }
+    // The last three were only submitted once the first six had finished.
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn pool_counts_in_flight_and_queued_submissions() {
+    let pool = WorkerPool::new(1);
+    pool.submit(sleep(Duration::from_secs(1))).await.unwrap();
+    let waiting: Vec<_> = (0..2)
+        .map(|_| {
+            let pool = pool.clone();
+            spawn(async move { pool.submit(async {}).await.map(drop) })
+        })
+        .collect();
+    sleep(Duration::from_millis(1)).await;
+    assert_eq!(pool.in_flight(), 1);
+    assert_eq!(pool.queued(), 2);
+
+    for submission in waiting {
+        submission.await.unwrap().unwrap();
+    }
+    assert_eq!(pool.queued(), 0);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn close_waits_for_tasks_and_rejects_waiting_submissions() {
+    let pool = WorkerPool::new(1);
+    let finished = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+    pool.submit({
+        let finished = Arc::clone(&finished);
+        async move {
+            sleep(Duration::from_secs(5)).await;
+            finished.fetch_add(1, Ordering::SeqCst);
+        }
+    })
+    .await
+    .unwrap();
+    let waiting = spawn({
+        let pool = pool.clone();
+        async move { pool.submit(async {}).await.map(drop) }
+    });
+    sleep(Duration::from_millis(1)).await;
+
+    pool.close().await;
+    assert_eq!(finished.load(Ordering::SeqCst), 1);
+    assert_eq!(start.elapsed(), Duration::from_secs(5));
+    assert_eq!(pool.in_flight(), 0);
+    assert_eq!(waiting.await.unwrap(), Err(PoolClosed));
+    assert!(pool.submit(async {}).await.is_err());
+}
@@ -0,0 +1,141 @@
+use mini_tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::Command,
+    Executor,
+};
+use std::{
+    os::unix::process::ExitStatusExt,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn output_collects_stdout_stderr_and_status() {
+    let output = Executor::new()
+        .block_on(
+            Command::new("sh")
+                .args(["-c", "echo out; echo err >&2; exit 3"])
+                .output(),
+        )
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stdout, b"out\n");
+    assert_eq!(output.stderr, b"err\n");
+}
+
+#[test]
+fn piped_stdin_and_stdout() {
+    let echoed = Executor::new().block_on(async {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+
+        // Larger than a pipe buffer, so writing only finishes while reading.
+        let data: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+        let writer = mini_tokio::spawn({
+            let data = data.clone();
+            async move {
+                stdin.write_all(&data).await.unwrap();
+            }
+        });
+        let mut echoed = Vec::new();
+        stdout.read_to_end(&mut echoed).await.unwrap();
+        writer.await.unwrap();
+        assert!(child.wait().await.unwrap().success());
+        assert_eq!(echoed, data);
+        echoed.len()
+    });
+    assert_eq!(echoed, 200_000);
+}
+
+#[test]
+fn children_run_concurrently() {
+    let start = Instant::now();
+    Executor::new().block_on(async {
+        let children: Vec<_> = (0..5)
+            .map(|_| Command::new("sleep").arg("0.2").spawn().unwrap())
+            .collect();
+        for mut child in children {
+            assert!(child.wait().await.unwrap().success());
+        }
+    });
+    assert!(start.elapsed() < Duration::from_millis(800));
+}
+
+#[test]
+fn kill_stops_the_child() {
+    Executor::new().block_on(async {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        assert_eq!(child.try_wait().unwrap(), None);
+        child.kill().await.unwrap();
+        let status = child.wait().await.unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    });
+}
+
+#[test]
+fn spawning_a_missing_program_fails() {
+    let err = Executor::new()
+        .block_on(Command::new("/nonexistent/program").status())
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn output_keeps_stdio_the_caller_set() {
+    Executor::new().block_on(async {
+        let mut command = Command::new("echo");
+        command.arg("hidden").stdout(Stdio::null());
+        let output = command.output().await.unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+
+        // The defaults of `output` do not stick to the command.
+        let mut command = Command::new("true");
+        command.output().await.unwrap();
+        let mut child = command.spawn().unwrap();
+        assert!(child.stdin.is_none() && child.stdout.is_none() && child.stderr.is_none());
+        child.wait().await.unwrap();
+    });
+}
+
+fn assert_reaped(pid: u32) {
+    // SAFETY: a null status pointer is allowed.
+    let ret = unsafe { libc::waitpid(pid as libc::pid_t, std::ptr::null_mut(), libc::WNOHANG) };
+    assert_eq!(ret, -1, "child {pid} was not reaped");
+}
+
+#[test]
+fn dropped_children_are_reaped_by_the_executor() {
+    let executor = Executor::new();
+    let pids = executor.block_on(async {
+        let pids: Vec<_> = (0..3)
+            .map(|_| Command::new("sleep").arg("0.1").spawn().unwrap().id())
+            .collect();
+        mini_tokio::time::sleep(Duration::from_millis(400)).await;
+        pids
+    });
+    pids.into_iter().for_each(assert_reaped);
+}
+
+#[test]
+fn children_outliving_their_executor_are_reaped() {
+    let executor = Executor::new();
+    let (child, orphan) = executor.block_on(async {
+        let child = Command::new("sleep").arg("0.05").spawn().unwrap();
+        // Still running when the executor shuts down.
+        let orphan = Command::new("sleep").arg("0.05").spawn().unwrap().id();
+        (child, orphan)
+    });
+    drop(executor);
+    let pid = child.id();
+    drop(child);
+
+    std::thread::sleep(Duration::from_millis(200));
+    assert_reaped(pid);
+    assert_reaped(orphan);
+}
@@ -0,0 +1,128 @@
+use mini_tokio::{
+    executor::{
+        replay::{Event, Trace},
+        scheduler::{Fifo, Random},
+        Builder, Scheduler,
+    },
+    time::sleep,
+};
+use std::{
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+fn trace_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mini-tokio-{name}-{}.trace", std::process::id()))
+}
+
+/// Spawns tasks that record their number when they finish, each after
+/// `sleep_ms(i)` milliseconds, and returns the order they finished in
+fn finish_order<S: Scheduler>(
+    builder: &mut Builder,
+    scheduler: S,
+    count: u64,
+    sleep_ms: fn(u64) -> u64,
+) -> Vec<u64> {
+    let executor = builder.build_with_scheduler(scheduler).unwrap();
+    let order = Arc::new(Mutex::new(Vec::new()));
+    executor.block_on(async {
+        let handles: Vec<_> = (0..count)
+            .map(|i| {
+                let order = Arc::clone(&order);
+                mini_tokio::spawn(async move {
+                    let ms = sleep_ms(i);
+                    if ms > 0 {
+                        sleep(Duration::from_millis(ms)).await;
+                    }
+                    order.lock().unwrap().push(i);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+    Arc::try_unwrap(order).unwrap().into_inner().unwrap()
+}
+
+fn record_and_replay(name: &str, sleep_ms: fn(u64) -> u64) -> (Vec<u64>, Vec<u64>, Trace) {
+    let path = trace_path(name);
+    let recorded = finish_order(
+        Builder::new_current_thread().record(&path),
+        Random::with_seed(3),
+        20,
+        sleep_ms,
+    );
+    // A different scheduler, which the replay overrides.
+    let replayed = finish_order(
+        Builder::new_current_thread().replay(&path),
+        Fifo::new(),
+        20,
+        sleep_ms,
+    );
+    let trace = Trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (recorded, replayed, trace)
+}
+
+#[test]
+fn replay_forces_recorded_poll_order() {
+    let (recorded, replayed, trace) = record_and_replay("order", |_| 0);
+    assert_ne!(recorded, (0..20).collect::<Vec<_>>());
+    assert_eq!(replayed, recorded);
+
+    let spawns = trace
+        .events()
+        .iter()
+        .filter(|event| matches!(event, Event::Spawn { .. }))
+        .count();
+    assert_eq!(spawns, 20);
+}
+
+#[test]
+fn replay_fires_recorded_timers() {
+    let (recorded, replayed, trace) = record_and_replay("timers", |i| (i * 7) % 5);
+    assert_eq!(replayed, recorded);
+    assert!(trace
+        .events()
+        .iter()
+        .any(|event| matches!(event, Event::Timers { .. })));
+}
+
+#[test]
+#[should_panic(expected = "replay diverged")]
+fn replaying_a_different_program_panics() {
+    let path = trace_path("diverged");
+    let executor = Builder::new_current_thread().record(&path).build().unwrap();
+    executor.block_on(async { mini_tokio::spawn(async {}).await.unwrap() });
+    drop(executor);
+
+    let executor = Builder::new_current_thread().replay(&path).build().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    executor.block_on(async {
+        mini_tokio::spawn(async {}).await.unwrap();
+        mini_tokio::spawn(async {}).await.unwrap();
+    });
+}
+
+#[test]
+fn replay_rejects_invalid_configurations() {
+    let err = Builder::new_multi_thread()
+        .record(trace_path("multi"))
+        .build()
+        .map(drop)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let path = trace_path("garbage");
+    std::fs::write(&path, b"not a trace").unwrap();
+    let err = Builder::new_current_thread()
+        .replay(&path)
+        .build()
+        .map(drop)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
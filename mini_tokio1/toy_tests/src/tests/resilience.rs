@@ -0,0 +1,181 @@
+use mini_tokio::{
+    resilience::{self, Backoff, CircuitBreaker, CircuitError, CircuitState, RetryPolicy},
+    time::{self, sleep, Instant},
+};
+use std::{cell::RefCell, future::pending, time::Duration};
+
+/// Runs `attempt` under `policy`, failing the first `failures` attempts,
+/// and returns the result with the time of each attempt in milliseconds
+async fn retry_timeline(
+    policy: &RetryPolicy,
+    failures: usize,
+) -> (Result<usize, usize>, Vec<u128>) {
+    let start = Instant::now();
+    let attempts = RefCell::new(Vec::new());
+    let result = resilience::retry(policy, || {
+        let mut attempts = attempts.borrow_mut();
+        attempts.push(start.elapsed().as_millis());
+        let attempt = attempts.len();
+        async move {
+            if attempt <= failures {
+                Err(attempt)
+            } else {
+                Ok(attempt)
+            }
+        }
+    })
+    .await;
+    (result, attempts.into_inner())
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn retry_backs_off_exponentially_up_to_the_max() {
+    let mut policy = RetryPolicy::new(Backoff::exponential(
+        Duration::from_millis(100),
+        Duration::from_millis(300),
+    ));
+    policy.max_retries(5);
+
+    let (result, attempts) = retry_timeline(&policy, 4).await;
+    assert_eq!(result, Ok(5));
+    assert_eq!(attempts, [0, 100, 300, 600, 900]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn retry_gives_up_with_the_last_error() {
+    let mut policy = RetryPolicy::new(Backoff::Fixed(Duration::from_secs(1)));
+    policy.max_retries(2);
+
+    let (result, attempts) = retry_timeline(&policy, usize::MAX).await;
+    assert_eq!(result, Err(3));
+    assert_eq!(attempts, [0, 1000, 2000]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn jittered_delays_stay_below_the_exponential_ones() {
+    let backoff = Backoff::jittered(Duration::from_millis(100), Duration::from_secs(1));
+    for retry in 1..=6 {
+        let ceiling =
+            Backoff::exponential(Duration::from_millis(100), Duration::from_secs(1)).delay(retry);
+        for _ in 0..20 {
+            assert!(backoff.delay(retry) <= ceiling);
+        }
+    }
+    let delays: Vec<_> = (0..20).map(|_| backoff.delay(3)).collect();
+    assert!(delays.iter().any(|&delay| delay != delays[0]));
+
+    let (result, attempts) = retry_timeline(&RetryPolicy::new(backoff), 1).await;
+    assert_eq!(result, Ok(2));
+    assert!(attempts[1] <= 100);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn circuit_breaker_opens_probes_and_closes() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(10));
+    let fail = || async { Err::<(), _>("down") };
+    let succeed = || async { Ok::<_, &str>(()) };
+
+    assert_eq!(breaker.call(fail).await, Err(CircuitError::Failed("down")));
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert_eq!(breaker.call(fail).await, Err(CircuitError::Failed("down")));
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    let mut ran = false;
+    let rejected = breaker
+        .call(|| {
+            ran = true;
+            succeed()
+        })
+        .await;
+    assert_eq!(rejected, Err(CircuitError::Open));
+    assert!(!ran);
+
+    // A failed probe opens the breaker for another timeout.
+    time::advance(Duration::from_secs(10));
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+    assert_eq!(breaker.call(fail).await, Err(CircuitError::Failed("down")));
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    time::advance(Duration::from_secs(10));
+    assert_eq!(breaker.call(succeed).await, Ok(()));
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn half_open_breaker_admits_a_single_probe() {
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(1));
+    let _ = breaker.call(|| async { Err::<(), _>(()) }).await;
+    time::advance(Duration::from_secs(1));
+
+    let probe = breaker.call(|| async {
+        sleep(Duration::from_secs(5)).await;
+        Ok::<_, ()>("probe")
+    });
+    let other = async {
+        sleep(Duration::from_millis(1)).await;
+        breaker.call(|| async { Ok::<_, ()>("other") }).await
+    };
+    let (probe, other) = futures::join!(probe, other);
+    assert_eq!(probe, Ok("probe"));
+    assert_eq!(other, Err(CircuitError::Open));
+
+    // A probe that is dropped lets the next call probe instead.
+    let _ = breaker.call(|| async { Err::<(), _>(()) }).await;
+    time::advance(Duration::from_secs(1));
+    mini_tokio::select! {
+        _ = breaker.call(pending::<Result<(), ()>>) => unreachable!(),
+        _ = sleep(Duration::from_secs(1)) => {}
+    }
+    assert_eq!(breaker.call(|| async { Ok::<_, ()>(()) }).await, Ok(()));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn dropped_probe_keeps_the_state_another_call_settled() {
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(1));
+    // Admitted while closed, it closes the breaker again while the probe
+    // below is in flight.
+    let slow = breaker.call(|| async {
+        sleep(Duration::from_secs(3)).await;
+        Ok::<_, ()>(())
+    });
+    let probe = async {
+        let _ = breaker.call(|| async { Err::<(), _>(()) }).await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+        sleep(Duration::from_secs(1)).await;
+        mini_tokio::select! {
+            _ = breaker.call(pending::<Result<(), ()>>) => unreachable!(),
+            _ = sleep(Duration::from_secs(3)) => {}
+        }
+    };
+    let (slow, ()) = futures::join!(slow, probe);
+    assert_eq!(slow, Ok(()));
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn hedge_starts_a_second_attempt_only_when_the_first_is_slow() {
+    let start = Instant::now();
+    let mut started = 0;
+    let fast = resilience::hedge(Duration::from_millis(100), || {
+        started += 1;
+        sleep(Duration::from_millis(50))
+    })
+    .await;
+    assert_eq!(fast, ());
+    assert_eq!(started, 1);
+    assert_eq!(start.elapsed(), Duration::from_millis(50));
+
+    // The first attempt still wins if it finishes before the second.
+    let start = Instant::now();
+    let latencies = RefCell::new(vec![300, 500]);
+    let winner = resilience::hedge(Duration::from_millis(100), || {
+        let latency = latencies.borrow_mut().remove(0);
+        async move {
+            sleep(Duration::from_millis(latency)).await;
+            latency
+        }
+    })
+    .await;
+    assert_eq!(winner, 300);
+    assert_eq!(start.elapsed(), Duration::from_millis(300));
+}
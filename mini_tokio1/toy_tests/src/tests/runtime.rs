@@ -0,0 +1,211 @@
+use futures::channel::oneshot;
+use mini_tokio::{
+    executor::Builder,
+    net::UdpSocket,
+    spawn,
+    time::{self, sleep, sleep_until, Instant},
+};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+#[mini_tokio::test]
+async fn test_attribute_runs_body() {
+    let handle = spawn(async { 21 });
+    assert_eq!(handle.await.unwrap() * 2, 42);
+}
+
+#[mini_tokio::test]
+async fn test_attribute_can_return_result() -> Result<(), std::num::ParseIntError> {
+    let n: i32 = "42".parse()?;
+    assert_eq!(n, 42);
+    Ok(())
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn paused_clock_jumps_to_next_timer() {
+    let real_start = std::time::Instant::now();
+    let start = Instant::now();
+
+    sleep(Duration::from_secs(3600)).await;
+
+    assert_eq!(start.elapsed(), Duration::from_secs(3600));
+    assert!(real_start.elapsed() < Duration::from_secs(5));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn paused_timers_fire_in_deadline_order() {
+    let start = Instant::now();
+    let first = spawn(async move {
+        sleep_until(start + Duration::from_millis(30)).await;
+        Instant::now()
+    });
+    let second = spawn(async move {
+        sleep_until(start + Duration::from_millis(10)).await;
+        Instant::now()
+    });
+
+    assert_eq!(second.await.unwrap() - start, Duration::from_millis(10));
+    assert_eq!(first.await.unwrap() - start, Duration::from_millis(30));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn advance_moves_paused_clock() {
+    let start = Instant::now();
+    time::advance(Duration::from_secs(5));
+    assert_eq!(start.elapsed(), Duration::from_secs(5));
+
+    time::resume();
+    sleep(Duration::from_millis(10)).await;
+    assert!(start.elapsed() >= Duration::from_millis(5010));
+}
+
+#[mini_tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn multi_thread_runs_tasks_on_workers() {
+    let main_thread = thread::current().id();
+    let handles: Vec<_> = (0..8)
+        .map(|_| spawn(async { thread::current().id() }))
+        .collect();
+
+    for handle in handles {
+        assert_ne!(handle.await.unwrap(), main_thread);
+    }
+}
+
+#[test]
+fn multi_thread_tasks_wait_on_timers() {
+    static DONE: AtomicUsize = AtomicUsize::new(0);
+    let executor = Builder::new_multi_thread()
+        .worker_threads(3)
+        .build()
+        .unwrap();
+
+    executor.block_on(async {
+        let handles: Vec<_> = (0..6)
+            .map(|i| {
+                spawn(async move {
+                    sleep(Duration::from_millis(5 * i)).await;
+                    DONE.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+
+    assert_eq!(DONE.load(Ordering::SeqCst), 6);
+}
+
+#[test]
+fn start_paused_requires_current_thread() {
+    let err = Builder::new_multi_thread()
+        .start_paused(true)
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// CPU time used by the calling thread
+fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `ts` is a valid timespec to write to.
+    unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[test]
+fn timerfd_executor_blocks_while_idle() {
+    let executor = Builder::new_current_thread().timerfd(true).build().unwrap();
+    let cpu = thread_cpu_time();
+    let start = Instant::now();
+    executor.block_on(sleep(Duration::from_millis(200)));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    // Spinning would take about as much CPU time as the sleep.
+    assert!(thread_cpu_time() - cpu < Duration::from_millis(100));
+}
+
+#[test]
+fn timerfd_sleeps_are_shorter_than_a_millisecond() {
+    let executor = Builder::new_current_thread().timerfd(true).build().unwrap();
+    executor.block_on(async {
+        for _ in 0..10 {
+            let start = Instant::now();
+            sleep(Duration::from_micros(200)).await;
+            let elapsed = start.elapsed();
+            assert!(elapsed >= Duration::from_micros(200));
+            assert!(elapsed < Duration::from_millis(20), "slept {elapsed:?}");
+        }
+    });
+}
+
+#[test]
+fn timerfd_executor_still_sees_other_threads() {
+    let executor = Builder::new_current_thread().timerfd(true).build().unwrap();
+    let value = executor.block_on(async {
+        mini_tokio::spawn_blocking(|| {
+            thread::sleep(Duration::from_millis(10));
+            7
+        })
+        .await
+        .unwrap()
+    });
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn idle_executor_sleeps_until_the_next_timer() {
+    let executor = Builder::new_current_thread().build().unwrap();
+    let cpu = thread_cpu_time();
+    let start = Instant::now();
+    executor.block_on(sleep(Duration::from_millis(200)));
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(thread_cpu_time() - cpu < Duration::from_millis(100));
+}
+
+/// Completes `rx` from a std thread after `delay`, checking that `block_on`
+/// slept meanwhile and woke up promptly
+fn wake_from_std_thread(executor: &mini_tokio::Executor, delay: Duration) {
+    let (tx, rx) = oneshot::channel();
+    let cpu = thread_cpu_time();
+    let start = Instant::now();
+    let sender = thread::spawn(move || {
+        thread::sleep(delay);
+        tx.send(Instant::now()).unwrap();
+    });
+    let sent = executor.block_on(async { spawn(rx).await.unwrap().unwrap() });
+    sender.join().unwrap();
+
+    assert!(start.elapsed() >= delay);
+    assert!(sent.elapsed() < Duration::from_millis(50));
+    assert!(thread_cpu_time() - cpu < delay / 2);
+}
+
+#[test]
+fn std_threads_wake_a_parked_executor() {
+    let executor = Builder::new_current_thread().build().unwrap();
+    wake_from_std_thread(&executor, Duration::from_millis(200));
+}
+
+#[test]
+fn std_threads_wake_an_executor_blocked_on_io() {
+    let executor = Builder::new_current_thread().build().unwrap();
+    // A registered socket makes the executor sleep in epoll_wait.
+    let _socket = executor.block_on(UdpSocket::bind("127.0.0.1:0")).unwrap();
+    wake_from_std_thread(&executor, Duration::from_millis(200));
+}
+
+#[test]
+fn multi_thread_executor_wakes_block_on_from_workers() {
+    let executor = Builder::new_multi_thread()
+        .worker_threads(2)
+        .build()
+        .unwrap();
+    wake_from_std_thread(&executor, Duration::from_millis(200));
+}
@@ -0,0 +1,159 @@
+use mini_tokio::{
+    executor::{
+        scheduler::{Edf, Fifo, LifoSlot, Random},
+        Builder, Executor, Scheduler,
+    },
+    task,
+    time::{sleep, Instant},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Spawns tasks 0 to `count` that record their number when polled, and
+/// returns the order they ran in
+fn run_order<S: Scheduler>(scheduler: S, count: usize) -> Vec<usize> {
+    let executor = Executor::with_scheduler(scheduler);
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let handles: Vec<_> = (0..count)
+        .map(|i| {
+            let order = Arc::clone(&order);
+            executor.spawn(async move { order.lock().unwrap().push(i) })
+        })
+        .collect();
+
+    executor.block_on(async {
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+    Arc::try_unwrap(order).unwrap().into_inner().unwrap()
+}
+
+#[test]
+fn fifo_polls_in_wake_order() {
+    assert_eq!(run_order(Fifo::new(), 5), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn lifo_slot_polls_latest_first_then_fifo() {
+    assert_eq!(run_order(LifoSlot::new(), 4), [3, 0, 1, 2]);
+}
+
+#[test]
+fn random_order_is_reproducible_from_seed() {
+    let order = run_order(Random::with_seed(7), 20);
+    assert_eq!(order, run_order(Random::with_seed(7), 20));
+
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    assert_ne!(order, sorted);
+}
+
+#[test]
+fn multi_thread_executor_accepts_scheduler() {
+    let executor = Builder::new_multi_thread()
+        .worker_threads(2)
+        .build_with_scheduler(LifoSlot::new())
+        .unwrap();
+
+    let sum = executor.block_on(async {
+        let handles: Vec<_> = (0..10)
+            .map(|i| mini_tokio::spawn(async move { i }))
+            .collect();
+        let mut sum = 0;
+        for handle in handles {
+            sum += handle.await.unwrap();
+        }
+        sum
+    });
+    assert_eq!(sum, 45);
+}
+
+#[test]
+fn edf_polls_earliest_deadline_first() {
+    let executor = Executor::with_scheduler(Edf::new());
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    executor.block_on(async {
+        let now = Instant::now();
+        let deadlines = [
+            ("none", None),
+            ("30", Some(30)),
+            ("10", Some(10)),
+            ("20", Some(20)),
+        ];
+        let handles: Vec<_> = deadlines
+            .into_iter()
+            .map(|(label, deadline)| {
+                let order = Arc::clone(&order);
+                let mut builder = task::Builder::new();
+                if let Some(ms) = deadline {
+                    builder.deadline(now + Duration::from_millis(ms));
+                }
+                builder.spawn(async move { order.lock().unwrap().push(label) })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+
+    assert_eq!(*order.lock().unwrap(), ["10", "20", "30", "none"]);
+}
+
+#[test]
+fn deadline_misses_are_reported_as_they_happen() {
+    let missed = Arc::new(Mutex::new(Vec::new()));
+    let executor = {
+        let missed = Arc::clone(&missed);
+        Builder::new_current_thread()
+            .start_paused(true)
+            .on_deadline_miss(move |miss| {
+                missed.lock().unwrap().push(miss.name().map(String::from));
+            })
+            .build_with_scheduler(Edf::new())
+            .unwrap()
+    };
+
+    executor.block_on(async {
+        let now = Instant::now();
+        let late = task::Builder::new()
+            .name("late")
+            .deadline(now + Duration::from_millis(10))
+            .spawn(sleep(Duration::from_millis(50)));
+        let on_time = mini_tokio::spawn_with_deadline(
+            now + Duration::from_millis(100),
+            sleep(Duration::from_millis(5)),
+        );
+
+        // Reported once the deadline passes, before the late task completes.
+        sleep(Duration::from_millis(20)).await;
+        assert_eq!(*missed.lock().unwrap(), [Some("late".to_string())]);
+
+        late.await.unwrap();
+        on_time.await.unwrap();
+    });
+
+    assert_eq!(executor.metrics().deadline_misses(), 1);
+    assert_eq!(missed.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn tasks_completed_in_time_are_not_reported() {
+    let executor = Builder::new_current_thread()
+        .start_paused(true)
+        .build()
+        .unwrap();
+
+    executor.block_on(async {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let handle = executor.spawn_with_deadline(deadline, async { 1 });
+        assert_eq!(handle.await.unwrap(), 1);
+        sleep(Duration::from_secs(120)).await;
+    });
+
+    assert_eq!(executor.metrics().deadline_misses(), 0);
+}
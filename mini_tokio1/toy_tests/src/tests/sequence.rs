@@ -0,0 +1,133 @@
+use futures::channel::oneshot;
+use mini_tokio::{
+    executor::{
+        sequence::{Participant, SequenceTrace, StepKind},
+        Builder, Executor,
+    },
+    net::UnixDatagram,
+    task,
+};
+use std::{thread, time::Duration};
+
+fn trace<F: std::future::Future>(future: F) -> SequenceTrace {
+    let executor = Builder::new_current_thread()
+        .trace_sequence(true)
+        .build()
+        .unwrap();
+    executor.block_on(future);
+    executor.sequence_trace().unwrap()
+}
+
+/// Lists the steps to or from `participant` as `(from, to, kind)`
+fn steps_of(
+    trace: &SequenceTrace,
+    participant: &Participant,
+) -> Vec<(Participant, Participant, StepKind)> {
+    trace
+        .steps()
+        .iter()
+        .filter(|step| step.from() == participant || step.to() == participant)
+        .map(|step| (step.from().clone(), step.to().clone(), step.kind()))
+        .collect()
+}
+
+#[test]
+fn sleeping_task_registers_with_timer_and_is_woken() {
+    let trace = trace(async {
+        task::Builder::new()
+            .name("sleeper")
+            .spawn(mini_tokio::delay(1))
+            .await
+            .unwrap();
+    });
+
+    use Participant::{Executor, Main, Task, Timer};
+    use StepKind::*;
+    assert_eq!(
+        steps_of(&trace, &Task(0)),
+        [
+            (Main, Task(0), Spawn),
+            (Executor, Task(0), Poll),
+            (Task(0), Timer, Register),
+            (Task(0), Executor, Pending),
+            (Timer, Task(0), Wake),
+            (Executor, Task(0), Poll),
+            (Task(0), Main, Wake),
+            (Task(0), Executor, Ready),
+        ]
+    );
+    assert_eq!(trace.task_name(0), Some("sleeper"));
+    assert_eq!(trace.participants(), [Executor, Main, Task(0), Timer],);
+}
+
+#[test]
+fn io_and_thread_wakeups_are_attributed() {
+    let trace = trace(async {
+        let (a, b) = UnixDatagram::pair().unwrap();
+        let reader = mini_tokio::spawn(async move {
+            let mut buf = [0; 4];
+            b.recv(&mut buf).await.unwrap()
+        });
+        let (tx, rx) = oneshot::channel();
+        thread::Builder::new()
+            .name("sender".into())
+            .spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                tx.send(()).unwrap();
+            })
+            .unwrap();
+        rx.await.unwrap();
+        a.send(b"ping").await.unwrap();
+        assert_eq!(reader.await.unwrap(), 4);
+    });
+
+    let steps = trace.steps();
+    let has = |from: Participant, to: Participant, kind: StepKind| {
+        steps
+            .iter()
+            .any(|step| *step.from() == from && *step.to() == to && step.kind() == kind)
+    };
+    assert!(has(
+        Participant::Task(0),
+        Participant::Io,
+        StepKind::Register
+    ));
+    assert!(has(Participant::Io, Participant::Task(0), StepKind::Wake));
+    assert!(has(
+        Participant::Thread("sender".into()),
+        Participant::Main,
+        StepKind::Wake
+    ));
+}
+
+#[test]
+fn svg_has_a_lifeline_per_participant() {
+    let trace = trace(async {
+        task::Builder::new()
+            .name("<sleeper>")
+            .spawn(mini_tokio::delay(1))
+            .await
+            .unwrap();
+    });
+    let svg = trace.to_svg();
+
+    assert!(svg.starts_with("<svg "), "{svg}");
+    assert!(svg.trim_end().ends_with("</svg>"));
+    for heading in [
+        "executor",
+        "block_on",
+        "task 0 &quot;&lt;sleeper&gt;&quot;",
+        "timer",
+    ] {
+        assert!(svg.contains(&format!(">{heading}</text>")), "{heading}");
+    }
+    assert_eq!(svg.matches("marker-end").count(), trace.steps().len());
+    assert!(svg.contains(">register waker</text>"));
+}
+
+#[test]
+fn sequence_trace_is_off_by_default() {
+    let executor = Executor::new();
+    executor.block_on(async {});
+    assert!(executor.sequence_trace().is_none());
+}
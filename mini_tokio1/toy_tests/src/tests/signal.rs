@@ -0,0 +1,78 @@
+use futures::StreamExt;
+use mini_tokio::{
+    signal::{
+        self,
+        unix::{signal, SignalKind},
+    },
+    Executor,
+};
+use std::{io, thread, time::Duration};
+
+/// Sends `kind` to this process from another thread, after a short delay so
+/// that the caller gets to wait for it first
+fn send_later(kind: SignalKind) {
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        // SAFETY: `kill` has no memory safety preconditions.
+        assert_eq!(
+            unsafe { libc::kill(libc::getpid(), kind.as_raw_value()) },
+            0
+        );
+    });
+}
+
+#[test]
+fn signal_wakes_listeners_on_every_executor() {
+    let kind = SignalKind::user_defined1();
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    let other = thread::spawn(move || {
+        Executor::new().block_on(async move {
+            let mut listener = signal(kind).unwrap();
+            ready_tx.send(()).unwrap();
+            listener.recv().await
+        })
+    });
+    ready_rx.recv().unwrap();
+
+    let received = Executor::new().block_on(async {
+        let mut listener = signal(kind).unwrap();
+        send_later(kind);
+        listener.recv().await
+    });
+    assert_eq!(received, Some(()));
+    assert_eq!(other.join().unwrap(), Some(()));
+}
+
+#[test]
+fn signal_stream_yields_once_per_signal() {
+    let kind = SignalKind::user_defined2();
+    Executor::new().block_on(async {
+        let mut listener = signal(kind).unwrap();
+        for _ in 0..3 {
+            send_later(kind);
+            assert_eq!(listener.next().await, Some(()));
+        }
+    });
+}
+
+#[test]
+fn ctrl_c_completes_on_sigint() {
+    Executor::new().block_on(async {
+        let ctrl_c = mini_tokio::spawn(signal::ctrl_c());
+        // Let the task install its listener before the signal is sent.
+        mini_tokio::delay(5).await;
+        send_later(SignalKind::interrupt());
+        ctrl_c.await.unwrap().unwrap();
+    });
+}
+
+#[test]
+fn uncatchable_signals_are_rejected() {
+    Executor::new().block_on(async {
+        let err = signal(SignalKind::from_raw(libc::SIGKILL)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = signal(SignalKind::from_raw(1000)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    });
+    assert!(signal(SignalKind::hangup()).is_err());
+}
@@ -0,0 +1,139 @@
+use mini_tokio::{
+    stream::{self, IntervalStream, ReceiverStream, StreamExt, UnboundedReceiverStream},
+    sync::mpsc,
+    time::{self, sleep, Instant},
+};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[mini_tokio::test(start_paused = true)]
+async fn interval_stream_ticks_every_period() {
+    let start = Instant::now();
+    let ticks: Vec<_> = IntervalStream::new(time::interval(Duration::from_secs(2)))
+        .take(3)
+        .map(|tick| tick - start)
+        .collect()
+        .await;
+    assert_eq!(
+        ticks,
+        [
+            Duration::ZERO,
+            Duration::from_secs(2),
+            Duration::from_secs(4)
+        ]
+    );
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn interval_skip_drops_missed_ticks() {
+    let start = Instant::now();
+    let mut interval = time::interval(Duration::from_secs(1));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+    interval.tick().await;
+    time::advance(Duration::from_millis(3500));
+
+    assert_eq!(interval.tick().await - start, Duration::from_secs(1));
+    assert_eq!(interval.tick().await - start, Duration::from_secs(4));
+}
+
+#[mini_tokio::test]
+async fn receiver_streams_end_when_senders_drop() {
+    let (tx, rx) = mpsc::channel(1);
+    mini_tokio::spawn(async move {
+        for i in 0..3 {
+            tx.send(i).await.unwrap();
+        }
+    });
+    let values: Vec<_> = ReceiverStream::new(rx).collect().await;
+    assert_eq!(values, [0, 1, 2]);
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    tx.send("a").unwrap();
+    let mut rx = UnboundedReceiverStream::new(rx);
+    rx.close();
+    assert_eq!(rx.collect::<Vec<_>>().await, ["a"]);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn for_each_concurrent_respects_the_limit() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+
+    stream::for_each_concurrent(futures::stream::iter(0..9), 3, |_| {
+        let running = Arc::clone(&running);
+        let peak = Arc::clone(&peak);
+        async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            sleep(Duration::from_secs(1)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn for_each_concurrent_without_a_limit_runs_everything_at_once() {
+    let start = Instant::now();
+    let delays = futures::stream::iter([3, 1, 2]);
+    stream::for_each_concurrent(delays, None, |secs| sleep(Duration::from_secs(secs)))
+        .await
+        .unwrap();
+    assert_eq!(start.elapsed(), Duration::from_secs(3));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn stream_macro_yields_items_as_they_are_polled() {
+    let start = Instant::now();
+    let ticks = mini_tokio::stream! {
+        for i in 1..=3u64 {
+            sleep(Duration::from_secs(i)).await;
+            yield start.elapsed();
+        }
+    };
+    let ticks: Vec<_> = ticks.collect().await;
+    assert_eq!(ticks, [1, 3, 6].map(Duration::from_secs),);
+}
+
+#[mini_tokio::test]
+async fn stream_macro_ignores_yield_in_nested_items() {
+    let pairs = mini_tokio::stream! {
+        let double = |x: i32| x * 2;
+        for i in 0..2 {
+            yield (i, double(i));
+        }
+    };
+    let pairs: Vec<_> = pairs.collect().await;
+    assert_eq!(pairs, [(0, 0), (1, 2)]);
+}
+
+#[mini_tokio::test]
+async fn try_stream_ends_with_the_first_error() {
+    fn parse(input: &'static [&'static str]) -> impl futures::Stream<Item = io::Result<i32>> {
+        mini_tokio::try_stream! {
+            for s in input {
+                let n: i32 = s.parse().map_err(|_| io::Error::other(*s))?;
+                yield n;
+            }
+        }
+    }
+
+    let ok: Vec<_> = parse(&["1", "2"]).map(Result::unwrap).collect().await;
+    assert_eq!(ok, [1, 2]);
+
+    let items: Vec<_> = parse(&["1", "x", "3"]).collect().await;
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].as_ref().unwrap(), &1);
+    assert_eq!(items[1].as_ref().unwrap_err().to_string(), "x");
+}
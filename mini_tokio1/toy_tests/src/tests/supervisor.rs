@@ -0,0 +1,176 @@
+use mini_tokio::{
+    supervisor::{Strategy, Supervisor},
+    time::{sleep, Instant},
+};
+use std::{
+    future::pending,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// Adds a child that counts its starts and then runs forever
+fn add_steady(supervisor: &mut Supervisor, name: &str) -> Arc<AtomicUsize> {
+    let starts = Arc::new(AtomicUsize::new(0));
+    supervisor.child(name, {
+        let starts = Arc::clone(&starts);
+        move || {
+            starts.fetch_add(1, Ordering::SeqCst);
+            pending::<Result<(), String>>()
+        }
+    });
+    starts
+}
+
+/// Adds a child that fails once, a second after it first starts, and
+/// then runs forever
+fn add_failing_once(supervisor: &mut Supervisor, name: &str) {
+    let started = Arc::new(AtomicUsize::new(0));
+    supervisor.child(name, move || {
+        let first = started.fetch_add(1, Ordering::SeqCst) == 0;
+        async move {
+            if first {
+                sleep(Duration::from_secs(1)).await;
+                return Err("failed");
+            }
+            pending().await
+        }
+    });
+}
+
+/// Runs `supervisor` for a while, returning whether it was still running
+async fn run_for(supervisor: &Supervisor, duration: Duration) -> bool {
+    mini_tokio::select! {
+        _ = supervisor.run() => false,
+        _ = sleep(duration) => true,
+    }
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn one_for_one_restarts_with_exponential_backoff() {
+    let start = Instant::now();
+    let starts = Arc::new(Mutex::new(Vec::new()));
+    let mut supervisor = Supervisor::new(Strategy::OneForOne);
+    supervisor
+        .restart_intensity(5, Duration::from_secs(60))
+        .backoff(Duration::from_secs(1), Duration::from_secs(3));
+    supervisor.child("flaky", {
+        let starts = Arc::clone(&starts);
+        move || {
+            let mut starts = starts.lock().unwrap();
+            starts.push(start.elapsed().as_secs());
+            let attempt = starts.len();
+            async move {
+                if attempt <= 4 {
+                    return Err(format!("attempt {attempt}"));
+                }
+                Ok(())
+            }
+        }
+    });
+    let steady = add_steady(&mut supervisor, "steady");
+
+    assert!(run_for(&supervisor, Duration::from_secs(60)).await);
+    assert_eq!(*starts.lock().unwrap(), [0, 1, 3, 6, 9]);
+    assert_eq!(steady.load(Ordering::SeqCst), 1);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn one_for_all_restarts_every_running_child() {
+    let mut supervisor = Supervisor::new(Strategy::OneForAll);
+    let first = add_steady(&mut supervisor, "first");
+    add_failing_once(&mut supervisor, "failing");
+    let last = add_steady(&mut supervisor, "last");
+
+    assert!(run_for(&supervisor, Duration::from_secs(10)).await);
+    assert_eq!(first.load(Ordering::SeqCst), 2);
+    assert_eq!(last.load(Ordering::SeqCst), 2);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn rest_for_one_restarts_the_children_after_the_failed_one() {
+    let mut supervisor = Supervisor::new(Strategy::RestForOne);
+    let first = add_steady(&mut supervisor, "first");
+    add_failing_once(&mut supervisor, "failing");
+    let last = add_steady(&mut supervisor, "last");
+
+    assert!(run_for(&supervisor, Duration::from_secs(10)).await);
+    assert_eq!(first.load(Ordering::SeqCst), 1);
+    assert_eq!(last.load(Ordering::SeqCst), 2);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn panicking_children_are_restarted() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let mut supervisor = Supervisor::new(Strategy::OneForOne);
+    supervisor.child("panics", {
+        let attempts = Arc::clone(&attempts);
+        move || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                assert!(attempt > 0, "first attempt panics");
+                Ok::<(), String>(())
+            }
+        }
+    });
+
+    supervisor.run().await.unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn too_many_failures_stop_the_supervisor_and_its_children() {
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let mut supervisor = Supervisor::new(Strategy::OneForOne);
+    supervisor.restart_intensity(2, Duration::from_secs(10));
+    supervisor.child("broken", || async { Err("no database") });
+    supervisor.child("steady", {
+        let stopped = Arc::clone(&stopped);
+        move || {
+            let guard = DropCounter(Arc::clone(&stopped));
+            async move {
+                let _guard = guard;
+                pending::<Result<(), String>>().await
+            }
+        }
+    });
+
+    let err = supervisor.run().await.unwrap_err();
+    assert_eq!(err.child(), "broken");
+    assert_eq!(err.reason(), "no database");
+    sleep(Duration::from_millis(1)).await;
+    assert_eq!(stopped.load(Ordering::SeqCst), 1);
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn supervisors_can_supervise_supervisors() {
+    let mut inner = Supervisor::new(Strategy::OneForOne);
+    inner.restart_intensity(0, Duration::from_secs(10));
+    add_failing_once(&mut inner, "leaf");
+
+    let mut outer = Supervisor::new(Strategy::OneForOne);
+    let inner_starts = Arc::new(AtomicUsize::new(0));
+    outer.child("inner", {
+        let inner_starts = Arc::clone(&inner_starts);
+        move || {
+            inner_starts.fetch_add(1, Ordering::SeqCst);
+            inner.run()
+        }
+    });
+
+    // The leaf fails once and, with no restarts allowed, takes the inner
+    // supervisor down with it. The outer one restarts it, and the leaf
+    // then keeps running.
+    assert!(run_for(&outer, Duration::from_secs(10)).await);
+    assert_eq!(inner_starts.load(Ordering::SeqCst), 2);
+}
+
+struct DropCounter(Arc<AtomicUsize>);
+
+impl Drop for DropCounter {
+    fn drop(&mut self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
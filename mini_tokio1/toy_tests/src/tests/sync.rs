@@ -0,0 +1,205 @@
+use mini_tokio::{
+    spawn,
+    sync::{
+        mpsc::{self, SendError, TryRecvError, TrySendError},
+        oneshot, CancellationToken, Semaphore, TryAcquireError,
+    },
+    time::{sleep, Instant},
+};
+use std::{sync::Arc, time::Duration};
+
+#[mini_tokio::test]
+async fn mpsc_delivers_values_in_order() {
+    let (tx, mut rx) = mpsc::channel(2);
+    let producer = spawn(async move {
+        for i in 0..10 {
+            tx.send(i).await.unwrap();
+        }
+    });
+
+    let mut received = Vec::new();
+    while let Some(i) = rx.recv().await {
+        received.push(i);
+    }
+    producer.await.unwrap();
+    assert_eq!(received, (0..10).collect::<Vec<_>>());
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn mpsc_senders_wait_while_full() {
+    let (tx, mut rx) = mpsc::channel(1);
+    tx.send(1).await.unwrap();
+    assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+
+    let start = Instant::now();
+    let producer = spawn(async move {
+        tx.send(2).await.unwrap();
+        Instant::now()
+    });
+    sleep(Duration::from_secs(1)).await;
+    assert_eq!(rx.recv().await, Some(1));
+
+    assert_eq!(producer.await.unwrap() - start, Duration::from_secs(1));
+    assert_eq!(rx.recv().await, Some(2));
+    assert_eq!(rx.recv().await, None);
+}
+
+#[mini_tokio::test]
+async fn mpsc_close_keeps_queued_values() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tx.send("a").unwrap();
+    rx.close();
+
+    assert!(tx.is_closed());
+    assert_eq!(tx.send("b"), Err(SendError("b")));
+    assert_eq!(rx.try_recv(), Ok("a"));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[mini_tokio::test]
+async fn mpsc_dropping_the_receiver_fails_waiting_senders() {
+    let (tx, rx) = mpsc::channel(1);
+    tx.send(1).await.unwrap();
+    let blocked = spawn({
+        let tx = tx.clone();
+        async move { tx.send(2).await }
+    });
+    mini_tokio::delay(1).await;
+    drop(rx);
+
+    assert_eq!(blocked.await.unwrap(), Err(SendError(2)));
+}
+
+#[mini_tokio::test]
+async fn cancelling_a_token_wakes_every_waiting_task() {
+    let token = CancellationToken::new();
+    let waiters: Vec<_> = (0..3)
+        .map(|_| {
+            let token = token.clone();
+            spawn(async move { token.cancelled().await })
+        })
+        .collect();
+    mini_tokio::delay(1).await;
+    assert!(!token.is_cancelled());
+
+    token.cancel();
+    assert!(token.is_cancelled());
+    for waiter in waiters {
+        waiter.await.unwrap();
+    }
+}
+
+#[mini_tokio::test]
+async fn cancelling_a_parent_cascades_to_its_children() {
+    let root = CancellationToken::new();
+    let child = root.child_token();
+    let grandchild = child.child_token();
+    let sibling = root.child_token();
+
+    child.cancel();
+    assert!(grandchild.is_cancelled());
+    assert!(!root.is_cancelled());
+    assert!(!sibling.is_cancelled());
+
+    let waiting = spawn({
+        let sibling = sibling.clone();
+        async move { sibling.cancelled().await }
+    });
+    root.cancel();
+    waiting.await.unwrap();
+    assert!(sibling.is_cancelled());
+    assert!(root.child_token().is_cancelled());
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn run_until_cancelled_stops_the_future() {
+    let token = CancellationToken::new();
+    assert_eq!(token.run_until_cancelled(async { 7 }).await, Some(7));
+
+    let start = Instant::now();
+    spawn({
+        let token = token.clone();
+        async move {
+            sleep(Duration::from_secs(1)).await;
+            token.cancel();
+        }
+    });
+    let result = token
+        .run_until_cancelled(sleep(Duration::from_secs(60)))
+        .await;
+    assert_eq!(result, None);
+    assert_eq!(start.elapsed(), Duration::from_secs(1));
+}
+
+#[mini_tokio::test(start_paused = true)]
+async fn semaphore_serves_waiters_in_order() {
+    let semaphore = Arc::new(Semaphore::new(2));
+    let held = semaphore.acquire_many(2).await.unwrap();
+    assert_eq!(
+        semaphore.try_acquire().err(),
+        Some(TryAcquireError::NoPermits)
+    );
+
+    let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let waiters: Vec<_> = [("many", 2), ("one", 1)]
+        .into_iter()
+        .map(|(label, permits)| {
+            let semaphore = Arc::clone(&semaphore);
+            let order = Arc::clone(&order);
+            spawn(async move {
+                let _permit = semaphore.acquire_many(permits).await.unwrap();
+                order.lock().unwrap().push(label);
+                sleep(Duration::from_secs(1)).await;
+            })
+        })
+        .collect();
+    sleep(Duration::from_millis(1)).await;
+
+    // A single free permit must not let "one" overtake "many".
+    held.forget();
+    semaphore.add_permits(1);
+    sleep(Duration::from_millis(1)).await;
+    assert!(order.lock().unwrap().is_empty());
+
+    semaphore.add_permits(1);
+    for waiter in waiters {
+        waiter.await.unwrap();
+    }
+    assert_eq!(*order.lock().unwrap(), ["many", "one"]);
+    assert_eq!(semaphore.available_permits(), 2);
+}
+
+#[mini_tokio::test]
+async fn closing_a_semaphore_fails_waiters() {
+    let semaphore = Arc::new(Semaphore::new(1));
+    let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
+    let waiter = spawn({
+        let semaphore = Arc::clone(&semaphore);
+        async move { semaphore.acquire().await.map(drop) }
+    });
+    mini_tokio::delay(1).await;
+
+    semaphore.close();
+    assert!(waiter.await.unwrap().is_err());
+    assert_eq!(semaphore.try_acquire().err(), Some(TryAcquireError::Closed));
+    drop(permit);
+    assert_eq!(semaphore.available_permits(), 1);
+}
+
+#[mini_tokio::test]
+async fn oneshot_delivers_a_value_or_reports_a_dropped_sender() {
+    let (tx, rx) = oneshot::channel();
+    spawn(async move { tx.send("done").unwrap() });
+    assert_eq!(rx.await, Ok("done"));
+
+    let (tx, mut rx) = oneshot::channel::<()>();
+    assert_eq!(rx.try_recv(), Err(oneshot::TryRecvError::Empty));
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(oneshot::TryRecvError::Closed));
+    assert_eq!(rx.await, Err(oneshot::RecvError));
+
+    let (tx, rx) = oneshot::channel();
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1), Err(1));
+}
@@ -0,0 +1,117 @@
+use futures::channel::oneshot;
+use mini_tokio::{
+    delay,
+    executor::{Executor, TaskState},
+    spawn, spawn_with_priority,
+    task::{self, Priority},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Spawns a task of the given priority that records `label` when first polled
+fn spawn_recording(
+    order: &Arc<Mutex<Vec<&'static str>>>,
+    priority: Priority,
+    label: &'static str,
+) -> mini_tokio::JoinHandle<()> {
+    let order = Arc::clone(order);
+    spawn_with_priority(priority, async move {
+        order.lock().unwrap().push(label);
+    })
+}
+
+#[test]
+fn dump_lists_pending_tasks_with_names_and_locations() {
+    let executor = Executor::new();
+    let (_tx, rx) = oneshot::channel::<()>();
+
+    let line = line!() + 2;
+    executor.block_on(async {
+        task::Builder::new().name("acceptor").spawn(async move {
+            let _ = rx.await;
+        });
+        spawn(std::future::pending::<()>());
+        delay(20).await;
+    });
+
+    let dump = executor.dump();
+    let tasks = dump.tasks();
+    assert_eq!(tasks.len(), 2);
+
+    assert_eq!(tasks[0].name(), Some("acceptor"));
+    assert_eq!(tasks[0].location().line(), line);
+    assert_eq!(tasks[1].name(), None);
+    for task in tasks {
+        assert_eq!(task.state(), TaskState::Idle);
+        assert_eq!(task.polls(), 1);
+        assert!(
+            task.idle() >= Duration::from_millis(15),
+            "{:?}",
+            task.idle()
+        );
+        assert!(task.location().file().ends_with("task.rs"));
+    }
+
+    let printed = dump.to_string();
+    assert!(printed.starts_with("2 tasks\n"), "{printed}");
+    assert!(
+        printed.contains("task 0 \"acceptor\": idle for"),
+        "{printed}"
+    );
+}
+
+#[test]
+fn dump_omits_completed_tasks() {
+    let executor = Executor::new();
+
+    executor.block_on(async {
+        let done = task::Builder::new().name("short").spawn(async { 1 });
+        assert_eq!(done.await.unwrap(), 1);
+    });
+
+    assert!(executor.dump().tasks().is_empty());
+    assert_eq!(executor.dump().to_string(), "0 tasks\n");
+}
+
+#[mini_tokio::test]
+async fn higher_priorities_are_polled_first() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let handles = [
+        spawn_recording(&order, Priority::Background, "background"),
+        spawn_recording(&order, Priority::Normal, "normal"),
+        spawn_recording(&order, Priority::High, "high"),
+    ];
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    assert_eq!(*order.lock().unwrap(), ["high", "normal", "background"]);
+}
+
+#[mini_tokio::test]
+async fn background_tasks_age_past_a_stream_of_urgent_ones() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let mut handles = vec![spawn_recording(&order, Priority::Background, "background")];
+    for _ in 0..50 {
+        handles.push(spawn_recording(&order, Priority::High, "high"));
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let order = order.lock().unwrap();
+    let position = order.iter().position(|&label| label == "background");
+    assert!(matches!(position, Some(1..=20)), "{position:?}");
+}
+
+#[test]
+fn dump_shows_priority() {
+    let executor = Executor::new();
+    executor.spawn_with_priority(Priority::Background, std::future::pending::<()>());
+
+    let dump = executor.dump();
+    assert_eq!(dump.tasks()[0].priority(), Priority::Background);
+    assert!(dump.to_string().contains("task 0 (background): "), "{dump}");
+}
@@ -0,0 +1,86 @@
+use mini_tokio::{
+    delay,
+    executor::{Builder, Executor, LongPoll},
+    task,
+};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// Builds an executor whose watchdog collects its reports. Reports may
+/// arrive after `block_on` returns, so they should be read after dropping
+/// the executor, which waits for the watchdog to stop.
+fn watched_executor(backtrace: bool) -> (Executor, Arc<Mutex<Vec<LongPoll>>>) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let executor = {
+        let reports = Arc::clone(&reports);
+        Builder::new_current_thread()
+            .watchdog(Duration::from_millis(20))
+            .watchdog_backtrace(backtrace)
+            .on_long_poll(move |poll| reports.lock().unwrap().push(poll.clone()))
+            .build()
+            .unwrap()
+    };
+    (executor, reports)
+}
+
+#[test]
+fn watchdog_reports_blocking_poll() {
+    let (executor, reports) = watched_executor(false);
+
+    let line = line!() + 4;
+    executor.block_on(async {
+        task::Builder::new()
+            .name("blocker")
+            .spawn(async { thread::sleep(Duration::from_millis(150)) })
+            .await
+            .unwrap();
+    });
+    assert_eq!(executor.metrics().long_polls(), 1);
+    drop(executor);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1, "a poll is reported once");
+    let report = &reports[0];
+    assert_eq!(report.name(), Some("blocker"));
+    assert_eq!(report.location().line(), line);
+    assert!(report.elapsed() >= Duration::from_millis(20));
+    assert!(report.backtrace().is_none());
+    assert!(report.to_string().contains("\"blocker\""), "{report}");
+}
+
+#[test]
+fn watchdog_captures_backtrace_of_blocked_thread() {
+    let (executor, reports) = watched_executor(true);
+
+    executor.block_on(async {
+        mini_tokio::spawn(async { thread::sleep(Duration::from_millis(300)) })
+            .await
+            .unwrap();
+    });
+    drop(executor);
+
+    let reports = reports.lock().unwrap();
+    let backtrace = reports[0].backtrace().expect("no backtrace captured");
+    assert!(backtrace.contains("sleep"), "{backtrace}");
+}
+
+#[test]
+fn watchdog_ignores_short_polls() {
+    let (executor, reports) = watched_executor(false);
+
+    executor.block_on(async {
+        let handles: Vec<_> = (0..5)
+            .map(|_| mini_tokio::spawn(async { delay(30).await }))
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    });
+    assert_eq!(executor.metrics().long_polls(), 0);
+    drop(executor);
+
+    assert!(reports.lock().unwrap().is_empty());
+}